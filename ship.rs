@@ -24,1271 +24,11045 @@ use std::collections::VecDeque;
 use std::collections::HashMap;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
-const BULLET_SPEED: f64 = 1000.0; // m/s
+use config::*;
+use comms::*;
+use radar::*;
+use guidance::*;
+use ships::*;
+
+// stays out of config: the prelude re-exports the f64 consts and a root
+// item shadows that glob where a module one would make E ambiguous
 const E: f64 = f64::EPSILON;
-const STICKY_TARGET_TICKS: u32 = 1;
-const MISSILE_STICKY_TARGET_TICKS: u32 = 60;
-const MISSILE_TARGET_HEADING_DELAY: u32 = 30;
-const MISSILE_ACCELERATION_DELAY: u32 = 30;
-const CHANNEL_SANCHEZ: u8 = 7;
+
+//****************************************************
+// Debug HUD: status lines and draw layers
+//****************************************************
+
+// draw layers, DRAW_LAYERS picks what's compiled in and set_draw_layers can
+// narrow it further at runtime
+const LAYER_GATES: u32 = 1 << 0; // association gates and reacquire boxes
+const LAYER_LEADS: u32 = 1 << 1; // lead points, intercepts and guidance lines
+const LAYER_TRAILS: u32 = 1 << 2; // track history
+const LAYER_THREATS: u32 = 1 << 3; // targets and inferred shooters
+const LAYER_NAV: u32 = 1 << 4; // maneuvering, formation and boundary
+const DRAW_LAYERS: u32 = LAYER_GATES | LAYER_LEADS | LAYER_TRAILS | LAYER_THREATS | LAYER_NAV;
+
+// debug output is decided once per tick in begin_debug_tick, the radar can turn
+// drawing off for the rest of the tick when it's overloaded
+static DEBUG_TEXT_ACTIVE: AtomicBool = AtomicBool::new(DEBUG);
+static DEBUG_DRAW_ACTIVE: AtomicBool = AtomicBool::new(DEBUG);
+static ACTIVE_LAYERS: AtomicU32 = AtomicU32::new(DRAW_LAYERS);
+
+// set while sense() is mutating tracks, the picture is copied through read_track()
+// which debug asserts on it, so a bad ordering fails at the read instead of as
+// a BorrowMutError somewhere inside the radar
+static RADAR_UPDATING: AtomicBool = AtomicBool::new(false);
+
+// one status line per component, printed in this order every tick
+#[derive(Debug, Clone, Copy)]
+enum HudLine {
+    Radar,
+    Guidance,
+    Weapons,
+    Nav,
+    Profile,
+    Revisit,
+}
+
+const HUD_LINE_COUNT: usize = 6;
+
+thread_local! {
+    static HUD_LINES: RefCell<[Option<String>; HUD_LINE_COUNT]> = RefCell::new(Default::default());
+}
+
+fn begin_debug_tick() {
+    DEBUG_TEXT_ACTIVE.store(DEBUG, Ordering::Relaxed);
+    DEBUG_DRAW_ACTIVE.store(DEBUG, Ordering::Relaxed);
+    HUD_LINES.with(|lines| *lines.borrow_mut() = Default::default());
+}
+
+fn debug_text_active() -> bool {
+    DEBUG_TEXT_ACTIVE.load(Ordering::Relaxed)
+}
+
+fn debug_draw_active() -> bool {
+    DEBUG_DRAW_ACTIVE.load(Ordering::Relaxed)
+}
+
+fn layer_active(layer: u32) -> bool {
+    debug_draw_active() && ACTIVE_LAYERS.load(Ordering::Relaxed) & layer != 0
+}
+
+#[allow(dead_code)]
+fn set_draw_layers(layers: u32) {
+    ACTIVE_LAYERS.store(layers & DRAW_LAYERS, Ordering::Relaxed);
+}
+
+// a second report from the same component this tick is appended to the first
+fn hud_status(line: HudLine, status: String) {
+    HUD_LINES.with(|lines| {
+        let mut lines = lines.borrow_mut();
+        let entry = &mut lines[line as usize];
+        *entry = match entry.take() {
+            Some(previous) => Some(format!("{} | {}", previous, status)),
+            None => Some(status),
+        };
+    });
+}
+
+// prints whatever the components reported this tick, in HudLine order
+fn render_hud() {
+    if !debug_text_active() {
+        return;
+    }
+    HUD_LINES.with(|lines| {
+        for (k, status) in lines.borrow().iter().enumerate() {
+            if let Some(status) = status {
+                let name = match k {
+                    0 => "radar",
+                    1 => "guidance",
+                    2 => "weapons",
+                    3 => "nav",
+                    4 => "profile",
+                    _ => "revisit",
+                };
+                debug!("{:>8}: {}", name, status);
+            }
+        }
+    });
+}
+
+// debug! that skips the string formatting entirely when debug text is off
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if debug_text_active() {
+            debug!($($arg)*);
+        }
+    };
+}
+
+// sets a component's status line, formatting only when debug text is on
+macro_rules! hud {
+    ($line:expr, $($arg:tt)*) => {
+        if debug_text_active() {
+            hud_status($line, format!($($arg)*));
+        }
+    };
+}
+
+macro_rules! debug_text {
+    ($layer:expr, $($arg:tt)*) => {
+        if layer_active($layer) {
+            draw_text!($($arg)*);
+        }
+    };
+}
+
+fn debug_line(layer: u32, a: Vec2, b: Vec2, color: u32) {
+    if layer_active(layer) {
+        draw_line(a, b, color);
+    }
+}
+
+fn debug_square(layer: u32, center: Vec2, size: f64, color: u32) {
+    if layer_active(layer) {
+        draw_square(center, size, color);
+    }
+}
+
+fn debug_triangle(layer: u32, center: Vec2, size: f64, color: u32) {
+    if layer_active(layer) {
+        draw_triangle(center, size, color);
+    }
+}
 
 pub enum Ship {
     Fighter(Fighter),
     Missile(Missile),
     Frigate(Frigate),
+    Cruiser(Cruiser),
+    Fallback(SafeFallback),
 }
 
 // game loop router
 impl Ship {
+    // every class gets a ship, the ones without an implementation yet get the
+    // fallback since a panic here forfeits the whole scenario
     pub fn new() -> Ship {
         match class() {
-            Class::Fighter => Ship::Fighter(Fighter::new()),
-            Class::Missile => Ship::Missile(Missile::new()),
-            Class::Frigate => Ship::Frigate(Frigate::new()),
-            _ => { todo!() },//TODO: future stuff
+            Class::Fighter => Ship::Fighter(Fighter::new(Fighter::default_config())),
+            Class::Missile | Class::Torpedo => Ship::Missile(Missile::new(Missile::default_config(), OrdnanceProfile::for_class(class()))),
+            Class::Frigate => Ship::Frigate(Frigate::new(Frigate::default_config())),
+            Class::Cruiser => Ship::Cruiser(Cruiser::new(Cruiser::default_config())),
+            other => Ship::Fallback(SafeFallback::new(other, SafeFallback::default_config())),
         }
     }
     pub fn tick(&mut self) {
+        begin_debug_tick();
         match self {
             Ship::Fighter(fighter) => { fighter.tick() },
             Ship::Missile(missile) => { missile.tick() },
             Ship::Frigate(frigate) => { frigate.tick() },
+            Ship::Cruiser(cruiser) => { cruiser.tick() },
+            Ship::Fallback(fallback) => { fallback.tick() },
         }
+        render_hud();
     }
 }
 
-pub struct Frigate {
-    target: Option<Rc<RefCell<RadarTrack>>>,
-    radar: Radar,
-    radio: Radio,
-}
+// tuning numbers: the shared constants and the per-class ShipConfig every
+// constructor takes, nothing in here reads the game state
+mod config {
+    use super::*;
 
-impl Frigate {
-    pub fn new() -> Self {
-        Frigate {
-            target: None,
-            radar: Radar {
-                name: "frigate_radar".to_string(),
-                beam: RadarBeam::Narrow,
-                designated_target: None,
-                state: RadarState::LongRange,
-                id_gen: 0,
-                potential_targets: HashMap::new(),
-                ticks_since_contact: 0,
-            },
-            radio: Radio {
-                current_channel: CHANNEL_SANCHEZ,
-            }
-        }
-    }
+    pub const BULLET_SPEED: f64 = 1000.0; // m/s, fighter gun rounds
 
-    pub fn tick(&mut self) {
-        self.radar.radar_loop();
-        debug!("frigate is alive!");
-    }
-}
+    pub const FIGHTER_GUN_OFFSET: f64 = -1.33333333; // m along the nose, the fudge position_fixed applies
 
-pub struct Radio {
-    // current radio channel
-    current_channel: u8,
-}
+    pub const FRIGATE_MAIN_GUN_SPEED: f64 = 500.0; // m/s, heavy rounds take seconds to arrive
 
-pub struct Missile {
-    target: Option<Rc<RefCell<RadarTrack>>>,
-    sticky_target_ticks: u32,
-    radar: Radar,
-    radio: Radio,
-    target_heading_delay_ticks: u32,
-    acceleration_delay_ticks: u32,
-}
+    pub const FRIGATE_MISSILE_LAUNCHER: usize = 3; // weapon index of the frigate's missile rack
 
-impl Missile {
-    pub fn new() -> Self {
-        Missile {
-            target: None,
-            sticky_target_ticks: MISSILE_STICKY_TARGET_TICKS,
-            target_heading_delay_ticks: MISSILE_TARGET_HEADING_DELAY,
-            acceleration_delay_ticks: MISSILE_ACCELERATION_DELAY,
-            radar: Radar {
-                name: "missile_radar".to_string(),
-                beam: RadarBeam::Wide,
-                designated_target: None,
-                state: RadarState::MediumRange,
-                id_gen: 0,
-                potential_targets: HashMap::new(),
-                ticks_since_contact: 0,
-            },
-            radio: Radio {
-                current_channel: CHANNEL_SANCHEZ,
-            }
-        }
-    }
-    pub fn tick(&mut self) {
-        self.radar.radar_loop();
+    pub const CRUISER_MISSILE_LAUNCHERS: [usize; 2] = [1, 2]; // weapon indices of the cruiser's missile launchers
 
-        if self.radar.has_contacts() {
-            // TODO: id handling needs improvements
-            let mut id = 0;
+    pub const MANEUVER_UNCERTAINTY_SHARE: f64 = 0.25; // of a class's max acceleration assumed unpredictable over a flight
 
-            self.radar.beam = RadarBeam::Narrow;
-            if self.sticky_target_ticks <= 0 {
-                id = self.radar.get_closest_target_to_point(position());
-            } else {
-                self.sticky_target_ticks -= 1;
-            }
+    pub const MISSILE_TARGET_HEADING_DELAY: u32 = 30;
 
-            if self.radar.still_tracking(id) {
-                self.target = Some(self.radar.get_track(id));
+    pub const MISSILE_ACCELERATION_DELAY: u32 = 30;
 
-                // TODO: loses radar signal and tracks invalid target
-                // self.radar.state = RadarState::TargetFocus;
-            }
-            let contact_distance: f64 = self.target.as_ref().unwrap().as_ref().borrow().distance_from(position_fixed());
-            let contact_direction: Vec2 = self.target.as_ref().unwrap().as_ref().borrow().get_target_direction(position_fixed());
-            let contact_velocity: Vec2 = self.target.as_ref().unwrap().as_ref().borrow().velocity;
-            let contact_position: Vec2 = self.target.as_ref().unwrap().as_ref().borrow().position;
-            let contact_future = contact_position + (contact_velocity / 60.0);
+    pub const CHANNEL_SANCHEZ: u8 = 7;
 
-            let dp = contact_position - position();
-            let dv = contact_velocity - velocity();
+    pub const CHANNEL_TORPEDO: u8 = 8; // capital ship handoffs, kept apart so missiles don't latch onto them
 
-            let targ_range = contact_position - position();
-            let targ_rel_v = contact_velocity - velocity();
+    pub const MISSILE_CHANNEL_BASE: u8 = 1; // missiles use 1..=6, one per launch slot
 
-            draw_line(position(), targ_range, 0xff0000);
+    pub const MISSILE_CHANNEL_COUNT: u8 = 6;
 
-            let heading_error = angle_diff(heading(), dp.angle());
-            
-            let heading_error = angle_diff(heading(), dp.angle());
-            // turn(42.0 * heading_error);
+    pub const RADIO_RESOLUTION: f64 = 0.01; // m or m/s per quantization step
 
-            draw_line(contact_position, contact_position+dv*4.0, 0xffffff);
+    pub const CONTACT_SHARE_INTERVAL: u32 = 30; // ticks between contact broadcast bursts
 
-            debug!("velocity.length: {}",velocity().length());
-            // if self.target_heading_delay_ticks > 0 {
-            //     self.target_heading_delay_ticks -= 1;
-            // } else {
-            //     if contact_distance + velocity().length() > contact_distance {
-            //         // getting further away
-            //         accelerate(dp+dv);
-            //     } else {
-            //         // getting closer, limit speed
-            //         if velocity().length() < 200.0 {
-            //             accelerate(2.0 * (dp + (dv*2.0)));
-            //         }
-            //     }
-            // }
+    pub const CONTACT_SHARE_COUNT: usize = 3; // tracks per burst
 
-            seek(contact_position, contact_velocity);
-            draw_triangle(contact_future, 15.0, 0xff0000);
+    pub const CONTACT_REPORT_SPACING: u32 = 2; // fewest ticks between contact reports, the rest go to other traffic
 
-            if self.target.as_ref().unwrap().as_ref().borrow().distance_from(position()) < 15.0 {
-                explode();
-            }
-            if fuel() <= 0.0 {
-                // out of fuel, missile dud
-                explode();
-            }
-        } else {
-            self.radar.beam = RadarBeam::Wide;
-        }
-    }
-}
+    pub const CONTACT_REPORT_DEADLINE: u32 = 10; // ticks a queued report is worth sending, its age is stamped when queued
 
-// used to drive general ship behavior
-pub enum ShipState {
-    NoTarget,
-    Searching,
-    Engaged,
-    OutOfTargetRange,
-    OutOfRadarRange,
-}
+    pub const RADIO_STATE_DEADLINE: u32 = 0; // state re-queued every tick goes out this tick or not at all
 
-// used to drive engaged state behavior
-pub enum CombatState {
-    Attack,
-    Evade,
-    Flee,
-}
+    pub const OWN_ECHO_RADIUS: f64 = 100.0; // remote reports this close to us are ourselves
 
-#[derive(Debug)]
-pub struct TimedScanResult {
-    scan: ScanResult,
-    tick: u32,
-}
+    pub const REMOTE_GATE_WIDTH: f64 = 150.0; // remote reports are older and coarser than plots
 
-// RadarTrack is a specific item formulated by the radar tracker algorithm
-// it will be used to house revolving data for the object as well as
-// having useful derived data members
-#[derive(Debug)]
-pub struct RadarTrack {
-    // list of scan results tracked for this item
-    // these need to be parsed and filtered
-    scans: VecDeque<TimedScanResult>,
+    pub const REMOTE_BLEND: f64 = 0.1; // weight of a remote report against a locally held track
 
-    // resolved position estimate
-    position: Vec2,
+    pub const REMOTE_GATE_GROWTH: f64 = 50.0; // m/s, how fast a report's gate widens with its age
 
-    // resolved velocity estimate
-    velocity: Vec2,
+    pub const REMOTE_QUALITY_WEIGHT: f64 = 0.5; // keeps remote only tracks under QUALITY_GUN
 
-    // velocity.y.atan2(velocity.x) in quadrant 1..
-    heading: f64,
-    
-    // need to create a uuid
-    id: u128,
+    pub const LAUNCH_VELOCITY_DELTA: f64 = 8.0; // m/s a foe fighter's plots jump beyond its thrust to read as a launch
 
-    // track classification
-    class: TrackType,
+    pub const LAUNCH_SIGNATURE_TICKS: u32 = 4; // plots further apart than this can't tell a launch from maneuvering
 
-    // gate is the predicted target fence for position estimates
-    gate: RadarTrackGate,
+    pub const LAUNCH_INFERENCE_COOLDOWN: u32 = 30; // ticks before the same launcher can cue again
 
-    // lifetime manager
-    contact_tick: u32,
+    pub const INFERRED_MISSILE_SPEED: f64 = 200.0; // m/s toward us on top of the launcher's velocity
 
-    filter: Kalman,
-}
+    pub const INFERRED_GATE_RADIUS: f64 = 300.0; // the seed is a guess, the first real plot lands well off it
 
-// classifier to apply to a RadarTrack
-#[derive(Debug)]
-enum TrackType {
-    Tentative,
-    Friend,
-    Foe,
-    Missile,
+    pub const INFERRED_TRACK_TICKS: u32 = 60; // provisional missile tracks the radar never confirms are dropped
 
-}
+    pub const INFERRED_QUALITY_WEIGHT: f64 = 0.2; // keeps provisional tracks under QUALITY_ENGAGE
 
-// geometry helpers specific to a single RadarTrack
-trait RadarTrackGeometry {
-    fn heading(&self) -> f64;
+    pub const SALVO_CONFIRM_TICKS: u32 = 60; // ticks a missile waits to see its assigned target
 
-    fn push_plot(&mut self, plot: Option<TimedScanResult>);
+    pub const ASSIGNMENT_MATCH_RADIUS: f64 = 300.0; // m from the handoff estimate to count as the same target
 
-    fn update(&mut self);
+    pub const TERMINAL_CLAIM_RADIUS: f64 = 200.0; // m from a terminal claim to deprioritize a track
 
-    fn check_gate(&mut self, point: Vec2) -> bool;
+    pub const TERMINAL_CLAIM_TICKS: u32 = 60; // ticks a terminal claim is remembered
 
-    fn distance_from(&self, point: Vec2) -> f64;
+    pub const FORMATION_ELECTION_TICKS: u32 = 10; // ticks at spawn spent deciding who leads
 
-    // returns x,y values as distance representation to target
-    fn get_target_direction(&self, point: Vec2) -> Vec2;
-    // returns closing speed to target in scalar m/s
-    fn get_closing_speed_to_target(&self) -> f64;
-}
+    pub const FORMATION_BEACON_TIMEOUT: u32 = 30; // ticks without a leader beacon before breaking
 
+    pub const CAPITAL_BEACON_TIMEOUT: u32 = 120; // ticks without a capital beacon before dropping escort
 
-//****************************************************
-// Kalman filter workarea
-//****************************************************
-fn kalman() {
-    // is this the result?
-    // let mut state_transition_model;
+    pub const FORCED_SEED: Option<u64> = None; // set to a seed from the spawn debug line to replay a run
 
-    // is this the scans vecdeque?
-    // let mut observation_model;
+    pub const FORCED_OPENING: Option<Opening> = None; // set to skip the spawn heuristic and always fly this opening
 
-    // let mut process_noise_covariance;
+    pub const OPENING_EDGE_SHARE: f64 = 0.5; // of the half width, spawning further out than this reads as a duel start
 
-    // let mut observation_noise_covariance;
-}
+    pub const DEBUG: bool = true; // master switch for debug text and drawing
 
-// find mean of collection of values for covariance
-// return Vec2 of mean for both x and y components
-// TODO: can just use 
-// fn mean(v: , n: u32) -> Vec2 {
-//     let sum = 0.0;
-// }
+    pub const MATCH_STATS: bool = true; // collect engagement stats and print summaries, false compiles it out
 
-// find covariance (magic) of two sets of similar values?
-// TODO: can i just use ndarray/_stats module?
-// fn covariance() {
+    pub const STATS_SUMMARY_INTERVAL: u32 = 300; // ticks between stats summaries
 
-// }
+    pub const STATS_FINAL_HEALTH: f64 = 10.0; // hit points, below this the final summary goes out
 
-#[derive(Debug)]
-struct Kalman {
-    //  state_transition_model;
-    //  observation_model;
-    //  process_noise_covariance;
-    //  observation_noise_covariance;
-}
+    pub const STATS_IDLE_TICKS: u32 = 600; // ticks without contact after a kill that count as the end
 
-impl Kalman {
-    pub fn new() -> Self {
-        Kalman {
+    pub const FRIENDLY_CORRIDOR_WIDTH: f64 = 50.0; // m either side of the gun line a friendly blocks firing
 
-        }
-    }
+    pub const FRIENDLY_CORRIDOR_SPREAD: f64 = 0.02; // m of extra corridor width per m downrange
 
-    pub fn initialize() {
-        todo!();
-    }
+    pub const FRIENDLY_LAUNCH_CONE: f64 = 0.25; // rad either side of a launch bearing a friendly blocks the launch
 
-    pub fn update_covariance() {
+    pub const TORPEDO_DANGER: f64 = 3.0; // threat weight multiplier for a torpedo on course for the defended point
 
-    }
-}
+    pub const TORPEDO_AIM_RADIUS: f64 = 300.0; // m, closest approach that counts as aimed at the defended point
 
-impl RadarTrackGeometry for RadarTrack {
-    fn heading(&self) -> f64 {
-        self.velocity.y.atan2(self.velocity.x)
-    }
+    pub const DEGRADE_TRACK_THRESHOLD: usize = 6; // above this many tracks the radar sheds work
 
-    fn push_plot(&mut self, plot: Option<TimedScanResult>) {
-        self.scans.push_back(plot.unwrap());
-    }
+    pub const DISTANT_TRACK_RANGE: f64 = 5_000.0; // m, degraded mode updates these every other tick
 
-    fn update(&mut self) {
-        //*******
-        // pseudo code for collecting many points of reference and running
-        // them through a kalman filter for processing an estimated result
+    pub const MAX_QUEUED_SCANS: usize = 4; // per track, oldest plots are dropped past this
 
-        if self.scans.is_empty() {
-            // initialize kalman loop
-            // self.filter.initialize();
-        } else {
-            // update estimate -> updated state estimates
+    pub const CLASS_CONFIRM_PLOTS: u32 = 2; // plots in a row agreeing on a contact's class before it's classified
 
-            // update covariance
+    pub const ASSOCIATION_VELOCITY_WEIGHT: f64 = 0.5; // s, meters of association score per m/s of velocity mismatch
 
-            // projection into k+1 space -> projected estimates (new position data?)
-        }
+    pub const TARGET_SWITCH_MARGIN: f64 = 0.2; // score, the designated target is kept over anything scoring less than this much more
 
+    pub const TARGET_SCORE_RANGE: f64 = 5_000.0; // m, range at which the distance term of a target score halves
 
-        //*******
+    pub const TARGET_SCORE_SPEED: f64 = 500.0; // m/s, closing speed that maxes out the closing term
 
-        // basic update code processing one value at a time and dropping it
-        if self.scans.is_empty() {
-            // no new scans in queue, just update one tick of velocity
-            debug!("using estimated velocity");
-            self.position += self.velocity / 60.0;
-        } else {
-            // we have scans to consider
-            if self.scans.len() == 1 {
-                debug!("one scans to consider for radartrack: {}", self.id);
-                // only one element front and back are the same here
-                let scan = self.scans.pop_front().unwrap();
-                debug!("scan position: {}", scan.scan.position);
+    pub const KALMAN_PROCESS_NOISE: f64 = 2_500.0; // m^2/s^3, maneuvering the acceleration estimate doesn't explain
 
-                // cur_vel(t-1) - scan.vel(t) => delta_vel
-                // delta_vel needs to be in ticks as well / 2 ticks => 
-                let current_velocity_in_ticks = self.velocity  / 60.0;
-                let acceleration = (current_velocity_in_ticks - (scan.scan.velocity / 60.0)) / 2.0;
-                // ^^ acceleration should be in meters / second / tick (m/s/t)
-                // add velocity in ticks with new acceleration, mult*60.0 should convert back to meters / second
-                let new_velocity = (current_velocity_in_ticks + acceleration) * 60.0;
-                debug!("old velocity: {}", self.velocity);
-                debug!("new velocity: {}", new_velocity);
-                self.velocity = new_velocity;
-                // add acceleration experienced in the last tick to the current estimated position
-                self.position += acceleration;
-            } else {
-                debug!("multiple scans to consider for radartrack: {}", self.id);
-                // multiple scans case
-                // TODO: can this happen? means update wasnt called on this for multiple ticks
-            }
-        }
+    pub const KALMAN_POSITION_NOISE: f64 = 10.0; // m, one sigma of a plot's position
 
-        // done processing, update RadarTrackGate::center
-        self.gate.update_center(self.position);
-    }
+    pub const KALMAN_VELOCITY_NOISE: f64 = 5.0; // m/s, one sigma of a plot's velocity
 
-    fn check_gate(&mut self, point: Vec2) -> bool {
-        self.gate.point_in_gate(point)
-    }
+    pub const KALMAN_GATE_SIGMAS: f64 = 3.0; // gate half width in position sigmas
 
-    fn distance_from(&self, point: Vec2) -> f64 {
-        (self.position - point).length()
-    }
+    pub const KALMAN_MIN_GATE: f64 = 30.0; // m, a converged filter still has plot noise to let in
 
-    fn get_target_direction(&self, point: Vec2) -> Vec2 {
-        self.position - point
-    }
+    pub const KALMAN_MAX_GATE: f64 = 5_000.0; // m, a long coast shouldn't swallow the whole scope
 
-    fn get_closing_speed_to_target(&self) -> f64 {
-        -((self.velocity - velocity()).dot(self.get_target_direction(position_fixed())) / self.distance_from(position_fixed()))
-    }
-}
+    pub const GATE_SPEED_SHARE: f64 = 0.1; // speed error an estimate can carry, stretches the gate along track while coasting
 
-// defines a square field for a given radartrack
-#[derive(Debug)]
-pub struct RadarTrackGate {
-    center: Vec2,
-    radius: f64,
-}
+    pub const GATE_DRAW_SEGMENTS: usize = 16; // sides of the polygon drawn for a gate ellipse
 
-impl RadarTrackGate {
-    pub fn new(point: Vec2, radius: f64) -> RadarTrackGate {
-        RadarTrackGate {
-            center: point,
-            radius,
-        }
-    }
-    pub fn draw_gate(&self, id: u128) {
-        draw_square(self.center, self.radius, 0xff0000);
-        let p4: Vec2 = Vec2::new(self.center.x + self.radius / 2.0, self.center.y - self.radius / 2.0);
-        draw_text!(p4, 0xff0000, "id: {}", id);
-    }
+    pub const PLOT_LATENCY_TICKS: u32 = 1; // scan() reports what the beam pointed last tick saw
 
-    pub fn update_center(&mut self, center: Vec2) {
-        self.center = center;
-    }
+    pub const DUPLICATE_PLOT_RADIUS: f64 = 10.0; // m, a same class plot this close to another from the same sweep is the same return
 
-    // find current points of square based on radius or error magnitude
-    pub fn point_in_gate(&self, point: Vec2) -> bool {
-        let p1: Vec2 = Vec2::new(self.center.x + self.radius / 2.0, self.center.y + self.radius / 2.0);
-        let p2: Vec2 = Vec2::new(self.center.x - self.radius / 2.0, self.center.y + self.radius / 2.0);
-        let p3: Vec2 = Vec2::new(self.center.x - self.radius / 2.0, self.center.y - self.radius / 2.0);
-        let p4: Vec2 = Vec2::new(self.center.x + self.radius / 2.0, self.center.y - self.radius / 2.0);
+    pub const BOUNDARY_MARGIN: f64 = 2_000.0; // m
 
-        if point.x >= p1.x || point.y >= p1.y {
-            return false;
-        }
-        if point.x <= p2.x || point.y >= p2.y {
-            return false;
-        }
-        if point.x <= p3.x || point.y <= p3.y {
-            return false;
-        }
-        if point.x >= p4.x || point.y <= p4.y {
-            return false;
-        }
-        true
-    }
+    pub const SEARCH_LEG_SPACING: f64 = 5_000.0; // m
 
-    pub fn update_radius(&mut self, radius: f64) {
-        self.radius = radius;
-    }
-}
+    pub const SEARCH_ESCALATE_TICKS: u32 = 600; // ticks without contact before sweeping at long range
 
-pub struct Fighter {
-    // does the ship have a target
-    target_lock: bool,
+    pub const SWEEP_SECTORS: usize = 16; // bearing buckets the sweep remembers contacts in
 
-    target: Option<Rc<RefCell<RadarTrack>>>,
+    pub const SWEEP_CONTACT_BIAS: f64 = 2.0; // extra share of the sweep per unit of sector weight
 
-    // current ship state
-    state: ShipState,
+    pub const SWEEP_SECTOR_MAX_WEIGHT: f64 = 4.0; // a sector's weight stops growing here however many plots land in it
 
-    radio: Radio,
+    pub const SWEEP_SECTOR_DECAY: f64 = 0.995; // per tick without contact, about a 140 tick half life
 
-    // ship radar component
-    radar: Radar,
+    pub const SWEEP_SECTOR_FLOOR: f64 = 0.05; // every sector under this and the sweep is back to uniform
 
-    sticky_target_ticks: u32,
+    pub const SWEEP_MAX_AGE: u32 = 2 * SWEEP_SECTORS as u32; // sector picks before a sector has to be swept again
 
-    rotation: Rotator,
+    pub const ACCELERATION_SMOOTHING: f64 = 0.3; // weight of the newest finite difference
 
-    // TODO:
-    // lateral_throttle
-    // longitudinal_throttle
-}
+    pub const TRACK_HISTORY_LENGTH: usize = 60; // ticks of estimates kept per track
 
-enum RadarState {
-    ShortRange,
-    MediumRange,
-    LongRange,
-    TargetFocus,
-}
+    pub const TRACK_HISTORY_WINDOW: usize = 10; // entries used for smoothed heading/turn rate
 
-enum RadarBeam {
-    Focused,
-    Narrow,
-    Standard,
-    Wide,
-}
+    pub const MISSILE_TERMINAL_RANGE: f64 = 2_000.0; // m
 
-pub struct Radar {
-    // radar name, currently just for debugging
-    name: String,
+    pub const MISSILE_NAV_GAIN: f64 = 4.0; // proportional navigation constant
 
-    beam: RadarBeam,
-    state: RadarState,
+    pub const WEAVE_MIN_FUEL_FRACTION: f64 = 0.2; // share of launch fuel under which ordnance stops weaving
 
-    // count ticks since contact to switch to extended radar sweep
-    ticks_since_contact: u32,
+    pub const WEAVE_FEASIBLE_FUEL_SHARE: f64 = 0.5; // the intercept has to stay feasible on this share of the fuel to weave
 
-    designated_target: Option<u128>,
+    pub const WEAVE_AVAILABLE_SHARE: f64 = 0.5; // most of the available acceleration the weave may take
 
-    // collect current target positions for time-based calculations
-    potential_targets: HashMap<u128, Rc<RefCell<RadarTrack>>>,
+    pub const MISSILE_COAST_SECONDS: f64 = 8.0; // s, the boost aims for a closing speed that covers the run to intercept in this
 
-    // simple unsigned integer id to use for uuids
-    id_gen: u128,
-}
+    pub const DOGLEG_ALIGNED: f64 = PI / 12.0; // rad off the offset axis that counts as having swung around
 
-trait RadarTracker {
-    // main loop
-    fn radar_loop(&mut self);
-    
-    // handle unique id creation
-    fn new_id_gen(&mut self) -> u128;
+    pub const DOGLEG_FUEL_SHARE: f64 = 0.5; // most of the remaining fuel the dogleg may spend
 
-    fn still_tracking(&self, id: u128) -> bool;
+    pub const LOST_CONTACT_TICKS: u32 = 120; // dead reckon a dropped target this long before searching again
 
-    fn has_contacts(&self) -> bool;
-    
-    fn update_tracks(&mut self);
+    pub const MISSILE_HEARTBEAT_INTERVAL: u32 = 15; // ticks between in-flight missile heartbeats
 
-    fn show_tracks(&self);
-    
-    fn insert_new_potential_target(&mut self, plot: Option<ScanResult>);
+    pub const MISSILE_HEARTBEAT_TIMEOUT: u32 = 45; // ticks of silence before a missile is written off
 
-    // used to add a new ScanResult plot to the potential_targets data
-    fn add_detection_point(&mut self, plot: Option<ScanResult>);
+    pub const PROBABLE_KILL_RADIUS: f64 = 200.0; // m from the dead track's last position for a heartbeat to claim it
 
-    fn get_closest_target_to_point(&self, point: Vec2) -> u128;
+    pub const GUN_MISS_TOLERANCE: f64 = 20.0; // m, predicted miss distance the gun fires inside
 
-    fn get_track(&self, id: u128) -> Rc<RefCell<RadarTrack>>;
+    pub const GUN_BURST_ROUNDS: u32 = 8; // rounds the gun puts out before pausing
 
-    // locks radar to closest target
-    fn lock_radar_to_target(&self);
+    pub const GUN_BURST_PAUSE_TICKS: u32 = 20; // ticks between bursts, lets the solution catch up
 
-    fn set_beam_width(&self);
+    pub const MISSILE_LAUNCH_INTERVAL: u32 = 120; // ticks between a fighter's missile launches
 
-    // short range radar sweep
-    fn short_range_sweep(&self);
-    // performs a standard radar sweep
-    fn standard_radar_sweep(&self);
-    // performs a long range radar sweep
-    fn long_range_radar_sweep(&self);
-}
+    pub const THRUST_ROTATION_THRESHOLD: f64 = 0.9; // fraction of nose on thrust the current attitude must give before the hull is turned
 
-// impl against Radar struct to remove dependency on Ship
-impl RadarTracker for Radar {
-    fn radar_loop(&mut self) {
-        self.update_tracks();
-        self.show_tracks();
-        self.set_beam_width();
+    pub const ROTATION_HOLD_ANGLE: f64 = 0.05; // rad of heading error where snap_to_heading hands off to a proportional hold
 
-        match self.state {
-            RadarState::ShortRange => {self.short_range_sweep();},
-            RadarState::MediumRange => {self.standard_radar_sweep();},
-            RadarState::LongRange => {self.long_range_radar_sweep();},
-            RadarState::TargetFocus => {self.lock_radar_to_target()},
-        }
+    pub const ROTATION_HOLD_GAIN: f64 = 100.0; // tune factor for the hold, 1/s^2
 
-        if let Some(plot) = scan() {
-            self.add_detection_point(Some(plot));
-        }
-    }
+    pub const TURN_SATURATION_ANGLE: f64 = 0.5; // rad of heading error the default turn controller saturates at
 
-    // use current value as next, then increment id counter
-    fn new_id_gen(&mut self) -> u128 {
-        let next = self.id_gen;
-        self.id_gen += 1;
-        debug!("new_id_gen: next: {}, incremented: {}", next, self.id_gen);
-        next
-    }
+    pub const TURN_PRECISE_SATURATION_ANGLE: f64 = 0.02; // rad, gunnery preset, stiff on the last few hundredths
 
-    fn still_tracking(&self, id: u128) -> bool {
-        self.potential_targets.contains_key(&id)
-    }
+    pub const TURN_DODGE_SATURATION_ANGLE: f64 = 0.1; // rad, dodge preset, full torque for all but the end of a turn
 
-    fn has_contacts(&self) -> bool {
-        !self.potential_targets.is_empty()
-    }
+    pub const KNIFE_LATERAL_GAIN: f64 = 2.0; // 1/s, how hard lateral thrust cancels crossing velocity up close
 
-    fn insert_new_potential_target(&mut self, plot: Option<ScanResult>) {
-        let mut scans: VecDeque<TimedScanResult> = VecDeque::new();
-        debug!("insert_new_potential_target: new plot position: {}", plot.as_ref().unwrap().position);
-        scans.push_back(TimedScanResult { tick: current_tick(), scan: ScanResult { ..plot.clone().unwrap() } });
-        let id = self.new_id_gen();
-        // populate initial RadarTrack with baseline values
-        let track = Rc::new(RefCell::new(RadarTrack {
-            scans,
-            position: plot.as_ref().unwrap().position,
-            velocity: plot.as_ref().unwrap().velocity,
-            heading: plot.as_ref().unwrap().velocity.y.atan2(plot.as_ref().unwrap().velocity.x),
-            id,
-            class: TrackType::Tentative,
-            gate: RadarTrackGate::new(plot.as_ref().unwrap().position, 50.0),
-            contact_tick: current_tick(),
-            filter: Kalman { }
-        }));
-        self.potential_targets.insert(id, track);
-    }
+    pub const AVOID_RADIUS: f64 = 150.0; // m, closest approach to a friendly hull or own missile worth steering off
 
-    fn show_tracks(&self) {
-        for (id, track) in &self.potential_targets {
-            track.borrow().gate.draw_gate(*id);
-        }
-    }
+    pub const AVOID_HORIZON: f64 = 3.0; // s, closest approaches further out than this are left alone
 
-    // iterate over existing tracks and call their update method
-    fn update_tracks(&mut self) {
-        for (_id, track) in &self.potential_targets {
-            track.borrow_mut().update();
-        }
-    }
-    fn get_closest_target_to_point(&self, point: Vec2) -> u128 {
-        let mut distance: f64 = 90_000_000.0;
-        let mut target_id: u128 = 0;
+    pub const ENERGY_BRAKE_GAIN: f64 = 2.0; // 1/s, how hard excess speed over the combat band is shed
 
-        // iterate over potential targets looking for 
-        for (id, track) in &self.potential_targets {
-            let dist = track.borrow().distance_from(point);
-            if dist < distance {
-                distance = dist;
-                target_id = *id;
-            }
-        }
-        target_id
-    }
+    pub const KILL_SILENCE_TICKS: u32 = 15; // ticks without a plot before a kill can be inferred
 
-    fn get_track(&self, id: u128) -> Rc<RefCell<RadarTrack>> {
-        Rc::clone(&self.potential_targets.get(&id).unwrap())
-    }
+    pub const KILL_WINDOW_TICKS: u32 = 15; // slack around the predicted impact ticks for the last plot
 
-    fn add_detection_point(&mut self, plot: Option<ScanResult>) {
-        debug!("adding detection point");
-        debug!("potential_targets.len: {}", self.potential_targets.len());
-        if self.potential_targets.is_empty() {
-            // first result, no values to compare with
-            self.insert_new_potential_target(plot);
-        } else {
-            let mut found = false;
-            let mut found_id = 0;
-            let mut old_tracks: Vec<u128> = Vec::new();
-            // TODO: improve detection point association
-            // check radartracks for potential match
-            for (id, track) in &self.potential_targets {
-                if found {
-                    break;
-                }
-                let mut t = track.borrow_mut();
-                if t.check_gate(plot.as_ref().unwrap().position) {
-                    debug!("associating new plot with existing target");
-                    found = true;
-                    // update current track with new data
-                    t.push_plot(Some(TimedScanResult { tick: current_tick(), scan: ScanResult { ..plot.clone().unwrap() } }));
-                    
-                    t.update();
-                } else {
-                    // check current track lifetime
-                    let delta_tick: f64 = (current_tick() - t.contact_tick).into();
-
-                    // check if num ticks hits 2 second window, remove outdated track
-                    if delta_tick >= 30.0 {
-                    // if delta_tick / 60.0 >= 1.0 {
-                        debug!("adding old_track id: {}", id);
-                        old_tracks.push(*id);
-                    }
-                }
-            }
-            // clear out of date tracks
-            if old_tracks.len() > 0 {
-                for i in &old_tracks {
-                    self.potential_targets.remove(i);
-                    debug!("targ bef len: {}", self.potential_targets.len());
-                    debug!("removed target: {}", i);
-                    debug!("targ after len: {}", self.potential_targets.len());
+    pub const KILL_QUALITY: f64 = 0.4; // quality the track has to have collapsed below
 
-                }
-                old_tracks.clear();
-            }
-            if !found {
-                // new potential target discovered
-                debug!("new target discovered");
-                self.insert_new_potential_target(plot);
-            }
-        }
-    }
-    
-    fn lock_radar_to_target(&self) {
-        let t = self.potential_targets.get(&self.get_closest_target_to_point(position())).unwrap();
-        let t_dir = t.as_ref().borrow().position - position_fixed();
-        let t_dist = t_dir.length();
-        set_radar_heading(t_dir.angle());
+    pub const MISSILE_RADAR_RANGE: f64 = 20_000.0; // m, further out than a missile's fuel gets it to
 
-        // focus radar on target
-        set_radar_width(PI / t_dist.log(2.0));
-        set_radar_max_distance(t_dist + (t_dist * 0.1));
-        set_radar_min_distance(t_dist - (t_dist * 0.3));
-    }
+    pub const LOCK_RANGE_MARGIN: f64 = 100.0; // m added on both sides of the predicted range when locked
 
-    fn set_beam_width(&self) {
-        match self.beam {
-            RadarBeam::Focused => { set_radar_width(PI / 32.0) },
-            RadarBeam::Narrow => { set_radar_width(PI / 8.0) },
-            RadarBeam::Standard => { set_radar_width(PI / 4.0) },
-            RadarBeam::Wide => { set_radar_width(PI / 2.0) },
-        }
-    }
+    pub const LOCK_MIN_WIDTH: f64 = PI / 64.0; // narrowest locked beam
 
-    fn short_range_sweep(&self) {
-        set_radar_heading(radar_heading() + radar_width());
-        set_radar_max_distance(10_000.0);
-        set_radar_min_distance(25.0);
-    }
+    pub const REVISIT_BEARING_BUDGET: f64 = LOCK_MIN_WIDTH / 2.0; // rad a track may drift off its last bearing before it's due
+
+    pub const REVISIT_MIN_TICKS: u32 = 2; // at least 2 so the designated target can't take every tick from the sweep
+
+    pub const DESIGNATED_DWELL_RANGE: f64 = 2_500.0; // m of range per tick between dwells on the designated target
+
+    pub const DESIGNATED_MAX_DWELL_TICKS: u32 = 12; // the designated target is looked at five times a second however far
+
+    pub const REVISIT_MAX_TICKS: u32 = 60; // even a distant, slow track gets a look once a second
+
+    pub const REVISIT_GUN_RANGE: f64 = 2_000.0; // m, tracks inside this are kept at QUALITY_GUN, the rest at QUALITY_ENGAGE
+
+    pub const REVISIT_MIN_SEARCH_SHARE: f64 = 0.25; // of ticks the background sweep is guaranteed, at most 0.5
+
+    pub const LOCK_MAX_WIDTH: f64 = PI / 8.0; // widest locked beam
+
+    pub const LOCK_LOST_TICKS: u32 = 15; // a lock this long without a plot drops back to the medium range sweep
+
+    pub const BEAM_HOLD_TICKS: u32 = 10; // ticks a beam request has to persist before the beam follows
+
+    pub const BEAM_CHANGE_INTERVAL: u32 = 30; // fewest ticks between beam class changes
+
+    pub const BEAM_NARROW_RATE: f64 = 0.7; // share of the width kept per tick while narrowing
+
+    pub const BEAM_NARROW_QUALITY: f64 = 0.3; // track quality a missile needs before asking for a narrow beam
+
+    pub const QUALITY_GUN: f64 = 0.6; // track quality the gun needs before firing
+
+    pub const QUALITY_MISSILE: f64 = 0.3; // track quality a missile launch needs
+
+    pub const QUALITY_ENGAGE: f64 = 0.1; // track quality needed to go Engaged at all
+
+    pub const QUALITY_PLOT_WINDOW: u32 = 60; // ticks of plots counted for the plot rate term
 
-    fn standard_radar_sweep(&self) {
-        // TODO: if we've been looking for a while, look harder
-        // if self.ticks_since_contact > 30 {
-        //     self.set_state(ShipState::OutOfRadarRange);
-        // }
+    pub const QUALITY_EXPECTED_PLOTS: f64 = 6.0; // plots per window that count as a fully fed track
 
-        set_radar_heading(radar_heading() + radar_width());
-        set_radar_max_distance(50_000.0);
-        set_radar_min_distance(25.0);
+    pub const QUALITY_STALE_TICKS: f64 = 30.0; // ticks since plot for quality to fall to 1/e
+
+    pub const ENGAGE_MIN_PLOTS: u32 = 2; // plots, local or remote, before a track can be engaged
+
+    pub const FRIENDLY_MATCH_RADIUS: f64 = 150.0; // m from a known friendly position to classify a track as friend
+
+    pub const FRIENDLY_REPORT_TICKS: u32 = 60; // ticks a friendly position is dead reckoned for classification
+
+    pub const POINT_DEFENSE_RANGE: f64 = 2_000.0; // m, an incoming missile inside this preempts the engagement
+
+    pub const POINT_DEFENSE_CLOSING_SPEED: f64 = 100.0; // m/s, slower closure is a missile passing or chasing someone else
+
+    pub const EVADE_TIME_TO_IMPACT: f64 = 2.0; // s, a missile closer than this in time is dodged rather than shot
+
+    pub const EVADE_FLIP_TICKS: (u32, u32) = (15, 25); // random wait between reversals of the evasive break
+
+    pub const FLEE_REENGAGE_RANGE: f64 = 6_000.0; // m, pursuers all past this and a retreat has broken contact
+
+    pub const FLEE_MIN_TICKS: u32 = 180; // a retreat runs at least this long before it reconsiders
+
+    pub const FLEE_GUN_RANGE: f64 = 1_500.0; // m, what an outrunning pursuer has to be kept out of
+
+    pub const FLEE_REENGAGE_RELATIVE_HEALTH: f64 = 0.5; // our hit points over the pursuers' assumed ones, at or above this a retreat turns back
+
+    pub const INITIAL_BURN_TICKS: u32 = 30; // ticks of the opening burn before the search starts
+
+    pub const OUT_OF_RANGE_MARGIN: f64 = 1.2; // past weapon range times this, stop fighting and close
+
+    pub const BACK_IN_RANGE_FRACTION: f64 = 0.9; // inside weapon range times this, fight again
+
+    pub const RADAR_DRY_SPELL_TICKS: u32 = 1_200; // ticks searching without contact before reaching out
+
+    pub const LOW_HEALTH: f64 = 50.0; // hit points, under fire below this we stop trading shots
+
+    pub const MISSILE_BOOST_ACCELERATION: f64 = 300.0; // m/s/s, launcher's estimate of a missile's main engine
+
+    pub const MISSILE_DELTA_V: f64 = 2_000.0; // m/s, launcher's estimate of a missile's fuel
+
+    pub const SOFT_LAUNCH_TICKS: u32 = 30; // longest a missile spends aligning before boosting anyway
+
+    pub const SOFT_LAUNCH_ALIGNED: f64 = 0.1; // rad of heading error that counts as aligned
+
+    pub const SOFT_LAUNCH_ABORT_RANGE: f64 = 1_000.0; // m, closer targets get full guidance immediately
+
+    pub const MISSILE_HANDOFF_WAIT_TICKS: u32 = 10; // launch boost only waits this long for a handoff
+
+    pub const MISSILE_FORWARD_SEARCH_TICKS: u32 = 120; // sweep ahead of the launcher for this long without one
+
+    pub const COORDINATED_STRIKE: bool = true; // time missile launches with a wingman to arrive together
+
+    pub const STRIKE_TIMEOUT_TICKS: u32 = 60; // ticks without strike traffic before launching independently
+
+    pub const CLAIM_INTERVAL: u32 = 30; // ticks between our target claim broadcasts
+
+    pub const CLAIM_EXPIRY_TICKS: u32 = 120; // ticks a teammate's claim holds without a repeat
+
+    pub const STRIKE_WINDOW_TICKS: u32 = 30; // ticks either side of the agreed arrival that count as on time
+
+    // seconds of sustained point defense fire to bring down a contact of this class,
+    // rough figures from hit points over what the guns put on target
+    pub fn estimated_time_to_kill(class: Class) -> f64 {
+        match class {
+            Class::Missile => 0.1,
+            Class::Torpedo => 1.0,
+            Class::Fighter => 2.0,
+            Class::Frigate => 30.0,
+            Class::Cruiser => 60.0,
+            _ => 1.0,
+        }
     }
 
-    fn long_range_radar_sweep(&self) {
-        debug!("long range radar sweep");
-        set_radar_heading(radar_heading() + radar_width());
-        set_radar_max_distance(1_000_000.0);
-        set_radar_min_distance(25.0);
+    // rough half size of a hull of this class, a round passing inside it hits, m
+    pub fn hull_radius(class: Class) -> f64 {
+        match class {
+            Class::Missile => 2.0,
+            Class::Torpedo => 4.0,
+            Class::Fighter => 10.0,
+            Class::Frigate => 30.0,
+            Class::Cruiser => 60.0,
+            _ => 10.0,
+        }
     }
-}
 
+    // tunable numbers for one ship class, built by each class's default_config()
+    // and handed to the constructor so scenario tuning happens in one place
+    #[derive(Clone)]
+    pub struct ShipConfig {
+        // one entry per gun index the class fires, empty for classes without guns
+        pub guns: Vec<GunConfig>,
 
-pub struct Rotator {
-    // current movement estimated ticks to accomplish desired rotation
-    estimated_ticks_to_angle: u32,
-    throttle: f64,
-}
+        // sweep ranges, beam widths and track bookkeeping for this class's radar
+        pub radar: RadarProfile,
 
-trait FigherGeometry {
-    fn shoot(&self);
+        // closing behavior range bands outside the orbit, m
+        pub close_range: f64,
+        pub far_range: f64,
 
-    fn fly_to_target(&self);
+        // effective ranges per weapon and target class, None class matches anything
+        // not listed, a weapon with no entry isn't used against that class
+        pub envelopes: Vec<(Weapon, Option<Class>, EngagementEnvelope)>,
 
-    fn engage_target(&mut self);
+        // inside this range the gun aims on a lead rate limited to what the hull can
+        // turn, fires on predicted miss only and translates to hold the target on the
+        // nose, m, 0.0 never
+        pub knife_fight_range: f64,
 
-    fn seconds_to_intercept(&self) -> f64;
+        // proportional navigation constant N, 0.0 for classes that don't navigate on PN
+        pub nav_gain: f64,
 
-    fn ticks_to_intercept(&self) -> f64;
+        // weight on the target acceleration term of augmented PN at full track
+        // quality, 0.0 is plain PN and for classes that don't navigate on PN
+        pub apn_gain: f64,
 
-    fn turn_to_lead_target(&self, lead: Vec2);
-    fn turn_to_lead_target_aggressive(&self, lead: Vec2);
-    
-    fn heading_to_target(&self, target: Vec2);
+        // speed relative to the target worth carrying into a gun fight, m/s, above
+        // the max the fighter sheds speed before pursuing, 0.0 max never does
+        pub combat_speed_min: f64,
+        pub combat_speed_max: f64,
 
-    fn basic_maneuver_to_target(&self);
+        // share of spawn health under which a fighter outnumbered or outrun breaks off
+        // and runs, 0.0 never
+        pub flee_health_fraction: f64,
 
-    fn set_current_target(&mut self, target: Rc<RefCell<RadarTrack>>);
-}
+        // how tracks are ranked for designation, see TargetWeights
+        pub target_weights: TargetWeights,
+    }
 
-impl FigherGeometry for Fighter {
-    fn shoot(&self) {
-        if self.target.as_ref().unwrap().as_ref().borrow().distance_from(position_fixed()) < 2000.0 {
-            fire(0);
+    impl ShipConfig {
+        pub fn gun(&self, index: usize) -> Option<GunConfig> {
+            self.guns.iter().find(|g| g.index == index).copied()
+        }
+
+        // speed of that gun's rounds, m/s, 0.0 for a gun index the class doesn't have
+        pub fn projectile_speed(&self, index: usize) -> f64 {
+            self.gun(index).map_or(0.0, |g| g.projectile_speed)
+        }
+
+        pub fn envelope(&self, weapon: Weapon, class: Class) -> Option<EngagementEnvelope> {
+            let exact = self.envelopes.iter().find(|(w, c, _)| *w == weapon && *c == Some(class));
+            let any = self.envelopes.iter().find(|(w, c, _)| *w == weapon && c.is_none());
+            exact.or(any).map(|(_, _, envelope)| *envelope)
         }
-    }
-    
-    fn seconds_to_intercept(&self) -> f64 {
-        let delta_position = position_fixed() - self.target.as_ref().unwrap().as_ref().borrow().position;
-        let _delta_velocity = velocity() - self.target.as_ref().unwrap().as_ref().borrow().velocity;
-        delta_position.length() / velocity().length()
     }
 
-    fn ticks_to_intercept(&self) -> f64 {
-        (position_fixed() - self.target.as_ref().unwrap().as_ref().borrow().position).length() / ((velocity() / 60.0) - (self.target.as_ref().unwrap().as_ref().borrow().velocity / 60.0)).length()
+    // one gun index as fire()/aim() see it
+    #[derive(Debug, Clone, Copy)]
+    pub struct GunConfig {
+        pub index: usize,
+
+        // m/s, the round also inherits the ship's velocity
+        pub projectile_speed: f64,
+
+        // how far off the target a round still does its job, blast radius for shells, m
+        pub hit_radius: f64,
+
+        // where the rounds leave the hull, body frame (x along the nose), m
+        pub offset: Vec2,
+
+        // half angle of the cone the rounds scatter into, rad
+        pub spread: f64,
+
+        // slewed with aim(), otherwise it fires along the nose
+        pub turret: bool,
     }
 
-    fn fly_to_target(&self) {
-        self.turn_to_lead_target(self.target.as_ref().unwrap().as_ref().borrow().get_target_direction(position_fixed()));
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Weapon {
+        Gun,
+        Missile,
     }
 
-    // engage fighter geometry with target
-    // TODO: this maybe should be changed to setup an attack orbit
-    fn engage_target(&mut self) {
-        if self.target.is_some() {
+    // where a weapon is worth using against a class of target, m
+    #[derive(Debug, Clone, Copy)]
+    pub struct EngagementEnvelope {
+        // inside this the round can't arm or turn onto the target in time
+        pub min_range: f64,
+
+        // beyond this the hit probability isn't worth the ammo
+        pub max_range: f64,
+
+        // band we try to hold while fighting with this weapon
+        pub standoff_min: f64,
+        pub standoff_max: f64,
+    }
 
-            // TODO: still no idea which of these works best / least worst
-            // let lead_point = quadratic_lead(self.target.as_ref().unwrap().borrow().position, self.target.as_ref().unwrap().borrow().velocity);
-            let lead_point = get_target_lead_in_ticks(self.target.as_ref().unwrap().as_ref().borrow().position, self.target.as_ref().unwrap().as_ref().borrow().velocity);
-            // let lead_point = self.get_adjusted_target_lead_in_ticks(self.target.as_ref().unwrap().borrow().position, self.target.as_ref().unwrap().borrow().velocity);
-            draw_triangle(self.target.as_ref().unwrap().as_ref().borrow().position, 50.0, 0x00ff00);
-            // draw_line(position_fixed(), lead_point, 0xff00f0);
+    // which side of the envelope is stopping us from firing
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum EnvelopeLimit {
+        MinRange(f64),
+        MaxRange(f64),
+    }
 
-            // TODO: fighter is dumb and flies straight at target which usually wins in the fight
-            if self.target.as_ref().unwrap().as_ref().borrow().distance_from(position_fixed()) < 1000.0 {
-                self.turn_to_lead_target_aggressive(lead_point);
+    impl EngagementEnvelope {
+        pub fn check(&self, range: f64) -> Result<(), EnvelopeLimit> {
+            if range < self.min_range {
+                Err(EnvelopeLimit::MinRange(self.min_range))
+            } else if range > self.max_range {
+                Err(EnvelopeLimit::MaxRange(self.max_range))
             } else {
-                self.fly_to_target();
-            }
-            fire(1);
-        }
-    }
-
-    fn set_current_target(&mut self, target: Rc<RefCell<RadarTrack>>) {
-        self.target = Some(target);
-    }
-
-    // NOTE: just write a new method, this works well enough now
-    fn turn_to_lead_target(&self, lead: Vec2) {
-        let current_diff = angle_diff(heading(), lead.angle());
-        if current_diff.abs() > 0.1 {
-            let next_ang_v = calculate_angular_velocity(50.0, current_diff);
-            debug!("turning angle velocity: {}", next_ang_v);
-            torque(next_ang_v);
-        } else {
-            let next_ang_v = calculate_angular_velocity(1_000.0, current_diff);
-            debug!("firing angle velocity: {}", next_ang_v);
-            torque(next_ang_v);
-            self.shoot();
-        }
-    }
-    // NOTE: just write a new method, this works well enough now
-    fn turn_to_lead_target_aggressive(&self, lead: Vec2) {
-        let current_diff = angle_diff(heading(), lead.angle());
-        if current_diff.abs() > 0.1 {
-            let next_ang_v = calculate_angular_velocity(4.0, current_diff);
-            debug!("turning angle velocity: {}", next_ang_v);
-            torque(next_ang_v);
-        } else {
-            let next_ang_v = calculate_angular_velocity(10.0, current_diff);
-            debug!("firing angle velocity: {}", next_ang_v);
-            torque(next_ang_v);
-            self.shoot();
-        }
-    }
-
-    fn heading_to_target(&self, target: Vec2) {
-        // let current_diff = angle_diff(heading(), self.get_angle_to_target());
-        let current_diff = angle_diff(heading(), target.angle());
-        if current_diff.abs() > 0.01 {
-            torque(calculate_angular_velocity(69.0, current_diff));
-        } else {
-            turn(calculate_angular_velocity(50_000.0, current_diff));
-        }
-    }
-    fn basic_maneuver_to_target(&self) {
-        let target_id = self.radar.get_closest_target_to_point(position_fixed());
-        let target = self.radar.get_track(target_id);
-        let contact_distance: f64 = self.target.as_ref().unwrap().as_ref().borrow().distance_from(position_fixed());
-        let contact_direction: Vec2 = self.target.as_ref().unwrap().as_ref().borrow().get_target_direction(position_fixed());
-        let contact_velocity: Vec2 = self.target.as_ref().unwrap().as_ref().borrow().velocity;
-        let contact_position: Vec2 = self.target.as_ref().unwrap().as_ref().borrow().position;
-        let contact_future = contact_position + (contact_velocity);
-        let contact_future_distance = (position_fixed() - contact_future).length();
-        let mut target_distance_increasing = false;
-
-        let tti = self.seconds_to_intercept();
-        debug!("time to intercept: {}", tti);
-
-        if contact_future_distance > contact_distance {
-            // target moving relatively away
-            debug!("target distance increasing!");
-            target_distance_increasing = true;
-        } else {
-            // target moving relatively closer
-            debug!("target distance decreasing!");
-            target_distance_increasing = false;
-        }
-
-        let unit_dir = contact_direction.normalize();
-
-        let relative_quadrant = contact_position.get_relative_quadrant(position_fixed());
-        debug!("target in relative quadrant {:?}!", relative_quadrant);
-
-        let closing_speed = self.target.as_ref().unwrap().as_ref().borrow().get_closing_speed_to_target();
-
-        debug!("closing speed: {}", closing_speed);
-
-        let time_to_stop: f64 = velocity().length() / max_forward_acceleration();
-        debug!("time to stop: {}", time_to_stop);
-        debug!("time to stop in ticks: {}", (time_to_stop * 60.0).ceil());
-
-        let normal_vec_y = Vec2::new(-contact_direction.x, contact_direction.y);
-        let normal_vec_x = Vec2::new(contact_direction.x, -contact_direction.y);
- 
-        draw_line(position(), normal_vec_y, 0xff0000);
-        draw_line(position(), normal_vec_x, 0xff0000);
-
-        if time_to_stop < tti {
-            // time to stop less than time to intercept, keep going!
-            // handle fighter moves based on distance to target
-            // current best ranges seem to be [0, 500], [500, 1000], [1000, +]
-            if contact_distance < 500.0 {
-                // close to target, just float, probably needs to be smarter here
-                if target_distance_increasing {
-                    accelerate(10.0 * unit_dir);
-                } else {
-                    accelerate(-10.0 * unit_dir);
-                }
-            } else if contact_distance > 500.0 && contact_distance < 1000.0 {
-                // attempts to match contact motion for combat engagement
-                accelerate(10.0 * (contact_velocity));
-            } else if contact_distance > 1000.0 {
-                // refactored math from target_position - position to pre-calc'd variable of the same
-                // need to change to a unit vector in the direction of the target to accelerate
-                // back into optimal combat range
-                accelerate(100.0 * unit_dir);
+                Ok(())
             }
-        } else {
-            // need to figure out how to slow down here
-            accelerate(-velocity());
         }
-    }
-}
 
-impl Fighter {
-    pub fn new() -> Self {
-        Fighter {
-            target_lock: false,
-            target: None,
-            state: ShipState::NoTarget,
-            radio: Radio {
-                current_channel: CHANNEL_SANCHEZ,
-            },
-            radar: Radar {
-                state: RadarState::MediumRange,
-                beam: RadarBeam::Standard,
-                name: "fighter_radar".to_string(),
-                designated_target: None,
-                ticks_since_contact: 0,
-                potential_targets: HashMap::new(),
-                id_gen: 0,
-            },
-            sticky_target_ticks: STICKY_TARGET_TICKS,
-            rotation: Rotator {
-                estimated_ticks_to_angle: 0,
-                throttle: 0.0,
-            },
+        pub fn standoff(&self) -> f64 {
+            (self.standoff_min + self.standoff_max) / 2.0
         }
     }
-    
-    pub fn set_state(&mut self, state: ShipState) {
-        self.state = state;
-    }
 
-    pub fn get_state(&self) -> &ShipState {
-        &self.state
+    #[derive(Clone, Copy)]
+    pub struct RadarRanges {
+        pub short: f64,
+        pub medium: f64,
+        pub long: f64,
+        pub min: f64,
     }
 
-    // TODO: improve no_target operations
-    // no_target might be the ship initializer
-    pub fn no_target(&mut self) {
-        // pick a random initial vector
-        let dir: Vec2 = Vec2::new(rand(-1.0, 1.0), rand(-1.0, 1.0));
-        debug!("random dir: {}", dir);
-        let mag = 42.20;
-        accelerate(dir * mag);
+    // width per beam class, rad
+    #[derive(Clone, Copy)]
+    pub struct BeamWidths {
+        pub focused: f64,
+        pub narrow: f64,
+        pub standard: f64,
+        pub wide: f64,
     }
 
-    pub fn searching_for_target(&mut self) {
-        debug!("searching for target");
+    pub const DEFAULT_BEAM_WIDTHS: BeamWidths = BeamWidths { focused: PI / 32.0, narrow: PI / 8.0, standard: PI / 4.0, wide: PI / 2.0 };
 
-        // look for a target
+    impl BeamWidths {
+        pub fn width(&self, beam: RadarBeam) -> f64 {
+            match beam {
+                RadarBeam::Focused => self.focused,
+                RadarBeam::Narrow => self.narrow,
+                RadarBeam::Standard => self.standard,
+                RadarBeam::Wide => self.wide,
+            }
+        }
     }
 
-    pub fn engaging_target(&mut self) {
-        debug!("engaging target");
+    // how one class runs its radar, everything the sweeps and the track store read
+    // that isn't shared by every class
+    #[derive(Clone, Copy)]
+    pub struct RadarProfile {
+        // max distance per sweep state, and min distance for all of them, m
+        pub ranges: RadarRanges,
 
-        // TODO:
-        if self.target.is_some() {
-            self.basic_maneuver_to_target();
-            self.engage_target();
-        }
+        pub beam_widths: BeamWidths,
 
-        // TODO: broken stuff below
-        // if self.get_target_distance() > 1000.0 {
-        //     debug!("maximum course to target");
-        //     // target is pretty far, fly course to target at max thrust
-        //     let ticks_to_intercept = self.ticks_to_intercept();
-        //     debug!("ticks to intercept: {}", ticks_to_intercept);
-        //     let velocity_in_ticks = velocity() / 60.0;
-        //     debug!("velocity in ticks: {}", velocity_in_ticks);
+        // beam class and sweep state the radar starts in
+        pub initial_beam: RadarBeam,
+        pub initial_state: RadarState,
 
-        //     // gets stopping ticks assuming main thrusters are used to slow down
-        //     let minimum_stopping_time = velocity_in_ticks.length() / (max_forward_acceleration() / 60.0);
+        // ticks without a plot before a track is dropped
+        pub track_expiry_ticks: u32,
 
-        //     // gets stopping ticks assuming reverse thrusters are used to slow down
-        //     let maximum_stopping_time = velocity_in_ticks.length() / (max_backward_acceleration() / 60.0);
-        //     debug!("minimum stopping time: {}", minimum_stopping_time);
-        //     if minimum_stopping_time <= ticks_to_intercept && minimum_stopping_time != 0.0 {
-        //         debug!("BRAKING!");
-        //         // hit earliest time to brake
+        // initial association gate size for new tracks, m
+        pub gate_width: f64,
 
-        //         // flip 180*
-        //         turn(-heading());
+        // ticks with no contact before medium range sweeps escalate to long range,
+        // 0 never escalates
+        pub escalate_after_ticks: u32,
 
-        //         // get inverse velocity
-        //         let inverse_velocity = -velocity();
-        //         // scalar inverse_velocity by max forward acceleration
-        //         let max_deceleration_velocity = inverse_velocity * max_forward_acceleration();
+        // ticks the beam sits on one heading before a sweep steps it on
+        pub dwell_ticks: u32,
 
-        //         // maximum thrusters in opposite direction
-        //         accelerate(max_deceleration_velocity);
-        //     } else {
-        //         debug!("heading to target");
-        //         let lead = self.get_target_lead_in_ticks(self.get_target_position(), self.get_target_velocity());
-        //         self.heading_to_target(lead);
-        //         accelerate(self.get_target_direction() * max_forward_acceleration());
-        //     }
-        // } else if self.get_target_distance() > 500.0 {
-        //     // target in firing range, but still pretty far
-        // } else {
-        //     // close quarters combat
-        //     // TODO: orbit calculations, maintain distance, get normal vector of target position
-        //     self.basic_maneuver_to_target();
-        //     self.engage_target();
-        // }
+        // half width of the sector searched first around a heading hint such as the
+        // launcher's facing, rad
+        pub search_sector_half_width: f64,
     }
 
-    pub fn out_of_range_target(&mut self) {
-        debug!("target out of range, maneuver closer!");
-
-        // fly ship to target
+    impl RadarProfile {
+        pub fn for_class(class: Class) -> Self {
+            let standard = RadarProfile {
+                ranges: RadarRanges { short: 10_000.0, medium: 50_000.0, long: 1_000_000.0, min: 25.0 },
+                beam_widths: DEFAULT_BEAM_WIDTHS,
+                initial_beam: RadarBeam::Standard,
+                initial_state: RadarState::MediumRange,
+                track_expiry_ticks: 90,
+                gate_width: 50.0,
+                escalate_after_ticks: SEARCH_ESCALATE_TICKS,
+                dwell_ticks: 1,
+                search_sector_half_width: PI / 2.0,
+            };
+            match class {
+                // nothing past the fuel range is worth a beam, and a target gone quiet
+                // for a third of a second has usually dodged out of the track
+                Class::Missile | Class::Torpedo => RadarProfile {
+                    ranges: RadarRanges { short: 5_000.0, medium: MISSILE_RADAR_RANGE, long: MISSILE_RADAR_RANGE, min: 25.0 },
+                    initial_beam: RadarBeam::Wide,
+                    track_expiry_ticks: 20,
+                    escalate_after_ticks: 0,
+                    ..standard
+                },
+                // capital ships look far out first and stay there
+                Class::Frigate => RadarProfile {
+                    initial_beam: RadarBeam::Narrow,
+                    initial_state: RadarState::LongRange,
+                    escalate_after_ticks: 0,
+                    ..standard
+                },
+                Class::Cruiser => RadarProfile {
+                    initial_beam: RadarBeam::Wide,
+                    initial_state: RadarState::LongRange,
+                    escalate_after_ticks: 0,
+                    ..standard
+                },
+                _ => standard,
+            }
+        }
     }
 
-    pub fn out_of_radar_range(&mut self) {
-        debug!("extending radar to maximum distance!");
+    // what a class looks for in a target, each term of target_score runs roughly 0..1
+    // before its weight so the weights compare directly
+    #[derive(Debug, Clone, Copy)]
+    pub struct TargetWeights {
+        // nearer is cheaper to reach and to hit
+        pub distance: f64,
+
+        // coming at us is a threat, and for ordnance a target that isn't opening
+        // is one it can still catch
+        pub closing: f64,
 
-        // fly ship somewhere
+        // hostile missile over fighter over anything else
+        pub class: f64,
+
+        // already on the nose is less turning before the shot
+        pub aspect: f64,
     }
 
-    pub fn ship_control(&mut self) {
-        match self.get_state() {
-            ShipState::NoTarget => self.no_target(),
-            ShipState::Searching => self.searching_for_target(),
-            ShipState::Engaged => self.engaging_target(),
-            ShipState::OutOfTargetRange => self.out_of_range_target(),
-            ShipState::OutOfRadarRange => self.out_of_radar_range(),
+    impl TargetWeights {
+        pub fn for_class(class: Class) -> Self {
+            match class {
+                // fighters fight whatever is fighting them
+                Class::Fighter => TargetWeights { distance: 1.0, closing: 1.0, class: 1.5, aspect: 0.5 },
+                // ordnance only cares whether it gets there
+                Class::Missile | Class::Torpedo => TargetWeights { distance: 2.0, closing: 1.0, class: 0.0, aspect: 0.5 },
+                // turrets don't care about the nose
+                _ => TargetWeights { distance: 1.0, closing: 0.5, class: 1.0, aspect: 0.0 },
+            }
         }
     }
+}
 
-    pub fn snap_to_heading(&mut self, angle: f64) {
-        debug!("heading: {}", heading());
-        debug!("target angle: {}", angle);
-        let delta = angle_diff(heading(), angle);
+// radio: the typed messages, their [f64; 4] codec and channel bookkeeping
+// interface is Radio::enqueue/flush/poll and the RadioMessage variants
+mod comms {
+    use super::*;
 
-        // amount of radians for max acceleration
-        let halfway = delta / 2.0;
+    pub struct Radio {
+        // current radio channel
+        current_channel: u8,
 
-        debug!("delta angle: {}", delta);
+        // latest decoded message of each kind and the tick it arrived, delivery isn't
+        // guaranteed so consumers should check the age before trusting it
+        latest: [Option<(u32, RadioMessage)>; RADIO_KIND_COUNT],
 
-        let max_angular_acceleration_ticks = max_angular_acceleration() / 60.0;
-        debug!("max angular acceleration: {}", max_angular_acceleration());
-        debug!("max angular acceleration in ticks: {}", max_angular_acceleration_ticks);
+        // waiting for the once per tick send, see schedule
+        queue: Vec<QueuedMessage>,
 
-        // set latest estimate for updated heading
-        self.rotation.estimated_ticks_to_angle = (delta.abs() / max_angular_acceleration_ticks).ceil() as u32;
-        debug!("estimated ticks to angle: {}", self.rotation.estimated_ticks_to_angle);
-        let future_heading: f64 = 0.0;
+        // fewest ticks between low priority sends of each kind, and when each was last sent
+        intervals: [u32; RADIO_KIND_COUNT],
+        last_sent: [Option<u32>; RADIO_KIND_COUNT],
 
-        // latest ticks to stop
-        let ticks_to_stop = ((angular_velocity().abs() / 60.0) / (max_angular_acceleration() / 60.0)).ceil();
+        // who we are in contact reports, see radio_id_from_spawn
+        pub id: u8,
+    }
 
-        if ticks_to_stop < self.rotation.estimated_ticks_to_angle as f64 {
-            self.rotation.throttle = 1.0 * delta.signum();
-        } else {
-            self.rotation.throttle = -1.0 * delta.signum();
-        }
-
-        // note: this correctly defines future heading per tick, so far so good
-        // let future_heading = heading() + (angular_velocity() / 60.0);
-        // debug!("future heading: {}", future_heading);
+    // wire protocol carried in the [f64; 4] radio payload
+    // slot 0: message tag
+    // slot 1: packed vec2 (position or offset)
+    // slot 2: packed vec2 (velocity, or target position for heartbeats)
+    // slot 3: extra scalar (((salvo index * 16 + override flags) * 8192 + launcher approach
+    //         bearing in mrad) * 8192 + intercept bearing in mrad,
+    //         class code + 16 * (sender + 256 * age in ticks), id, heading + PI + 10 if engaged,
+    //         sender + 256 * ticks for strike offers and proposals,
+    //         or sender + 256 * strength in thousandths for target claims)
+    // vec2s are quantized to RADIO_RESOLUTION and packed as two 26 bit integers so
+    // every slot is an integer that f64 represents exactly. positions are clamped
+    // onto the map on the way in, nothing off it is worth acting on
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum RadioMessage {
+        TargetHandoff { position: Vec2, velocity: Vec2, salvo: u32, bearing: f64, overrides: u8, approach: f64, sender: u8 },
+        ContactReport { position: Vec2, velocity: Vec2, class: Class, age: u32, sender: u8 },
+        Ack { id: u32 },
+        FormationSlot { offset: Vec2 },
+        TerminalOn { position: Vec2 },
+        FormationLead { position: Vec2, velocity: Vec2, heading: f64, engaged: bool },
+        CapitalBeacon { position: Vec2, velocity: Vec2 },
+        MissileHeartbeat { position: Vec2, target: Vec2, salvo: u32 },
+        StrikeOffer { target: Vec2, flight_ticks: u32, sender: u8 },
+        StrikeProposal { target: Vec2, arrival_tick: u32, sender: u8 },
+        TargetClaim { target: Vec2, strength: f64, sender: u8 },
+    }
 
-        debug!("angular velocity: {}", angular_velocity());
-
-        // if delta > 0.0 {
-        //     // positive delta, turning ccw
-
-        //     if future_heading < angle {
-        //         // future heading will be beyond target angle
-        //         self.rotational_throttle = 1.0; // counter throttle
-        //     } else {
-        //         self.rotational_throttle = -1.0;
-        //     }
-        // } else {
-        //     // negative delta, turning cw
-        //     if future_heading > angle {
-        //         // future heading will be beyond target angle
-        //         self.rotational_throttle = -1.0; // counter throttle
-        //     } else {
-        //         self.rotational_throttle = 1.0; // counter throttle
-        //     }
-        // }
-        // e.g. delta => -0.14 radians
-        // current_velocity in radians per second per second per tick, e.g. => 0.0
-        // throttle => -1.0
-        // 
+    // message type without the payload, indexes the per-kind caches and counters
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum RadioKind {
+        TargetHandoff,
+        ContactReport,
+        Ack,
+        FormationSlot,
+        TerminalOn,
+        FormationLead,
+        CapitalBeacon,
+        MissileHeartbeat,
+        StrikeOffer,
+        StrikeProposal,
+        TargetClaim,
+    }
 
-        // fastest possible snap to heading
-        // count number of ticks to rotate to target
-            // based on current angular velocity
-        // find number of ticks to stop on target at max deceleration
+    pub const RADIO_KIND_COUNT: usize = 11;
 
-        torque(self.rotation.throttle * max_angular_acceleration());
+    // the highest queued priority goes out first, ties go to the oldest
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum RadioPriority {
+        Low,
+        Normal,
+        High,
     }
 
-    pub fn tick(&mut self) {
-        // uncomment for squadron tutorial 11 for wall of bullets
-        // TODO: figure out how to use this strategically
-        // fire(0);
-        self.radar.radar_loop();
-        self.ship_control();
-        if self.radar.has_contacts() {
-            match self.get_state() {
-                ShipState::Engaged => { () },
-                _ => { self.set_state(ShipState::Engaged); }
-            }
-            
-            if self.sticky_target_ticks > 0 {
-                debug!("sticky ticks remaining: {}", self.sticky_target_ticks);
-                self.sticky_target_ticks -= 1;
-            } else {
-                debug!("setting new target");
-                self.sticky_target_ticks = STICKY_TARGET_TICKS;
-                debug!("setting latest target values");
-                let id = self.radar.get_closest_target_to_point(position_fixed());
-                let track = self.radar.get_track(id);
-                self.set_current_target(track);
-            }
-        }
+    struct QueuedMessage {
+        message: RadioMessage,
+        priority: RadioPriority,
+        queued_tick: u32,
+
+        // last tick it's still worth sending
+        deadline: u32,
     }
-}
 
-//**************************************************************************
-// Slightly more solidified "library" code below
-//**************************************************************************
+    // what the scheduler did with the channel this tick, for the stats collector
+    #[derive(Debug, Default)]
+    pub struct RadioTraffic {
+        pub sent: Option<RadioKind>,
+        pub dropped: Vec<RadioKind>,
+    }
 
-#[derive(Debug)]
-enum Quadrant {One = 1,Two = 2,Three = 3,Four = 4,}
+    pub const RADIO_TAG_TARGET_HANDOFF: f64 = 1.0;
 
-trait UnitCircleQuadrant {
-    fn get_quadrant(&self) -> Quadrant;
-    fn get_relative_quadrant(&self, other: Vec2) -> Quadrant;
-}
+    pub const RADIO_TAG_CONTACT_REPORT: f64 = 2.0;
 
-impl UnitCircleQuadrant for Vec2 {
-    // returns the quadrant that SELF is in
-    fn get_quadrant(&self) -> Quadrant {
-        if self.x > 0.0 && self.y > 0.0 { Quadrant::One }
-        else if self.x < 0.0 && self.y > 0.0 { Quadrant::Two }
-        else if self.x < 0.0 && self.y < 0.0 { Quadrant::Three }
-        else { Quadrant::Four }
-    }
+    pub const RADIO_TAG_ACK: f64 = 3.0;
 
-    // returns relative quadrant of SELF compared to OTHER
-    // i.e. target_position.get_relative_quadrant(ship.position)
-    fn get_relative_quadrant(&self, other: Vec2) -> Quadrant {
-        if self.x > other.x && self.y > other.y { Quadrant::One }
-        else if self.x < other.x && self.y > other.y { Quadrant::Two }
-        else if self.x < other.x && self.y < other.y { Quadrant::Three }
-        else { Quadrant::Four }
-    }
-}
+    pub const RADIO_TAG_FORMATION_SLOT: f64 = 4.0;
 
-// magic "PID" function to get an angular acceleration scalar towards target angle
-// used for turning ships with torque() method, does not work for turn()
-fn calculate_angular_velocity(tune_factor: f64, angle_to_mark: f64) -> f64 {
-    let c1: f64 = 2.0 * tune_factor.sqrt();
-    tune_factor * angle_to_mark - c1 * angular_velocity()
-}
+    pub const RADIO_TAG_TERMINAL_ON: f64 = 5.0;
 
-// basic turn helper
-fn turn_to(target_heading: f64) {
-    let heading_error = angle_diff(heading(), target_heading);
-    turn(10.0 * heading_error);
-}
+    pub const RADIO_TAG_FORMATION_LEAD: f64 = 6.0;
 
-// returns a leading vec2 with coords one velocity tick behind target position
-fn get_target_lag_in_ticks(target_position: Vec2, target_velocity: Vec2) -> Vec2 {
-    let delta_position = target_position - position_fixed();
-    let delta_velocity = (target_velocity - velocity()) / 60.0; // divide down to ticks
-    delta_position - delta_velocity * delta_position.length() / (BULLET_SPEED / 60.0).ceil()
-}
+    pub const RADIO_TAG_CAPITAL_BEACON: f64 = 7.0;
 
-// returns a leading vec2 with coords one velocity tick ahead of target position
-fn get_target_lead_in_ticks(target_position: Vec2, target_velocity: Vec2) -> Vec2 {
-    let delta_position = target_position - position_fixed();
-    let delta_velocity = (target_velocity - velocity()) / 60.0; // divide down to ticks
-    delta_position + delta_velocity * delta_position.length() / (BULLET_SPEED / 60.0).ceil()
-}
+    pub const RADIO_TAG_MISSILE_HEARTBEAT: f64 = 8.0;
 
-// discriminant is the part under the sqrt when solved for x
-// d = b^2-4ac
-fn get_smallest_quadratic_solution(a: f64, b: f64, c: f64) -> f64 {
-    let discriminant: f64 = (b * b) - (4.0 * a * c);
-
-    if discriminant < 0.0 {
-        return -1.0;
-    }
-
-    // solved for x: x = (-b +/- sqrt(b^2-4ac)) / 2a
-    // getting one or both x solutions from above
-    // only the positive solutions are useful
-    let s: f64 = discriminant.sqrt();
-    let x2: f64 = (-b + s) / (2.0 * a);
-    let x1: f64 = (-b - s) / (2.0 * a);
-    if x2 > 0.0 && x1 > 0.0 {
-        return x2.min(x1);
-    } else if x1 > 0.0 {
-        return x1;
-    } else if x2 > 0.0 {
-        return x2;
-    }
-    return -1.0; //no positive solution
-}
+    pub const RADIO_TAG_STRIKE_OFFER: f64 = 9.0;
 
-// uses quadratic math from available info to produce useful lead vector
-// remember, quadratic formula is: ax^2+bx+c=0
-// solved for x: x = (-b +/- sqrt(b^2-4ac)) / 2a
-fn quadratic_lead(target_position: Vec2, target_velocity: Vec2) -> Vec2 {
-    let a: f64 = target_velocity.dot(target_velocity) - (BULLET_SPEED * BULLET_SPEED);
-    let b: f64 = 2.0 * target_position.dot(target_velocity);
-    let c: f64 = target_position.dot(target_position);
+    pub const RADIO_TAG_STRIKE_PROPOSAL: f64 = 10.0;
 
-    let t: f64 = get_smallest_quadratic_solution(a, b, c);
-    if t <= 0.0 {
-        return get_target_lead_in_ticks(target_position, target_velocity);
-        // return position_fixed();
-    }
-    return target_position + t * target_velocity;
-}
+    pub const RADIO_TAG_TARGET_CLAIM: f64 = 11.0;
 
-// rotating adjusts base position(), this fixes that
-fn position_fixed() -> Vec2 {
-    position() - vec2(1.0, 0.0).rotate(heading()) * 1.33333333
-}
+    // claim strengths go out in thousandths
+    pub const CLAIM_STRENGTH_SCALE: f64 = 1000.0;
 
-fn iterative_approximation_gun(target_position: Vec2, target_velocity: Vec2) -> Vec2 {
-    let mut t: f64 = 0.0;
-    let mut iterations = 20;
-    while iterations > 0 {
-        let old_t: f64 = t;
-        t = ((target_position - position_fixed()) + (t * target_velocity)).length() / BULLET_SPEED;
-        if t - old_t < E {
-            break;
-        }
-        iterations = iterations - 1;
-    }
+    // room for a bearing in milliradians below the salvo index in a handoff's slot 3
+    pub const BEARING_MRAD_SPAN: f64 = 8192.0;
 
-    return target_position + (t * target_velocity);
-}
+    // room for the override flags between the sender and the bearings
+    pub const HANDOFF_OVERRIDE_SPAN: f64 = 16.0;
 
-fn get_target_lead(target_position: Vec2, target_velocity: Vec2) -> Vec2 {
-    let delta_position = target_position - position_fixed();
-    let delta_velocity = target_velocity - velocity();
-    let prediction = delta_position + delta_velocity * delta_position.length() / BULLET_SPEED;
-    prediction
-}
+    // two bearings, the flags and the sender leave this much of the 53 exact bits for
+    // the salvo index
+    pub const HANDOFF_MAX_SALVO: u32 = (1 << 15) - 1;
 
-fn get_adjusted_target_lead_in_ticks_gun(target_position: Vec2, target_velocity: Vec2) -> Vec2 {
-    let delta_position = target_position - position_fixed();
-    let delta_velocity = (target_velocity - velocity()) / 60.0; // divide down to ticks
-    let bullet_delta = BULLET_SPEED - target_velocity;
-    delta_position + delta_velocity * delta_position.length() / (bullet_delta / 60.0)
-}
+    // handoff override flags, the launcher's say on how the ordnance flies this target
+    pub const HANDOFF_NO_WEAVE: u8 = 1; // no weave anywhere in the flight
+
+    pub const HANDOFF_STRAIGHT_TERMINAL: u8 = 2; // plain PN once terminal, even if the profile weaves there
 
-// TODO: missile seek method
-fn seek(p: Vec2, v: Vec2) {
-    let dp = p - position();
-    let dv = v - velocity();
-    let closing_speed = -(dp.y * dv.y - dp.x * dv.x).abs() / dp.length();
-    let los = dp.angle();
-    let los_rate = (dp.y * dv.x - dp.x * dv.y) / (dp.length() * dp.length());
-
-    const N: f64 = 4.0;
-    let a = vec2(100.0, N * closing_speed * los_rate).rotate(los);
-    let a = vec2(400.0, 0.0).rotate(a.angle());
-    accelerate(a);
-    turn_to(a.angle());
+    pub const HANDOFF_FULL_BURN: u8 = 4; // no midcourse throttling and no terminal fuel reserve
+
+    impl RadioMessage {
+        // the same message held back for ticks before going out, only a contact
+        // report says how old it is
+        pub fn aged(self, ticks: u32) -> RadioMessage {
+            match self {
+                RadioMessage::ContactReport { position, velocity, class, age, sender } =>
+                    RadioMessage::ContactReport { position, velocity, class, age: age + ticks, sender },
+                other => other,
+            }
+        }
+
+        pub fn kind(&self) -> RadioKind {
+            match self {
+                RadioMessage::TargetHandoff { .. } => RadioKind::TargetHandoff,
+                RadioMessage::ContactReport { .. } => RadioKind::ContactReport,
+                RadioMessage::Ack { .. } => RadioKind::Ack,
+                RadioMessage::FormationSlot { .. } => RadioKind::FormationSlot,
+                RadioMessage::TerminalOn { .. } => RadioKind::TerminalOn,
+                RadioMessage::FormationLead { .. } => RadioKind::FormationLead,
+                RadioMessage::CapitalBeacon { .. } => RadioKind::CapitalBeacon,
+                RadioMessage::MissileHeartbeat { .. } => RadioKind::MissileHeartbeat,
+                RadioMessage::StrikeOffer { .. } => RadioKind::StrikeOffer,
+                RadioMessage::StrikeProposal { .. } => RadioKind::StrikeProposal,
+                RadioMessage::TargetClaim { .. } => RadioKind::TargetClaim,
+            }
+        }
+
+        pub fn encode(&self) -> [f64; 4] {
+            match self {
+                RadioMessage::TargetHandoff { position, velocity, salvo, bearing, overrides, approach, sender } => {
+                    let launch = (*salvo).min(HANDOFF_MAX_SALVO) as f64 * 256.0 + *sender as f64;
+                    let header = launch * HANDOFF_OVERRIDE_SPAN + (*overrides as f64).min(HANDOFF_OVERRIDE_SPAN - 1.0);
+                    let bearings = angle_to_mrad(*approach) * BEARING_MRAD_SPAN + angle_to_mrad(*bearing);
+                    [RADIO_TAG_TARGET_HANDOFF, pack_vec2(*position), pack_vec2(*velocity), header * BEARING_MRAD_SPAN * BEARING_MRAD_SPAN + bearings]
+                },
+                RadioMessage::ContactReport { position, velocity, class, age, sender } => {
+                    let header = *sender as f64 + 256.0 * *age as f64;
+                    [RADIO_TAG_CONTACT_REPORT, pack_vec2(*position), pack_vec2(*velocity), class_to_code(*class) + 16.0 * header]
+                },
+                RadioMessage::Ack { id } => {
+                    [RADIO_TAG_ACK, 0.0, 0.0, *id as f64]
+                },
+                RadioMessage::FormationSlot { offset } => {
+                    [RADIO_TAG_FORMATION_SLOT, pack_vec2(*offset), 0.0, 0.0]
+                },
+                RadioMessage::TerminalOn { position } => {
+                    [RADIO_TAG_TERMINAL_ON, pack_vec2(*position), 0.0, 0.0]
+                },
+                RadioMessage::FormationLead { position, velocity, heading, engaged } => {
+                    let engaged_flag = if *engaged { 10.0 } else { 0.0 };
+                    [RADIO_TAG_FORMATION_LEAD, pack_vec2(*position), pack_vec2(*velocity), heading.rem_euclid(TAU) + engaged_flag]
+                },
+                RadioMessage::CapitalBeacon { position, velocity } => {
+                    [RADIO_TAG_CAPITAL_BEACON, pack_vec2(*position), pack_vec2(*velocity), 0.0]
+                },
+                RadioMessage::MissileHeartbeat { position, target, salvo } => {
+                    [RADIO_TAG_MISSILE_HEARTBEAT, pack_vec2(*position), pack_vec2(*target), *salvo as f64]
+                },
+                RadioMessage::StrikeOffer { target, flight_ticks, sender } => {
+                    [RADIO_TAG_STRIKE_OFFER, pack_vec2(*target), 0.0, *sender as f64 + 256.0 * *flight_ticks as f64]
+                },
+                RadioMessage::StrikeProposal { target, arrival_tick, sender } => {
+                    [RADIO_TAG_STRIKE_PROPOSAL, pack_vec2(*target), 0.0, *sender as f64 + 256.0 * *arrival_tick as f64]
+                },
+                RadioMessage::TargetClaim { target, strength, sender } => {
+                    let strength = (strength.max(0.0) * CLAIM_STRENGTH_SCALE).round().min(u32::MAX as f64);
+                    [RADIO_TAG_TARGET_CLAIM, pack_vec2(*target), 0.0, *sender as f64 + 256.0 * strength]
+                },
+            }
+        }
+
+        // returns None for anything that isn't a well formed message from us
+        pub fn decode(payload: [f64; 4]) -> Option<RadioMessage> {
+            if payload.iter().any(|v| !v.is_finite()) {
+                return None;
+            }
+            if payload[0] == RADIO_TAG_TARGET_HANDOFF {
+                if payload[3] < 0.0 || payload[3] >= (1u64 << 53) as f64 {
+                    return None;
+                }
+                let upper = (payload[3] / BEARING_MRAD_SPAN).floor();
+                let header = (upper / BEARING_MRAD_SPAN).floor();
+                let launch = (header / HANDOFF_OVERRIDE_SPAN).floor();
+                Some(RadioMessage::TargetHandoff {
+                    position: unpack_position(payload[1])?,
+                    velocity: unpack_vec2(payload[2])?,
+                    salvo: (launch / 256.0).floor() as u32,
+                    bearing: (payload[3] % BEARING_MRAD_SPAN) / 1000.0,
+                    overrides: (header % HANDOFF_OVERRIDE_SPAN) as u8,
+                    approach: (upper % BEARING_MRAD_SPAN) / 1000.0,
+                    sender: (launch % 256.0) as u8,
+                })
+            } else if payload[0] == RADIO_TAG_CONTACT_REPORT {
+                if payload[3] < 0.0 || payload[3] > 16.0 * 256.0 * u32::MAX as f64 {
+                    return None;
+                }
+                let header = (payload[3] / 16.0).floor();
+                Some(RadioMessage::ContactReport {
+                    position: unpack_position(payload[1])?,
+                    velocity: unpack_vec2(payload[2])?,
+                    class: code_to_class(payload[3] % 16.0)?,
+                    age: (header / 256.0).floor() as u32,
+                    sender: (header % 256.0) as u8,
+                })
+            } else if payload[0] == RADIO_TAG_ACK {
+                if payload[3] < 0.0 || payload[3] > u32::MAX as f64 {
+                    return None;
+                }
+                Some(RadioMessage::Ack { id: payload[3] as u32 })
+            } else if payload[0] == RADIO_TAG_FORMATION_SLOT {
+                Some(RadioMessage::FormationSlot { offset: unpack_vec2(payload[1])? })
+            } else if payload[0] == RADIO_TAG_TERMINAL_ON {
+                Some(RadioMessage::TerminalOn { position: unpack_position(payload[1])? })
+            } else if payload[0] == RADIO_TAG_FORMATION_LEAD {
+                if payload[3] < 0.0 || payload[3] >= 10.0 + TAU {
+                    return None;
+                }
+                Some(RadioMessage::FormationLead {
+                    position: unpack_position(payload[1])?,
+                    velocity: unpack_vec2(payload[2])?,
+                    heading: payload[3] % 10.0,
+                    engaged: payload[3] >= 10.0,
+                })
+            } else if payload[0] == RADIO_TAG_CAPITAL_BEACON {
+                Some(RadioMessage::CapitalBeacon { position: unpack_position(payload[1])?, velocity: unpack_vec2(payload[2])? })
+            } else if payload[0] == RADIO_TAG_MISSILE_HEARTBEAT {
+                if payload[3] < 0.0 || payload[3] > u32::MAX as f64 {
+                    return None;
+                }
+                Some(RadioMessage::MissileHeartbeat {
+                    position: unpack_position(payload[1])?,
+                    target: unpack_position(payload[2])?,
+                    salvo: payload[3] as u32,
+                })
+            } else if payload[0] == RADIO_TAG_STRIKE_OFFER || payload[0] == RADIO_TAG_STRIKE_PROPOSAL {
+                if payload[3] < 0.0 || payload[3] > 256.0 * u32::MAX as f64 {
+                    return None;
+                }
+                let target = unpack_position(payload[1])?;
+                let sender = (payload[3] % 256.0) as u8;
+                let ticks = (payload[3] / 256.0).floor() as u32;
+                if payload[0] == RADIO_TAG_STRIKE_OFFER {
+                    Some(RadioMessage::StrikeOffer { target, flight_ticks: ticks, sender })
+                } else {
+                    Some(RadioMessage::StrikeProposal { target, arrival_tick: ticks, sender })
+                }
+            } else if payload[0] == RADIO_TAG_TARGET_CLAIM {
+                if payload[3] < 0.0 || payload[3] > 256.0 * u32::MAX as f64 {
+                    return None;
+                }
+                Some(RadioMessage::TargetClaim {
+                    target: unpack_position(payload[1])?,
+                    strength: (payload[3] / 256.0).floor() / CLAIM_STRENGTH_SCALE,
+                    sender: (payload[3] % 256.0) as u8,
+                })
+            } else {
+                None
+            }
+        }
+    }
+
+    impl Radio {
+        pub fn new(channel: u8) -> Self {
+            Radio {
+                current_channel: channel,
+                latest: [None; RADIO_KIND_COUNT],
+                queue: Vec::new(),
+                intervals: [0; RADIO_KIND_COUNT],
+                last_sent: [None; RADIO_KIND_COUNT],
+                id: radio_id_from_spawn(),
+            }
+        }
+
+        pub fn set_channel(&mut self, channel: u8) {
+            self.current_channel = channel;
+        }
+
+        pub fn set_interval(&mut self, kind: RadioKind, ticks: u32) {
+            self.intervals[kind as usize] = ticks;
+        }
+
+        // queues a message that's worth sending for deadline more ticks, a queued
+        // message of the same kind is replaced, except contact reports which each
+        // carry a different contact
+        pub fn enqueue(&mut self, message: RadioMessage, priority: RadioPriority, deadline: u32) {
+            let now = current_tick();
+            let kind = message.kind();
+            if kind != RadioKind::ContactReport {
+                self.queue.retain(|q| q.message.kind() != kind);
+            }
+            self.queue.push(QueuedMessage { message, priority, queued_tick: now, deadline: now + deadline });
+        }
+
+        // drops what went stale and picks this tick's message: highest priority, then
+        // oldest, with low priority kinds held back until their interval has passed
+        pub fn schedule(&mut self, now: u32) -> (Option<RadioMessage>, RadioTraffic) {
+            let mut traffic = RadioTraffic::default();
+            self.queue.retain(|q| {
+                if now > q.deadline {
+                    traffic.dropped.push(q.message.kind());
+                    return false;
+                }
+                true
+            });
+
+            let (intervals, last_sent) = (&self.intervals, &self.last_sent);
+            let ready = |q: &QueuedMessage| {
+                let kind = q.message.kind() as usize;
+                q.priority > RadioPriority::Low || last_sent[kind].is_none_or(|tick| now >= tick + intervals[kind])
+            };
+            let pick = self.queue.iter().enumerate()
+                .filter(|(_, q)| ready(q))
+                .min_by(|(_, a), (_, b)| b.priority.cmp(&a.priority).then(a.queued_tick.cmp(&b.queued_tick)))
+                .map(|(i, _)| i);
+            // a report that sat in the queue is that much older than when it was stamped
+            let message = pick.map(|i| {
+                let queued = self.queue.remove(i);
+                queued.message.aged(now.saturating_sub(queued.queued_tick))
+            });
+            if let Some(m) = message {
+                self.last_sent[m.kind() as usize] = Some(now);
+                traffic.sent = Some(m.kind());
+            }
+            (message, traffic)
+        }
+
+        // the radio only carries one message per tick, call once at the end of the
+        // tick after everything has queued
+        pub fn flush(&mut self) -> RadioTraffic {
+            let (message, traffic) = self.schedule(current_tick());
+            if let Some(m) = message {
+                trace!("radio sending on channel {}: {:?}", self.current_channel, m);
+                set_radio_channel(self.current_channel as usize);
+                send(m.encode());
+            }
+            if !traffic.dropped.is_empty() {
+                trace!("radio dropped stale {:?}", traffic.dropped);
+            }
+            traffic
+        }
+
+        // polls the current channel, keeps the last good message with its arrival tick
+        pub fn poll(&mut self) -> Option<RadioMessage> {
+            set_radio_channel(self.current_channel as usize);
+            let message = receive().and_then(RadioMessage::decode);
+            if let Some(m) = message {
+                trace!("radio received on channel {}: {:?}", self.current_channel, m);
+                self.latest[m.kind() as usize] = Some((current_tick(), m));
+            }
+            message
+        }
+
+        // newest message of a kind with its arrival tick, None if none has arrived
+        pub fn latest(&self, kind: RadioKind) -> Option<(u32, RadioMessage)> {
+            self.latest[kind as usize]
+        }
+
+        // ticks since the last good message, None if nothing has ever arrived
+        pub fn message_age(&self) -> Option<u32> {
+            self.latest.iter().flatten().map(|(tick, _)| current_tick() - tick).min()
+        }
+    }
+
+    // 1..=255 from the spawn position and class, two ships sharing one only makes
+    // their reports look like they came from the same teammate
+    pub fn radio_id_from_spawn() -> u8 {
+        let seed = pack_vec2(position()).to_bits() ^ class_to_code(class()) as u64;
+        (splitmix64(seed) % 255 + 1) as u8
+    }
+
+    // the squadron channel, contact reports spaced out so the burst doesn't crowd
+    // out beacons and handoffs
+    pub fn fighter_radio() -> Radio {
+        let mut radio = Radio::new(CHANNEL_SANCHEZ);
+        radio.set_interval(RadioKind::ContactReport, CONTACT_REPORT_SPACING);
+        radio.set_interval(RadioKind::TargetClaim, CLAIM_INTERVAL);
+        radio
+    }
+
+    // launch slot n listens on its own channel so handoffs don't collide
+    pub fn missile_channel(launch_index: u32) -> u8 {
+        MISSILE_CHANNEL_BASE + (launch_index % MISSILE_CHANNEL_COUNT as u32) as u8
+    }
+
+    // [0, TAU) in whole milliradians, fits under BEARING_MRAD_SPAN
+    pub fn angle_to_mrad(angle: f64) -> f64 {
+        (angle.rem_euclid(TAU) * 1000.0).round().min(BEARING_MRAD_SPAN - 1.0)
+    }
+
+    // two signed components quantized into one exactly representable integer
+    pub fn pack_vec2(v: Vec2) -> f64 {
+        let offset: f64 = (1u64 << 25) as f64;
+        let max: f64 = ((1u64 << 26) - 1) as f64;
+        let qx = ((v.x / RADIO_RESOLUTION).round() + offset).clamp(0.0, max);
+        let qy = ((v.y / RADIO_RESOLUTION).round() + offset).clamp(0.0, max);
+        qx * (1u64 << 26) as f64 + qy
+    }
+
+    pub fn unpack_vec2(packed: f64) -> Option<Vec2> {
+        if packed < 0.0 || packed >= (1u64 << 52) as f64 || packed.fract() != 0.0 {
+            return None;
+        }
+        let bits = packed as u64;
+        let offset: f64 = (1u64 << 25) as f64;
+        let qx = (bits >> 26) as f64 - offset;
+        let qy = (bits & ((1u64 << 26) - 1)) as f64 - offset;
+        Some(Vec2::new(qx * RADIO_RESOLUTION, qy * RADIO_RESOLUTION))
+    }
+
+    // a reported position, clamped onto the map
+    pub fn unpack_position(packed: f64) -> Option<Vec2> {
+        let half = world_size() / 2.0;
+        unpack_vec2(packed).map(|v| vec2(v.x.clamp(-half, half), v.y.clamp(-half, half)))
+    }
+
+    pub fn class_to_code(class: Class) -> f64 {
+        match class {
+            Class::Fighter => 1.0,
+            Class::Frigate => 2.0,
+            Class::Cruiser => 3.0,
+            Class::Asteroid => 4.0,
+            Class::Target => 5.0,
+            Class::Missile => 6.0,
+            Class::Torpedo => 7.0,
+            _ => 0.0,
+        }
+    }
+
+    pub fn code_to_class(code: f64) -> Option<Class> {
+        match code as u32 {
+            0 => Some(Class::Unknown),
+            1 => Some(Class::Fighter),
+            2 => Some(Class::Frigate),
+            3 => Some(Class::Cruiser),
+            4 => Some(Class::Asteroid),
+            5 => Some(Class::Target),
+            6 => Some(Class::Missile),
+            7 => Some(Class::Torpedo),
+            _ => None,
+        }
+    }
+}
+
+// sensing: the radar scheduler and the tracks it maintains
+// consumers read the per-tick RadarPicture and talk back by track id, only
+// the tracker mutates tracks
+mod radar {
+    use super::*;
+    pub use gate::*;
+    pub use track::*;
+    pub use tracker::*;
+
+    // association gate around a track's predicted position
+    mod gate {
+        use super::*;
+
+        // ellipse around a track's estimate, width across track and stretched
+        // along the velocity by the distance a speed error covers while coasting
+        #[derive(Debug)]
+        pub struct RadarTrackGate {
+            center: Vec2,
+
+            // full width across track, twice the semi minor axis, m
+            pub width: f64,
+
+            // direction of the major axis, rad
+            heading: f64,
+
+            // half length along heading, never under width / 2, m
+            semi_major: f64,
+        }
+
+        impl RadarTrackGate {
+            pub fn new(point: Vec2, width: f64) -> RadarTrackGate {
+                RadarTrackGate {
+                    center: point,
+                    width,
+                    heading: 0.0,
+                    semi_major: width / 2.0,
+                }
+            }
+
+            pub fn semi_minor(&self) -> f64 {
+                self.width / 2.0
+            }
+
+            pub fn semi_major(&self) -> f64 {
+                self.semi_major.max(self.semi_minor())
+            }
+
+            // point on the edge at angle around the ellipse, from the major axis
+            fn edge_point(&self, angle: f64) -> Vec2 {
+                self.center + vec2(self.semi_major() * angle.cos(), self.semi_minor() * angle.sin()).rotate(self.heading)
+            }
+
+            pub fn draw_gate(&self, id: u128, class: &TrackType, quality: f64) {
+                let step = 2.0 * PI / GATE_DRAW_SEGMENTS as f64;
+                for i in 0..GATE_DRAW_SEGMENTS {
+                    debug_line(LAYER_GATES, self.edge_point(i as f64 * step), self.edge_point((i + 1) as f64 * step), 0xff0000);
+                }
+                let label = self.center + vec2(self.semi_major(), -self.semi_minor());
+                debug_text!(LAYER_GATES, label, 0xff0000, "id: {} {:?} q: {:.2}", id, class, quality);
+            }
+
+            pub fn update_center(&mut self, center: Vec2) {
+                self.center = center;
+            }
+
+            // lines the gate up with velocity and stretches it by the ticks the track
+            // has coasted, a stationary track keeps a circle
+            pub fn update_axis(&mut self, velocity: Vec2, ticks_since_plot: u32) {
+                if velocity.length() > 0.0 {
+                    self.heading = velocity.angle();
+                }
+                self.semi_major = self.semi_minor() + GATE_SPEED_SHARE * velocity.length() * ticks_since_plot as f64 / 60.0;
+            }
+
+            // in the ellipse's frame, normalized radius of at most one, the edge counts
+            // as inside
+            pub fn point_in_gate(&self, point: Vec2) -> bool {
+                let local = (point - self.center).rotate(-self.heading);
+                let (a, b) = (self.semi_major(), self.semi_minor());
+                if a <= 0.0 || b <= 0.0 {
+                    return point == self.center;
+                }
+                (local.x / a).powi(2) + (local.y / b).powi(2) <= 1.0 + 1e-9
+            }
+
+            pub fn update_width(&mut self, width: f64) {
+                self.width = width;
+            }
+        }
+    }
+
+    // one tracked contact: estimate, history, quality and the snapshot consumers read
+    mod track {
+        use super::*;
+
+        // one tick of resolved track state, kept for trend analysis
+        #[derive(Debug, Clone, Copy)]
+        pub struct TrackHistoryEntry {
+            tick: u32,
+            pub position: Vec2,
+            velocity: Vec2,
+        }
+
+        #[derive(Debug)]
+        pub struct TimedScanResult {
+            pub scan: ScanResult,
+            pub tick: u32,
+        }
+
+        // RadarTrack is a specific item formulated by the radar tracker algorithm
+        // it will be used to house revolving data for the object as well as
+        // having useful derived data members
+        #[derive(Debug)]
+        pub struct RadarTrack {
+            // list of scan results tracked for this item
+            // these need to be parsed and filtered
+            pub scans: VecDeque<TimedScanResult>,
+
+            // resolved position estimate
+            pub position: Vec2,
+
+            // resolved velocity estimate
+            pub velocity: Vec2,
+
+            // smoothed finite difference of plot velocities, m/s/s
+            pub acceleration: Vec2,
+
+            // last plot used for the acceleration estimate
+            last_plot_velocity: Vec2,
+            pub last_plot_tick: u32,
+
+            // newest plot folded into the filter, later arrivals from before it are stale
+            last_corrected_tick: Option<u32>,
+
+            // game class reported by the scan, bounds the plausible acceleration
+            pub contact_class: Class,
+
+            // plots and reports in a row that agreed on contact_class
+            pub class_streak: u32,
+
+            // bounded per-tick history of resolved estimates, oldest at the front
+            pub history: VecDeque<TrackHistoryEntry>,
+
+            // whose measurements have fed the estimate
+            pub source: TrackSource,
+
+            // last teammate report folded in, keeps remote only tracks fresh
+            last_report_tick: u32,
+
+            // velocity.y.atan2(velocity.x) in quadrant 1..
+            heading: f64,
+
+            // need to create a uuid
+            pub id: u128,
+
+            // track classification
+            pub class: TrackType,
+
+            // gate is the predicted target fence for position estimates
+            pub gate: RadarTrackGate,
+
+            // plots folded in and remote reports applied so far, a crude confidence
+            pub plot_count: u32,
+
+            // went quiet right as our ordnance arrived, cleared by the next local plot
+            pub probably_dead: bool,
+
+            // a consumer is coasting this track, past expiry it's kept as a ghost until
+            // this tick instead of being deleted
+            pub coast_until_tick: Option<u32>,
+
+            // plots just showed a launch signature, the radar takes it and seeds a missile
+            pub launch_cue: bool,
+            last_launch_tick: Option<u32>,
+
+            // ticks of local plots in the last QUALITY_PLOT_WINDOW
+            recent_plots: VecDeque<u32>,
+
+            // smoothed distance between plots and where we predicted them, m
+            innovation: f64,
+
+            // 0..1, how much the estimate can be trusted, refreshed in update()
+            pub quality: f64,
+
+            // lifetime manager
+            pub contact_tick: u32,
+
+            // tick the track was created, never refreshed
+            pub first_tick: u32,
+
+            // tick of the last update(), coasting covers however many ticks were skipped
+            last_update_tick: u32,
+
+            filter: Kalman,
+        }
+
+        // where a track's measurements came from, one store holds all three
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum TrackSource {
+            // our own radar only
+            Local,
+
+            // teammates' reports only, sender is the radio id of the latest one
+            Remote { sender: u8 },
+
+            // nothing seen yet, a missile guessed from the launcher track's plots
+            Inferred { launcher: u128 },
+
+            // our own plots and teammates' reports both
+            Fused,
+        }
+
+        impl TrackSource {
+            // seen by our own radar, good enough to aim a gun at and to pass on
+            pub fn has_local(&self) -> bool {
+                matches!(self, TrackSource::Local | TrackSource::Fused)
+            }
+
+            // applied on top of track_quality
+            pub fn quality_weight(&self) -> f64 {
+                match self {
+                    TrackSource::Remote { .. } => REMOTE_QUALITY_WEIGHT,
+                    TrackSource::Inferred { .. } => INFERRED_QUALITY_WEIGHT,
+                    _ => 1.0,
+                }
+            }
+        }
+
+        // classifier to apply to a RadarTrack
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum TrackType {
+            Tentative,
+            Friend,
+            Foe,
+            Missile,
+
+        }
+
+        // geometry helpers specific to a single RadarTrack
+        pub trait RadarTrackGeometry {
+            fn heading(&self) -> f64;
+
+            fn push_plot(&mut self, plot: TimedScanResult);
+
+            fn update(&mut self);
+
+            // point as measured at tick, brought up to the gate's time before the check
+            fn check_gate(&mut self, point: Vec2, tick: u32) -> bool;
+
+            fn distance_from(&self, point: Vec2) -> f64;
+
+            // returns x,y values as distance representation to target
+            fn get_target_direction(&self, point: Vec2) -> Vec2;
+
+            // relative kinematics against an observer at point moving with observer_velocity
+            fn closing_speed_from(&self, point: Vec2, observer_velocity: Vec2) -> f64;
+            fn time_to_closest_approach(&self, point: Vec2, observer_velocity: Vec2) -> f64;
+            fn distance_at_closest_approach(&self, point: Vec2, observer_velocity: Vec2) -> f64;
+            fn intercept_feasible(&self, point: Vec2, observer_velocity: Vec2, max_accel: f64, fuel: f64) -> bool;
+
+            // heading of the average velocity over the recent history window
+            fn smoothed_heading(&self) -> f64;
+            // rate of heading change in rad/s, positive is a left (ccw) turn
+            fn turn_rate(&self) -> f64;
+            // rate of speed change in m/s/s, positive is speeding up
+            fn speed_trend(&self) -> f64;
+
+            // 0..1 trust in the estimate, see track_quality
+            fn quality(&self) -> f64;
+        }
+
+
+        // constant velocity Kalman filter over [x, y, vx, vy], the track's acceleration
+        // estimate goes in as a known input and white acceleration noise covers the
+        // rest. plots measure position and velocity directly and both axes see the
+        // same noise at the same times, so one position/velocity covariance serves both
+        #[derive(Debug, Clone, Copy)]
+        pub struct Kalman {
+            pub state: [f64; 4],
+
+            // per axis [[position, cross], [cross, velocity]]
+            pub covariance: [[f64; 2]; 2],
+
+            // spectral density of the unmodeled acceleration, m^2/s^3
+            process_noise: f64,
+
+            // plot variances, m^2 and (m/s)^2
+            position_noise: f64,
+            velocity_noise: f64,
+        }
+
+        impl Kalman {
+            pub fn new(position: Vec2, velocity: Vec2, position_sigma: f64) -> Self {
+                Kalman {
+                    state: [position.x, position.y, velocity.x, velocity.y],
+                    covariance: [[position_sigma * position_sigma, 0.0], [0.0, KALMAN_VELOCITY_NOISE * KALMAN_VELOCITY_NOISE]],
+                    process_noise: KALMAN_PROCESS_NOISE,
+                    position_noise: KALMAN_POSITION_NOISE * KALMAN_POSITION_NOISE,
+                    velocity_noise: KALMAN_VELOCITY_NOISE * KALMAN_VELOCITY_NOISE,
+                }
+            }
+
+            pub fn position(&self) -> Vec2 {
+                vec2(self.state[0], self.state[1])
+            }
+
+            pub fn velocity(&self) -> Vec2 {
+                vec2(self.state[2], self.state[3])
+            }
+
+            pub fn position_sigma(&self) -> f64 {
+                self.covariance[0][0].max(0.0).sqrt()
+            }
+
+            // gate width, the ellipse's minor axis, holding KALMAN_GATE_SIGMAS of
+            // position error either side of the estimate
+            pub fn gate_width(&self) -> f64 {
+                (2.0 * KALMAN_GATE_SIGMAS * self.position_sigma()).clamp(KALMAN_MIN_GATE, KALMAN_MAX_GATE)
+            }
+
+            // the estimate was replaced from outside, a teammate's report for one
+            pub fn set_state(&mut self, position: Vec2, velocity: Vec2) {
+                self.state = [position.x, position.y, velocity.x, velocity.y];
+            }
+
+            pub fn set_position_sigma(&mut self, sigma: f64) {
+                self.covariance[0][0] = sigma * sigma;
+            }
+
+            pub fn predict(&mut self, dt: f64, acceleration: Vec2) {
+                if dt <= 0.0 {
+                    return;
+                }
+                for (axis, a) in [acceleration.x, acceleration.y].into_iter().enumerate() {
+                    self.state[axis] += self.state[axis + 2] * dt + 0.5 * a * dt * dt;
+                    self.state[axis + 2] += a * dt;
+                }
+                let [[p00, p01], [_, p11]] = self.covariance;
+                let q = self.process_noise;
+                let cross = p01 + dt * p11 + q * dt * dt / 2.0;
+                self.covariance = [
+                    [p00 + 2.0 * dt * p01 + dt * dt * p11 + q * dt.powi(3) / 3.0, cross],
+                    [cross, p11 + q * dt],
+                ];
+            }
+
+            // folds in a plot, returns the position residual it was taken against
+            pub fn correct(&mut self, position: Vec2, velocity: Vec2) -> Vec2 {
+                let residual = position - self.position();
+                let [[p00, p01], [_, p11]] = self.covariance;
+
+                // gain = P (P + R)^-1
+                let (s00, s01, s11) = (p00 + self.position_noise, p01, p11 + self.velocity_noise);
+                let det = s00 * s11 - s01 * s01;
+                if det.abs() < f64::EPSILON {
+                    return residual;
+                }
+                let (i00, i01, i11) = (s11 / det, -s01 / det, s00 / det);
+                let (k00, k01) = (p00 * i00 + p01 * i01, p00 * i01 + p01 * i11);
+                let (k10, k11) = (p01 * i00 + p11 * i01, p01 * i01 + p11 * i11);
+
+                let measured = [[position.x, velocity.x], [position.y, velocity.y]];
+                for (axis, [z_position, z_velocity]) in measured.into_iter().enumerate() {
+                    let r_position = z_position - self.state[axis];
+                    let r_velocity = z_velocity - self.state[axis + 2];
+                    self.state[axis] += k00 * r_position + k01 * r_velocity;
+                    self.state[axis + 2] += k10 * r_position + k11 * r_velocity;
+                }
+
+                // (I - K) P, kept symmetric
+                let cross = (1.0 - k00) * p01 - k01 * p11;
+                self.covariance = [
+                    [(1.0 - k00) * p00 - k01 * p01, cross],
+                    [cross, -k10 * p01 + (1.0 - k11) * p11],
+                ];
+                residual
+            }
+        }
+
+        impl RadarTrackGeometry for RadarTrack {
+            fn heading(&self) -> f64 {
+                self.velocity.y.atan2(self.velocity.x)
+            }
+
+            fn push_plot(&mut self, plot: TimedScanResult) {
+                self.contact_tick = self.contact_tick.max(plot.tick);
+                self.scans.push_back(plot);
+                while self.scans.len() > MAX_QUEUED_SCANS {
+                    self.scans.pop_front();
+                }
+            }
+
+            fn update(&mut self) {
+                self.update_at(current_tick());
+            }
+
+            fn check_gate(&mut self, point: Vec2, tick: u32) -> bool {
+                self.gate.point_in_gate(self.plot_at_estimate(point, tick))
+            }
+
+            fn distance_from(&self, point: Vec2) -> f64 {
+                (self.position - point).length()
+            }
+
+            fn get_target_direction(&self, point: Vec2) -> Vec2 {
+                self.position - point
+            }
+
+            fn closing_speed_from(&self, point: Vec2, observer_velocity: Vec2) -> f64 {
+                closing_speed(self.position - point, self.velocity - observer_velocity)
+            }
+
+            fn time_to_closest_approach(&self, point: Vec2, observer_velocity: Vec2) -> f64 {
+                time_to_closest_approach(self.position - point, self.velocity - observer_velocity)
+            }
+
+            fn distance_at_closest_approach(&self, point: Vec2, observer_velocity: Vec2) -> f64 {
+                distance_at_closest_approach(self.position - point, self.velocity - observer_velocity)
+            }
+
+            fn intercept_feasible(&self, point: Vec2, observer_velocity: Vec2, max_accel: f64, fuel: f64) -> bool {
+                intercept_feasible(self.position - point, self.velocity - observer_velocity, max_accel, fuel)
+            }
+
+            fn quality(&self) -> f64 {
+                self.quality
+            }
+
+            fn smoothed_heading(&self) -> f64 {
+                let window = self.history.len().min(TRACK_HISTORY_WINDOW);
+                if window == 0 {
+                    return self.heading;
+                }
+                let mut sum = Vec2::new(0.0, 0.0);
+                for entry in self.history.iter().rev().take(window) {
+                    sum += entry.velocity;
+                }
+                sum.y.atan2(sum.x)
+            }
+
+            fn turn_rate(&self) -> f64 {
+                let window = self.history.len().min(TRACK_HISTORY_WINDOW);
+                if window < 2 {
+                    return 0.0;
+                }
+                let newest = self.history[self.history.len() - 1];
+                let oldest = self.history[self.history.len() - window];
+                if newest.tick <= oldest.tick {
+                    return 0.0;
+                }
+                let dt = (newest.tick - oldest.tick) as f64 / 60.0;
+                angle_diff(oldest.velocity.angle(), newest.velocity.angle()) / dt
+            }
+
+            fn speed_trend(&self) -> f64 {
+                let window = self.history.len().min(TRACK_HISTORY_WINDOW);
+                if window < 2 {
+                    return 0.0;
+                }
+                let newest = self.history[self.history.len() - 1];
+                let oldest = self.history[self.history.len() - window];
+                if newest.tick <= oldest.tick {
+                    return 0.0;
+                }
+                let dt = (newest.tick - oldest.tick) as f64 / 60.0;
+                (newest.velocity.length() - oldest.velocity.length()) / dt
+            }
+        }
+
+        impl RadarTrack {
+            pub fn new(id: u128, position: Vec2, velocity: Vec2, contact_class: Class, gate_width: f64, tick: u32) -> Self {
+                RadarTrack {
+                    scans: VecDeque::new(),
+                    position,
+                    velocity,
+                    acceleration: Vec2::new(0.0, 0.0),
+                    last_plot_velocity: velocity,
+                    last_plot_tick: tick,
+                    last_corrected_tick: None,
+                    contact_class,
+                    class_streak: 0,
+                    history: VecDeque::new(),
+                    source: TrackSource::Local,
+                    last_report_tick: tick,
+                    heading: velocity.y.atan2(velocity.x),
+                    id,
+                    class: TrackType::Tentative,
+                    gate: RadarTrackGate::new(position, gate_width),
+                    plot_count: 0,
+                    probably_dead: false,
+                    coast_until_tick: None,
+                    launch_cue: false,
+                    last_launch_tick: None,
+                    recent_plots: VecDeque::new(),
+                    innovation: 0.0,
+                    quality: 0.0,
+                    contact_tick: tick,
+                    first_tick: tick,
+                    last_update_tick: tick,
+                    filter: Kalman::new(position, velocity, gate_width / (2.0 * KALMAN_GATE_SIGMAS)),
+                }
+            }
+
+            pub fn snapshot(&self) -> TrackSnapshot {
+                TrackSnapshot {
+                    id: self.id,
+                    position: self.position,
+                    velocity: self.velocity,
+                    acceleration: self.acceleration,
+                    turn_rate: self.turn_rate(),
+                    smoothed_heading: self.smoothed_heading(),
+                    contact_class: self.contact_class,
+                    class: self.class,
+                    friendly: matches!(self.class, TrackType::Friend),
+                    source: self.source,
+                    plot_count: self.plot_count,
+                    quality: self.quality,
+                    last_plot_tick: self.last_plot_tick,
+                    first_tick: self.first_tick,
+                    probably_dead: self.probably_dead,
+                    gate_width: self.gate.width,
+                }
+            }
+
+            // everything update() does with the tick passed in, so it runs off-line
+            pub fn update_at(&mut self, tick: u32) {
+                // oldest first, a track that skipped ticks or was plotted twice in one
+                // can have several queued and they needn't have arrived in order
+                self.scans.make_contiguous().sort_by_key(|scan| scan.tick);
+                while let Some(scan) = self.scans.pop_front() {
+                    if let Some(corrected) = self.last_corrected_tick.filter(|t| scan.tick < *t) {
+                        trace!("track {} dropping plot from {}, already corrected to {}", self.id, scan.tick, corrected);
+                        continue;
+                    }
+                    trace!("track {} plot at {}", self.id, scan.scan.position);
+
+                    self.note_class(scan.scan.class);
+
+                    // predict to the plot, then correct with it
+                    self.coast(scan.tick.max(self.last_update_tick));
+                    self.update_acceleration_estimate(&scan);
+                    let measured = self.plot_at_estimate(scan.scan.position, scan.tick);
+                    let measured_velocity = self.plot_velocity_at_estimate(scan.scan.velocity, scan.tick);
+                    let residual = self.filter.correct(measured, measured_velocity);
+                    self.position = self.filter.position();
+                    self.velocity = self.filter.velocity();
+                    self.innovation = self.innovation * 0.8 + residual.length() * 0.2;
+                    self.recent_plots.push_back(scan.tick);
+                    self.last_corrected_tick = Some(scan.tick);
+                    self.plot_count += 1;
+                }
+                // and on over the ticks since
+                self.coast(tick);
+
+                // remote only gates are sized by the reports' age, the rest by the filter
+                if !matches!(self.source, TrackSource::Remote { .. } | TrackSource::Inferred { .. }) {
+                    self.gate.update_width(self.filter.gate_width());
+                }
+                self.gate.update_axis(self.velocity, tick.saturating_sub(self.contact_tick));
+                self.gate.update_center(self.position);
+
+                while self.recent_plots.front().is_some_and(|t| tick - t >= QUALITY_PLOT_WINDOW) {
+                    self.recent_plots.pop_front();
+                }
+                // remote only tracks are kept fresh by reports, everything else by our own plots
+                let last_seen = match self.source {
+                    TrackSource::Remote { .. } => self.last_report_tick,
+                    _ => self.last_plot_tick,
+                };
+                self.quality = track_quality(
+                    tick.saturating_sub(last_seen),
+                    self.recent_plots.len(),
+                    self.innovation,
+                    self.gate.width,
+                    !matches!(self.class, TrackType::Tentative),
+                ) * self.source.quality_weight();
+
+                // record the resolved state and refresh the derived heading
+                self.heading = self.velocity.y.atan2(self.velocity.x);
+                self.history.push_back(TrackHistoryEntry { tick, position: self.position, velocity: self.velocity });
+                while self.history.len() > TRACK_HISTORY_LENGTH {
+                    self.history.pop_front();
+                }
+            }
+
+            // a local plot landed on this track, a remote only one snaps its gate down
+            // to the local size since our own plot is far better than any report
+            pub fn confirm_local(&mut self, gate_width: f64) {
+                match self.source {
+                    TrackSource::Remote { sender } => {
+                        trace!("remote track {} from {} confirmed by local plot", self.id, sender);
+                        self.source = TrackSource::Fused;
+                        self.filter.set_position_sigma(gate_width / (2.0 * KALMAN_GATE_SIGMAS));
+                        self.gate.update_width(gate_width);
+                    },
+                    TrackSource::Inferred { launcher } => {
+                        // a real missile now, classified like any other new contact
+                        trace!("inferred launch {} from {} confirmed by local plot", self.id, launcher);
+                        self.source = TrackSource::Local;
+                        self.class = TrackType::Tentative;
+                        self.filter.set_position_sigma(gate_width / (2.0 * KALMAN_GATE_SIGMAS));
+                        self.gate.update_width(gate_width);
+                    },
+                    TrackSource::Local | TrackSource::Fused => {},
+                }
+            }
+
+            // reports are older and coarser than plots, so they widen a remote only
+            // track's gate with their age and only nudge one we plot ourselves
+            pub fn apply_remote_report(&mut self, position: Vec2, velocity: Vec2, age: u32, sender: u8, tick: u32) {
+                let width = remote_report_width(age);
+                if matches!(self.source, TrackSource::Inferred { .. }) {
+                    self.class = TrackType::Tentative;
+                }
+                // what the last report is worth by now, nothing held for anything else
+                let held = match self.source {
+                    TrackSource::Remote { .. } => Some(self.gate.width + REMOTE_GATE_GROWTH * tick.saturating_sub(self.last_report_tick) as f64 / 60.0),
+                    _ => None,
+                };
+                let fresher = held.is_none_or(|held| width <= held);
+                match self.source {
+                    TrackSource::Remote { .. } | TrackSource::Inferred { .. } => {
+                        // nothing better to go on, but a second teammate's staler report of
+                        // the same contact shouldn't drag a fresher one, weigh the two by
+                        // their spread and keep the spread of the fused estimate
+                        let (weight, fused) = match held {
+                            Some(held) => (held * held / (held * held + width * width), held * width / (held * held + width * width).sqrt()),
+                            None => (1.0, width),
+                        };
+                        self.position = self.position * (1.0 - weight) + position * weight;
+                        self.velocity = self.velocity * (1.0 - weight) + velocity * weight;
+                        self.filter.set_position_sigma(fused / (2.0 * KALMAN_GATE_SIGMAS));
+                        self.gate.update_width(fused);
+                        if fresher {
+                            self.source = TrackSource::Remote { sender };
+                        }
+                        self.recent_plots.push_back(tick);
+                        self.contact_tick = tick;
+                    },
+                    TrackSource::Local | TrackSource::Fused => {
+                        // local plots win, older reports count for less and neither the
+                        // gate nor the plot clock is touched
+                        let blend = REMOTE_BLEND * REMOTE_GATE_WIDTH / width;
+                        self.position = self.position * (1.0 - blend) + position * blend;
+                        self.velocity = self.velocity * (1.0 - blend) + velocity * blend;
+                        self.source = TrackSource::Fused;
+                    },
+                }
+                self.filter.set_state(self.position, self.velocity);
+                // a staler report doesn't reset how old the fresher one is
+                if fresher {
+                    self.last_report_tick = tick;
+                }
+                self.gate.update_center(self.position);
+                self.plot_count += 1;
+            }
+
+            // a contact that changes class is a different contact, or a misassociation,
+            // either way its classification starts over
+            pub fn note_class(&mut self, class: Class) {
+                if class == self.contact_class {
+                    self.class_streak += 1;
+                    return;
+                }
+                trace!("track {} class {:?} -> {:?}", self.id, self.contact_class, class);
+                self.contact_class = class;
+                self.class_streak = 1;
+                if matches!(self.class, TrackType::Foe | TrackType::Missile) {
+                    self.class = TrackType::Tentative;
+                }
+            }
+
+            // already holds a plot from this tick, a second one would be folded in as if
+            // it came later
+            pub fn plotted_at(&self, tick: u32) -> bool {
+                self.last_corrected_tick == Some(tick) || self.scans.iter().any(|scan| scan.tick == tick)
+            }
+
+            // anything that isn't known to be ours and has been seen often enough
+            pub fn engageable(&self, min_plots: u32) -> bool {
+                !matches!(self.class, TrackType::Friend) && !self.probably_dead && self.plot_count >= min_plots
+            }
+
+            // a plot taken before the estimate's time moved on by the track's motion,
+            // comparing it to the estimate as is biases it behind a fast mover. velocity
+            // is the estimate's, so the acceleration term comes off
+            pub fn plot_at_estimate(&self, point: Vec2, tick: u32) -> Vec2 {
+                let dt = self.last_update_tick.saturating_sub(tick) as f64 / 60.0;
+                point + self.velocity * dt - 0.5 * self.acceleration * dt * dt
+            }
+
+            // and its velocity, which a thrusting contact has added to since
+            pub fn plot_velocity_at_estimate(&self, velocity: Vec2, tick: u32) -> Vec2 {
+                velocity + self.acceleration * (self.last_update_tick.saturating_sub(tick) as f64 / 60.0)
+            }
+
+            // the estimate dead reckoned to tick without touching the filter
+            pub fn extrapolated(&self, tick: u32) -> Vec2 {
+                let dt = tick.saturating_sub(self.last_update_tick) as f64 / 60.0;
+                self.position + self.velocity * dt + 0.5 * self.acceleration * dt * dt
+            }
+
+            // filter prediction from the last update up to tick
+            fn coast(&mut self, tick: u32) {
+                let dt = tick.saturating_sub(self.last_update_tick) as f64 / 60.0;
+                self.filter.predict(dt, self.acceleration);
+                self.position = self.filter.position();
+                self.velocity = self.filter.velocity();
+                self.last_update_tick = tick;
+            }
+
+            // smoothed finite difference of velocity between plots, clamped so a noisy
+            // plot can't claim more acceleration than the contact's class is capable of
+            fn update_acceleration_estimate(&mut self, scan: &TimedScanResult) {
+                if scan.tick <= self.last_plot_tick {
+                    return;
+                }
+                let gap = scan.tick - self.last_plot_tick;
+                let dt = gap as f64 / 60.0;
+                let raw = (scan.scan.velocity - self.last_plot_velocity) / dt;
+
+                let delta = (scan.scan.velocity - self.last_plot_velocity).length();
+                let rearmed = self.last_launch_tick.is_none_or(|t| scan.tick - t >= LAUNCH_INFERENCE_COOLDOWN);
+                if rearmed && launch_signature(self.contact_class, &self.class, delta, gap) {
+                    trace!("track {} launch signature, {:.1} m/s over {} ticks", self.id, delta, gap);
+                    self.launch_cue = true;
+                    self.last_launch_tick = Some(scan.tick);
+                }
+                let smoothed = self.acceleration * (1.0 - ACCELERATION_SMOOTHING) + raw * ACCELERATION_SMOOTHING;
+
+                let limit = max_plausible_acceleration(self.contact_class);
+                self.acceleration = if smoothed.length() > limit {
+                    smoothed.normalize() * limit
+                } else {
+                    smoothed
+                };
+                trace!("track {} acceleration estimate: {}", self.id, self.acceleration);
+
+                self.last_plot_velocity = scan.scan.velocity;
+                self.last_plot_tick = scan.tick;
+            }
+        }
+
+        // read-only copy of one track, taken with a single borrow so consumers never
+        // hold a RefCell guard across calls that can reach back into the radar
+        #[derive(Debug, Clone, Copy)]
+        pub struct TrackSnapshot {
+            pub id: u128,
+            pub position: Vec2,
+            pub velocity: Vec2,
+            pub acceleration: Vec2,
+            pub turn_rate: f64,
+            pub smoothed_heading: f64,
+            pub contact_class: Class,
+            pub class: TrackType,
+            pub friendly: bool,
+            pub source: TrackSource,
+            pub plot_count: u32,
+            pub quality: f64,
+            pub last_plot_tick: u32,
+            pub first_tick: u32,
+            pub probably_dead: bool,
+            pub gate_width: f64,
+        }
+
+        impl TrackSnapshot {
+            pub fn engageable(&self, min_plots: u32) -> bool {
+                !self.friendly && !self.probably_dead && self.plot_count >= min_plots
+            }
+
+            pub fn distance_from(&self, point: Vec2) -> f64 {
+                (self.position - point).length()
+            }
+
+            pub fn get_target_direction(&self, point: Vec2) -> Vec2 {
+                self.position - point
+            }
+
+            pub fn closing_speed_from(&self, point: Vec2, observer_velocity: Vec2) -> f64 {
+                closing_speed(self.position - point, self.velocity - observer_velocity)
+            }
+
+            pub fn time_to_closest_approach(&self, point: Vec2, observer_velocity: Vec2) -> f64 {
+                time_to_closest_approach(self.position - point, self.velocity - observer_velocity)
+            }
+
+            pub fn distance_at_closest_approach(&self, point: Vec2, observer_velocity: Vec2) -> f64 {
+                distance_at_closest_approach(self.position - point, self.velocity - observer_velocity)
+            }
+
+            pub fn intercept_feasible(&self, point: Vec2, observer_velocity: Vec2, max_accel: f64, fuel: f64) -> bool {
+                intercept_feasible(self.position - point, self.velocity - observer_velocity, max_accel, fuel)
+            }
+        }
+
+        // every track as it stood at the end of the radar's sense, the one copy the
+        // ships read from for the rest of the tick. tracks are named by id, anything
+        // that has to change a track goes back through the radar with that id
+        #[derive(Debug, Clone, Default)]
+        pub struct RadarPicture {
+            pub tick: u32,
+            pub tracks: Vec<TrackSnapshot>,
+        }
+
+        impl RadarPicture {
+            pub fn get(&self, id: u128) -> Option<TrackSnapshot> {
+                self.tracks.iter().find(|t| t.id == id).copied()
+            }
+
+            pub fn iter(&self) -> impl Iterator<Item = &TrackSnapshot> {
+                self.tracks.iter()
+            }
+
+            // the newer copy of a snapshot, or the one passed in once its track is gone
+            pub fn refresh(&self, track: TrackSnapshot) -> TrackSnapshot {
+                self.get(track.id).unwrap_or(track)
+            }
+        }
+
+        // the one way a track handle is copied out, the picture and the tests go through it
+        pub fn read_track(track: &Rc<RefCell<RadarTrack>>) -> TrackSnapshot {
+            debug_assert!(!RADAR_UPDATING.load(Ordering::Relaxed), "track read while the radar is updating");
+            track.borrow().snapshot()
+        }
+
+        // 0..1 trust in a track: decays with time since the last plot, scales with how
+        // many plots fed it recently and how close they landed to the prediction, and
+        // is docked until the track's classification is settled
+        pub fn track_quality(ticks_since_plot: u32, recent_plots: usize, innovation: f64, gate_width: f64, confirmed: bool) -> f64 {
+            let freshness = (-(ticks_since_plot as f64) / QUALITY_STALE_TICKS).exp();
+            let rate = (recent_plots as f64 / QUALITY_EXPECTED_PLOTS).min(1.0);
+            let consistency = 1.0 / (1.0 + innovation / gate_width.max(E));
+            let classification = if confirmed { 1.0 } else { 0.7 };
+            freshness * (0.5 + 0.5 * rate) * consistency * classification
+        }
+
+        // gate width for a teammate's report, a fresh one is already coarser than a
+        // plot and it grows with the time the contact had to move since
+        pub fn remote_report_width(age: u32) -> f64 {
+            REMOTE_GATE_WIDTH + REMOTE_GATE_GROWTH * age as f64 / 60.0
+        }
+
+        // lower is a better match for a plot: its distance from the track's prediction,
+        // plus the distance the velocity mismatch opens up over the weight's time
+        pub fn association_score(residual: Vec2, velocity_mismatch: Vec2) -> f64 {
+            residual.length() + velocity_mismatch.length() * ASSOCIATION_VELOCITY_WEIGHT
+        }
+
+        // the designated target is only given up for one that scores clearly better, two
+        // contacts about as good would otherwise trade the designation back and forth.
+        // the margin is added, the closing term takes totals below zero
+        pub fn worth_switching(designated_score: f64, candidate_score: f64) -> bool {
+            candidate_score > designated_score + TARGET_SWITCH_MARGIN
+        }
+
+        // Tentative until enough plots in a row agree on the contact's class, then
+        // ordnance or a ship. friends are only ever marked from radio positions
+        pub fn track_type_for(class: Class, agreeing_plots: u32) -> TrackType {
+            if agreeing_plots < CLASS_CONFIRM_PLOTS {
+                return TrackType::Tentative;
+            }
+            match class {
+                Class::Missile | Class::Torpedo => TrackType::Missile,
+                _ => TrackType::Foe,
+            }
+        }
+
+        // a foe fighter's plot velocity jumping by more than its thrust explains
+        // between close plots, the kick of something leaving the rails
+        pub fn launch_signature(contact_class: Class, class: &TrackType, velocity_delta: f64, gap_ticks: u32) -> bool {
+            if contact_class != Class::Fighter || !matches!(class, TrackType::Foe) || gap_ticks == 0 || gap_ticks > LAUNCH_SIGNATURE_TICKS {
+                return false;
+            }
+            velocity_delta - max_plausible_acceleration(contact_class) * gap_ticks as f64 / 60.0 > LAUNCH_VELOCITY_DELTA
+        }
+
+        // upper bound on how hard a contact of this class can accelerate, m/s/s
+        pub fn max_plausible_acceleration(class: Class) -> f64 {
+            match class {
+                Class::Fighter => 70.0,
+                Class::Frigate => 15.0,
+                Class::Cruiser => 10.0,
+                Class::Missile => 400.0,
+                Class::Torpedo => 100.0,
+                _ => 70.0,
+            }
+        }
+    }
+
+    // beam scheduling, plot association, classification and track lifetime
+    mod tracker {
+        use super::*;
+
+        // final state of a designated track that dropped, dead reckoned until it's
+        // reacquired or given up on
+        pub struct LostContact {
+            position: Vec2,
+            pub velocity: Vec2,
+            contact_class: Class,
+            gate_width: f64,
+            tick: u32,
+        }
+
+        impl LostContact {
+            pub fn from_track(track: &TrackSnapshot) -> Self {
+                LostContact {
+                    position: track.position,
+                    velocity: track.velocity,
+                    contact_class: track.contact_class,
+                    gate_width: track.gate_width,
+                    tick: current_tick(),
+                }
+            }
+
+            pub fn age(&self) -> u32 {
+                current_tick() - self.tick
+            }
+
+            pub fn expired(&self) -> bool {
+                self.age() >= LOST_CONTACT_TICKS
+            }
+
+            pub fn predicted_position(&self) -> Vec2 {
+                self.position + self.velocity * (self.age() as f64 / 60.0)
+            }
+
+            // the target could have maneuvered anywhere inside this since we lost it
+            pub fn reacquire_radius(&self) -> f64 {
+                let t = self.age() as f64 / 60.0;
+                self.gate_width + 0.5 * max_plausible_acceleration(self.contact_class) * t * t
+            }
+
+            // half angle of the cone around the extrapolated bearing that covers the reacquire radius
+            pub fn search_half_width(&self, from: Vec2) -> f64 {
+                let range = (self.predicted_position() - from).length().max(1.0);
+                (self.reacquire_radius() / range).atan().clamp(PI / 32.0, PI)
+            }
+
+            // closest fresh track of the same class inside the grown gate around the
+            // extrapolation, never a friend, the radar's current designation or one taken
+            pub fn reacquire(&self, radar: &Radar, taken: &dyn Fn(&RadarTrack) -> bool) -> Option<u128> {
+                let predicted = self.predicted_position();
+                let radius = self.reacquire_radius();
+                let designated = radar.designated();
+                radar.get_closest_target_matching(predicted, &|t: &RadarTrack| {
+                    t.engageable(ENGAGE_MIN_PLOTS)
+                        && !matches!(t.class, TrackType::Friend)
+                        && t.contact_class == self.contact_class
+                        && Some(t.id) != designated
+                        && !taken(t)
+                        && t.distance_from(predicted) < radius
+                })
+            }
+
+            pub fn draw(&self) {
+                debug_line(LAYER_GATES, self.position, self.predicted_position(), 0xffff00);
+                debug_square(LAYER_GATES, self.predicted_position(), self.reacquire_radius() * 2.0, 0xffff00);
+            }
+        }
+
+        #[derive(Debug, Clone, Copy)]
+        pub enum RadarState {
+            ShortRange,
+            MediumRange,
+            LongRange,
+            TargetFocus,
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum RadarBeam {
+            Focused,
+            Narrow,
+            Standard,
+            Wide,
+        }
+
+        // one input to the track store
+        pub enum Detection {
+            // a plot off our own radar this tick
+            Local(ScanResult),
+
+            // a teammate's contact report, age in ticks when it was sent
+            Remote { position: Vec2, velocity: Vec2, class: Class, age: u32, sender: u8 },
+        }
+
+        pub struct Radar {
+            // radar name, currently just for debugging
+            name: String,
+
+            // beam class the ships ask for goes through hysteresis here
+            beam: BeamController,
+            state: RadarState,
+
+            // count ticks since contact to switch to extended radar sweep
+            pub ticks_since_contact: u32,
+
+            designated_target: Option<u128>,
+
+            // looked at ahead of the designation for one tick, see cue
+            cued: Option<u128>,
+
+            // collect current target positions for time-based calculations
+            pub potential_targets: HashMap<u128, Rc<RefCell<RadarTrack>>>,
+
+            // expired tracks still being coasted by a consumer, out of every selection
+            // and the picture, but a plot or report in one's grown gate revives it
+            ghosts: HashMap<u128, Rc<RefCell<RadarTrack>>>,
+
+            // simple unsigned integer id to use for uuids
+            id_gen: u128,
+
+            // ranges, widths and track bookkeeping for this class
+            profile: RadarProfile,
+
+            // per tick work counters, reset at the top of sense
+            stats: RadarStats,
+
+            // (center, half width) the sweeps stay inside, None sweeps the full circle
+            sweep_sector: Option<(f64, f64)>,
+            // (min, max) range the sweeps look at while a sector bias is on
+            sweep_bracket: Option<(f64, f64)>,
+
+            // known friendly positions as (tick, position, velocity), tracks near them are ours
+            friendlies: Vec<(u32, Vec2, Vec2)>,
+
+            // acceleration control committed to this tick, handed over in point()
+            own_acceleration: Vec2,
+
+            // launch inference events this tick, reset at the top of sense
+            launches: LaunchInferences,
+
+            // shares the beam between the sweep and track revisits
+            revisit: RevisitScheduler,
+
+            // where the sweep is, kept apart from the beam so dwells don't move it
+            sweep_heading: f64,
+            sweep_ticks: u32,
+
+            // contact history the sweep leans toward
+            sectors: SweepSectors,
+
+            // copied out at the end of sense, see RadarPicture
+            picture: RadarPicture,
+
+            // (plot tick, position, class) of the plots already taken from the current sweep
+            sweep_plots: Vec<(u32, Vec2, Class)>,
+        }
+
+        // provisional missile tracks seeded, confirmed by a plot or report, and
+        // dropped unseen
+        #[derive(Debug, Default, Clone, Copy)]
+        pub struct LaunchInferences {
+            pub inferred: u32,
+            pub confirmed: u32,
+            pub expired: u32,
+        }
+
+        // range gate and beam width for a locked radar
+        #[derive(Debug, Clone, Copy)]
+        pub struct LockBracket {
+            pub min_distance: f64,
+            pub max_distance: f64,
+            pub width: f64,
+        }
+
+        // symmetric bracket around the predicted range, widened by how far the target
+        // can close or open since its last plot, beam sized to cover the cross range
+        // uncertainty at that range
+        pub fn lock_bracket(predicted_range: f64, closing_speed: f64, ticks_since_plot: u32, cross_range: f64) -> LockBracket {
+            let spread = closing_speed.abs() * (ticks_since_plot as f64 / 60.0) + LOCK_RANGE_MARGIN;
+            let width = (2.0 * (cross_range / predicted_range.max(1.0)).atan()).clamp(LOCK_MIN_WIDTH, LOCK_MAX_WIDTH);
+            LockBracket {
+                min_distance: (predicted_range - spread).max(0.0),
+                max_distance: predicted_range + spread,
+                width,
+            }
+        }
+
+        // what this tick's beam is spent on
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Revisit {
+            Search,
+            Dwell(u128),
+        }
+
+        // one track up for a revisit
+        #[derive(Debug, Clone, Copy)]
+        pub struct RevisitCandidate {
+            pub id: u128,
+            pub last_plot_tick: u32,
+            pub desired_ticks: u32,
+        }
+
+        // ticks a track can go without a plot: until it could drift out of a lock
+        // beam's bearing budget or its quality decays to the floor it's needed at,
+        // whichever comes first. close fast crossers come out at a couple of ticks,
+        // distant ones coast for a second
+        pub fn desired_revisit_ticks(range: f64, cross_speed: f64, quality: f64, quality_floor: f64) -> u32 {
+            let drift = REVISIT_BEARING_BUDGET * range / cross_speed.max(1.0) * 60.0;
+            // freshness falls off exponentially, so the loss per tick scales with quality
+            let decay = quality / QUALITY_STALE_TICKS;
+            // at or under the floor the weapon is already holding for it, it's due now
+            let fade = if quality > quality_floor { (quality - quality_floor) / decay } else { 0.0 };
+            (drift.min(fade) as u32).clamp(REVISIT_MIN_TICKS, REVISIT_MAX_TICKS)
+        }
+
+        // where recent contacts came from, for the sweep to spend more of its time
+        // there. a sector is picked by weight times how long since it was swept, and
+        // one left out for SWEEP_MAX_AGE picks goes next regardless, so a flanker
+        // still gets looked at. with no weight left this is the plain rotation
+        pub struct SweepSectors {
+            weights: [f64; SWEEP_SECTORS],
+
+            // pick count at each sector's last sweep, and the running count
+            last_pick: [u32; SWEEP_SECTORS],
+            picks: u32,
+
+            pub current: usize,
+        }
+
+        impl SweepSectors {
+            pub fn new(heading: f64) -> Self {
+                SweepSectors {
+                    weights: [0.0; SWEEP_SECTORS],
+                    last_pick: [0; SWEEP_SECTORS],
+                    picks: 0,
+                    current: Self::sector_of(heading),
+                }
+            }
+
+            pub fn sector_of(bearing: f64) -> usize {
+                let width = 2.0 * PI / SWEEP_SECTORS as f64;
+                (bearing.rem_euclid(2.0 * PI) / width) as usize % SWEEP_SECTORS
+            }
+
+            // bearing the sector starts at, counterclockwise from here to the next
+            pub fn start_of(sector: usize) -> f64 {
+                sector as f64 * 2.0 * PI / SWEEP_SECTORS as f64
+            }
+
+            pub fn weight(&self, sector: usize) -> f64 {
+                self.weights[sector]
+            }
+
+            pub fn note_contact(&mut self, bearing: f64) {
+                let sector = Self::sector_of(bearing);
+                self.weights[sector] = (self.weights[sector] + 1.0).min(SWEEP_SECTOR_MAX_WEIGHT);
+            }
+
+            // fades the weights while nothing is being seen, true on the tick the last
+            // of them falls under the floor
+            pub fn decay(&mut self, ticks_since_contact: u32) -> bool {
+                if ticks_since_contact == 0 || self.decayed() {
+                    return false;
+                }
+                for weight in self.weights.iter_mut() {
+                    *weight *= SWEEP_SECTOR_DECAY;
+                }
+                self.decayed()
+            }
+
+            pub fn decayed(&self) -> bool {
+                self.weights.iter().all(|w| *w < SWEEP_SECTOR_FLOOR)
+            }
+
+            // the next sector to sweep, counting round from the current one so ties
+            // keep the rotation going the same way
+            pub fn choose(&mut self) -> usize {
+                self.picks += 1;
+                let age = |s: usize| self.picks - self.last_pick[s];
+                let order = (1..=SWEEP_SECTORS).map(|step| (self.current + step) % SWEEP_SECTORS);
+                let overdue = order.clone().filter(|s| age(*s) >= SWEEP_MAX_AGE).max_by_key(|s| age(*s));
+                let sector = overdue.unwrap_or_else(|| {
+                    let score = |s: usize| (1.0 + SWEEP_CONTACT_BIAS * self.weights[s]) * age(s) as f64;
+                    order.fold(None, |best: Option<usize>, s| match best {
+                        Some(b) if score(b) >= score(s) => Some(b),
+                        _ => Some(s),
+                    }).unwrap_or(self.current)
+                });
+                self.last_pick[sector] = self.picks;
+                self.current = sector;
+                sector
+            }
+        }
+
+        // track while scan cadence for the designated target, a close target needs a
+        // fresh solution every other tick, a distant one can wait for its slot
+        pub fn designated_dwell_ticks(range: f64) -> u32 {
+            ((range / DESIGNATED_DWELL_RANGE).ceil() as u32).clamp(REVISIT_MIN_TICKS, DESIGNATED_MAX_DWELL_TICKS)
+        }
+
+        // splits the beam between the background sweep and dwells on tracks that are
+        // overdue, worst staleness over desired interval first. the designated target
+        // goes first whenever it's due, and the sweep is owed REVISIT_MIN_SEARCH_SHARE
+        // of ticks; the designated target can't be due twice running, so a search owed
+        // is never put off by more than a tick
+        pub struct RevisitScheduler {
+            // searches owed, one is taken whenever it reaches 1
+            search_debt: f64,
+
+            last_dwell: HashMap<u128, u32>,
+
+            ticks: u32,
+            searches: u32,
+
+            // (id, ticks until due) as of the last choice, most urgent first
+            due: Vec<(u128, i64)>,
+        }
+
+        impl RevisitScheduler {
+            pub fn new() -> Self {
+                RevisitScheduler {
+                    search_debt: 0.0,
+                    last_dwell: HashMap::new(),
+                    ticks: 0,
+                    searches: 0,
+                    due: Vec::new(),
+                }
+            }
+
+            // a dwell counts as a visit even if it comes back empty, or a track that's
+            // gone would pull the beam every tick until it expires
+            fn staleness(&self, now: u32, candidate: &RevisitCandidate) -> u32 {
+                let visited = self.last_dwell.get(&candidate.id).map_or(candidate.last_plot_tick, |t| (*t).max(candidate.last_plot_tick));
+                now.saturating_sub(visited)
+            }
+
+            pub fn choose(&mut self, now: u32, candidates: &[RevisitCandidate], designated: Option<u128>) -> Revisit {
+                self.last_dwell.retain(|id, _| candidates.iter().any(|c| c.id == *id));
+                self.search_debt += REVISIT_MIN_SEARCH_SHARE;
+                self.ticks += 1;
+
+                let mut due: Vec<(u128, i64)> = candidates.iter()
+                    .map(|c| (c.id, c.desired_ticks as i64 - self.staleness(now, c) as i64))
+                    .collect();
+                due.sort_by_key(|(_, ticks)| *ticks);
+
+                let overdue = |c: &&RevisitCandidate| self.staleness(now, c) >= c.desired_ticks;
+                let urgency = |c: &&RevisitCandidate| self.staleness(now, c) as f64 / c.desired_ticks.max(1) as f64;
+                let choice = match candidates.iter().find(|c| Some(c.id) == designated) {
+                    Some(c) if overdue(&c) => Revisit::Dwell(c.id),
+                    _ if self.search_debt >= 1.0 => Revisit::Search,
+                    _ => candidates.iter()
+                        .filter(overdue)
+                        .max_by(|a, b| urgency(a).partial_cmp(&urgency(b)).unwrap_or(std::cmp::Ordering::Equal))
+                        .map_or(Revisit::Search, |c| Revisit::Dwell(c.id)),
+                };
+
+                match choice {
+                    Revisit::Dwell(id) => {
+                        self.last_dwell.insert(id, now);
+                    },
+                    Revisit::Search => {
+                        self.search_debt = (self.search_debt - 1.0).max(0.0);
+                        self.searches += 1;
+                    },
+                }
+                self.due = due;
+                choice
+            }
+
+            pub fn search_share(&self) -> f64 {
+                self.searches as f64 / self.ticks.max(1) as f64
+            }
+
+            pub fn status(&self, choice: Revisit) -> String {
+                let due: Vec<String> = self.due.iter().take(4).map(|(id, ticks)| format!("{} due in {}", id, ticks)).collect();
+                format!("{:?}, search {:.0}% | {}", choice, self.search_share() * 100.0, due.join(", "))
+            }
+        }
+
+        // beam class state machine: a request has to hold for BEAM_HOLD_TICKS and the
+        // class changes at most every BEAM_CHANGE_INTERVAL ticks, so a contact flickering
+        // in and out doesn't strobe the beam. widening is immediate, narrowing steps down
+        // so a marginal contact isn't clipped out the tick the class changes
+        pub struct BeamController {
+            current: RadarBeam,
+
+            // latest request and how many ticks it has gone unchanged
+            requested: RadarBeam,
+            requested_ticks: u32,
+
+            last_change_tick: Option<u32>,
+
+            widths: BeamWidths,
+
+            // width being stepped toward the current class, rad
+            width: f64,
+
+            // last width handed to set_radar_width, None when something else set it
+            applied: Option<f64>,
+        }
+
+        impl BeamController {
+            pub fn new(beam: RadarBeam, widths: BeamWidths) -> Self {
+                BeamController {
+                    current: beam,
+                    requested: beam,
+                    requested_ticks: 0,
+                    last_change_tick: None,
+                    widths,
+                    width: widths.width(beam),
+                    applied: None,
+                }
+            }
+
+            // ships ask every tick, the latest call in a tick wins
+            pub fn request(&mut self, beam: RadarBeam) {
+                if beam != self.requested {
+                    self.requested = beam;
+                    self.requested_ticks = 0;
+                }
+            }
+
+            pub fn width(&self) -> f64 {
+                self.width
+            }
+
+            pub fn ticks_until_change_allowed(&self, now: u32) -> u32 {
+                self.last_change_tick.map_or(0, |tick| (tick + BEAM_CHANGE_INTERVAL).saturating_sub(now))
+            }
+
+            // advances one tick and returns the width to use
+            pub fn update(&mut self, now: u32) -> f64 {
+                self.requested_ticks += 1;
+                if self.requested != self.current
+                    && self.requested_ticks >= BEAM_HOLD_TICKS
+                    && self.ticks_until_change_allowed(now) == 0
+                {
+                    trace!("beam {:?} -> {:?} after {} ticks requested, {} since the last change", self.current, self.requested,
+                        self.requested_ticks, self.last_change_tick.map_or(now, |tick| now - tick));
+                    self.current = self.requested;
+                    self.last_change_tick = Some(now);
+                }
+                let target = self.widths.width(self.current);
+                self.width = if target < self.width { (self.width * BEAM_NARROW_RATE).max(target) } else { target };
+                self.width
+            }
+
+            // only touches the radar when the width actually changed
+            pub fn apply(&mut self, now: u32) {
+                let width = self.update(now);
+                if self.applied != Some(width) {
+                    set_radar_width(width);
+                    self.applied = Some(width);
+                }
+            }
+
+            // something else set the width, the next apply has to set it again
+            pub fn invalidate(&mut self) {
+                self.applied = None;
+            }
+
+            pub fn status(&self, now: u32) -> String {
+                let pending = if self.requested != self.current { format!(", {:?} requested {} ticks", self.requested, self.requested_ticks) } else { String::new() };
+                format!("{:?} beam {:.3} rad{}, next change in {} ticks", self.current, self.width, pending, self.ticks_until_change_allowed(now))
+            }
+        }
+
+        // coarse self profiling for the per-tick execution budget
+        #[derive(Default)]
+        pub struct RadarStats {
+            tracks_processed: u32,
+            plots_associated: u32,
+            plots_duplicate: u32,
+
+            // proxy for tick time: track borrows plus gate checks
+            work_units: u32,
+
+            // shedding work because there are too many tracks
+            degraded: bool,
+        }
+
+        impl Radar {
+            // drops tracks nothing has plotted or reported for the profile's window,
+            // whether or not a plot came in this tick. provisional launch tracks run
+            // on their own clock in infer_launches
+            pub fn expire_tracks(&mut self, now: u32) {
+                let expiry = self.profile.track_expiry_ticks;
+                let mut coasted = Vec::new();
+                self.potential_targets.retain(|id, track| {
+                    let t = track.borrow();
+                    let expired = !matches!(t.source, TrackSource::Inferred { .. }) && now.saturating_sub(t.contact_tick) >= expiry;
+                    if expired && t.coast_until_tick.is_some_and(|until| now < until) {
+                        trace!("track {} expired, coasting as a ghost until {:?}", id, t.coast_until_tick);
+                        coasted.push((*id, track.clone()));
+                    } else if expired {
+                        trace!("track {} expired, last contact {}", id, t.contact_tick);
+                    }
+                    !expired
+                });
+                self.ghosts.extend(coasted);
+                self.ghosts.retain(|_, track| track.borrow().coast_until_tick.is_some_and(|until| now < until));
+            }
+
+            // keep id around as a ghost for ticks past its expiry, renewed on every call
+            pub fn coast(&mut self, id: u128, ticks: u32) {
+                let expiry = self.profile.track_expiry_ticks;
+                if let Some(track) = self.potential_targets.get(&id) {
+                    let mut t = track.borrow_mut();
+                    t.coast_until_tick = Some(t.contact_tick + expiry + ticks);
+                }
+            }
+
+            pub fn ghost_count(&self) -> usize {
+                self.ghosts.len()
+            }
+
+            // the nearest ghost whose gate, grown by how long it's been dead reckoned,
+            // takes position goes back in the store under its old id
+            fn revive_ghost(&mut self, position: Vec2, tick: u32) -> Option<u128> {
+                let (id, _) = self.ghosts.iter()
+                    .map(|(id, track)| {
+                        let t = track.borrow();
+                        let dt = tick.saturating_sub(t.contact_tick) as f64 / 60.0;
+                        let radius = t.gate.width + 0.5 * max_plausible_acceleration(t.contact_class) * dt * dt;
+                        (*id, (t.extrapolated(tick) - position).length(), radius)
+                    })
+                    .filter(|(_, distance, radius)| distance < radius)
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(id, distance, _)| (id, distance))?;
+                let track = self.ghosts.remove(&id)?;
+                trace!("{} ghost {} reacquired", self.name, id);
+                self.potential_targets.insert(id, track);
+                Some(id)
+            }
+
+            // the sweep's range band, the bracket when a consumer set one
+            fn sweep_range(&self, max_distance: f64) {
+                let (min, max) = self.sweep_bracket.unwrap_or((self.profile.ranges.min, max_distance));
+                set_radar_max_distance(max);
+                set_radar_min_distance(min);
+            }
+
+            // the radar mutates tracks in place, so every handle it gave out has to be
+            // released by the time it starts, debug builds check at the phase boundary
+            fn check_no_outstanding_borrows(&self, phase: &str) {
+                if cfg!(debug_assertions) {
+                    for (id, track) in self.potential_targets.iter() {
+                        debug_assert!(track.try_borrow_mut().is_ok(), "track {} still borrowed entering {}", id, phase);
+                    }
+                }
+            }
+
+            // copies every track out for the rest of the tick, again whenever a report
+            // changes them after sense
+            pub fn take_picture(&mut self) {
+                self.picture = RadarPicture { tick: current_tick(), tracks: self.potential_targets.values().map(read_track).collect() };
+            }
+
+            pub fn new(profile: RadarProfile, name: &str) -> Self {
+                Radar {
+                    name: name.to_string(),
+                    beam: BeamController::new(profile.initial_beam, profile.beam_widths),
+                    state: profile.initial_state,
+                    ticks_since_contact: 0,
+                    designated_target: None,
+                    cued: None,
+                    potential_targets: HashMap::new(),
+                    ghosts: HashMap::new(),
+                    id_gen: 0,
+                    profile,
+                    stats: RadarStats::default(),
+                    sweep_sector: None,
+                    sweep_bracket: None,
+                    friendlies: Vec::new(),
+                    own_acceleration: Vec2::new(0.0, 0.0),
+                    launches: LaunchInferences::default(),
+                    revisit: RevisitScheduler::new(),
+                    sweep_heading: heading(),
+                    sweep_ticks: 0,
+                    sectors: SweepSectors::new(heading()),
+                    picture: RadarPicture::default(),
+                    sweep_plots: Vec::new(),
+                }
+            }
+
+            // the weapons' current target, it's revisited at least at its desired rate
+            pub fn designate(&mut self, id: u128) {
+                self.designated_target = Some(id);
+            }
+
+            pub fn clear_designation(&mut self) {
+                self.designated_target = None;
+            }
+
+            // the designated target while its track lives
+            pub fn designated(&self) -> Option<u128> {
+                self.designated_target.filter(|id| self.still_tracking(*id))
+            }
+
+            // the beam favors this track on the next point without the weapons moving
+            // off their target
+            pub fn cue(&mut self, id: u128) {
+                self.cued = Some(id);
+            }
+
+            // follows the designated target while it lives and still passes keep, the
+            // ship's own pick only takes over when it scores clearly better
+            pub fn reselect(&mut self, candidate: Option<u128>, weights: &TargetWeights, keep: &dyn Fn(&RadarTrack) -> bool) -> Option<u128> {
+                let current = self.designated().filter(|id| keep(&self.potential_targets[id].borrow()));
+                let score = |id: &u128| self.score_track(*id, weights).total();
+                let next = match (current, candidate) {
+                    (Some(current), Some(candidate)) if worth_switching(score(&current), score(&candidate)) => {
+                        trace!("{} switching target {} -> {}", self.name, current, candidate);
+                        Some(candidate)
+                    },
+                    (Some(current), _) => Some(current),
+                    (None, candidate) => candidate,
+                };
+                self.designated_target = next;
+                next
+            }
+
+            // ticks since the track was last plotted, None once it's gone
+            pub fn plot_age(&self, id: u128) -> Option<u32> {
+                self.potential_targets.get(&id).map(|t| current_tick().saturating_sub(t.borrow().last_plot_tick))
+            }
+
+            // holds the beam on the designated track, only taken while that track is
+            // fresh so a lock that just fell back isn't thrown onto a stale track again
+            pub fn lock_designated(&mut self) {
+                let fresh = self.designated().and_then(|id| self.plot_age(id)).is_some_and(|age| age <= LOCK_LOST_TICKS);
+                if fresh && !matches!(self.state, RadarState::TargetFocus) {
+                    trace!("{} locking on track {:?}", self.name, self.designated_target);
+                    self.state = RadarState::TargetFocus;
+                }
+            }
+
+            pub fn release_lock(&mut self) {
+                if matches!(self.state, RadarState::TargetFocus) {
+                    self.state = RadarState::MediumRange;
+                }
+            }
+
+            pub fn search_state(&self) -> RadarState {
+                self.state
+            }
+
+            // scored from where we are now, heading included
+            pub fn score_track(&self, id: u128, weights: &TargetWeights) -> TargetScore {
+                target_score(&self.potential_targets[&id].borrow(), position_fixed(), velocity(), heading(), weights)
+            }
+
+            pub fn profile(&self) -> &RadarProfile {
+                &self.profile
+            }
+
+            pub fn launch_inferences(&self) -> LaunchInferences {
+                self.launches
+            }
+
+            // search sweep to run until contact moves it on
+            pub fn set_search_state(&mut self, state: RadarState) {
+                self.state = state;
+            }
+
+            // every gate that takes the plot is scored and the best match wins, where
+            // gates overlap the first one found would swap close tracks. a track that
+            // already has a plot from this tick can't take another
+            pub fn choose_track(&mut self, plot: &ScanResult, plot_tick: u32) -> Option<u128> {
+                let mut best: Option<(u128, f64)> = None;
+                for (id, track) in &self.potential_targets {
+                    let mut t = track.borrow_mut();
+                    self.stats.work_units += 1;
+                    // the launcher's own plots land right on a fresh provisional track
+                    let inferred = matches!(t.source, TrackSource::Inferred { .. });
+                    let accepts = !inferred || matches!(plot.class, Class::Missile | Class::Torpedo);
+                    if !accepts || t.plotted_at(plot_tick) || !t.check_gate(plot.position, plot_tick) {
+                        continue;
+                    }
+                    let score = association_score(t.plot_at_estimate(plot.position, plot_tick) - t.position, plot.velocity - t.velocity);
+                    if best.is_none_or(|(_, s)| score < s) {
+                        best = Some((*id, score));
+                    }
+                }
+                best.map(|(id, _)| id)
+            }
+
+            // a second return of something this sweep already plotted, folding it in as
+            // well would count one measurement twice or start a twin track off it
+            fn duplicate_plot(&mut self, plot: &ScanResult, plot_tick: u32) -> bool {
+                self.sweep_plots.retain(|(tick, _, _)| *tick == plot_tick);
+                let duplicate = self.sweep_plots.iter()
+                    .any(|(_, position, class)| *class == plot.class && (*position - plot.position).length() < DUPLICATE_PLOT_RADIUS);
+                if !duplicate {
+                    self.sweep_plots.push((plot_tick, plot.position, plot.class));
+                }
+                duplicate
+            }
+
+            // best scoring gate takes the plot, otherwise it starts a track
+            fn associate_plot(&mut self, plot: ScanResult) {
+                let plot_tick = current_tick().saturating_sub(PLOT_LATENCY_TICKS);
+                trace!("adding detection point");
+                trace!("potential_targets.len: {}", self.potential_targets.len());
+                if self.duplicate_plot(&plot, plot_tick) {
+                    trace!("dropping duplicate plot at {}", plot.position);
+                    self.stats.plots_duplicate += 1;
+                    return;
+                }
+                let id = match self.choose_track(&plot, plot_tick).or_else(|| self.revive_ghost(plot.position, plot_tick)) {
+                    Some(id) => id,
+                    None => {
+                        // new potential target discovered
+                        trace!("new target discovered");
+                        self.insert_new_potential_target(plot);
+                        return;
+                    },
+                };
+
+                trace!("associating new plot with track {}", id);
+                let track = match self.get_track(id) {
+                    Some(track) => track,
+                    None => return,
+                };
+                let mut t = track.borrow_mut();
+                self.stats.plots_associated += 1;
+                if t.probably_dead {
+                    // only notched, it's still out there
+                    trace!("track {} plotted again, not dead", id);
+                    t.probably_dead = false;
+                }
+                if matches!(t.source, TrackSource::Inferred { .. }) {
+                    self.launches.confirmed += 1;
+                }
+                // update current track with new data
+                t.push_plot(TimedScanResult { tick: plot_tick, scan: plot });
+                t.confirm_local(self.profile.gate_width);
+                t.update();
+            }
+
+            // nearest track inside the report's widened gate takes it, otherwise it
+            // starts a remote only track
+            fn associate_report(&mut self, report_position: Vec2, report_velocity: Vec2, class: Class, age: u32, sender: u8) {
+                self.check_no_outstanding_borrows("associate_report");
+                // bring the report up to now, it was already stale when it was sent
+                let report_position = report_position + report_velocity * (age as f64 / 60.0);
+
+                // a teammate tracking us, or our own broadcast coming back, designating
+                // it would have us chasing ourselves
+                if (report_position - position()).length() < OWN_ECHO_RADIUS {
+                    trace!("discarding remote report of own ship from {}", sender);
+                    return;
+                }
+
+                let width = remote_report_width(age);
+                let mut best: Option<(u128, f64)> = None;
+                for (id, track) in &self.potential_targets {
+                    let distance = track.borrow().distance_from(report_position);
+                    let accepted = distance < width || track.borrow_mut().check_gate(report_position, current_tick());
+                    if accepted && best.is_none_or(|(_, d)| distance < d) {
+                        best = Some((*id, distance));
+                    }
+                }
+
+                let best = best.map(|(id, _)| id).or_else(|| self.revive_ghost(report_position, current_tick()));
+                if let Some(track) = best.and_then(|id| self.get_track(id)) {
+                    let mut t = track.borrow_mut();
+                    if matches!(t.source, TrackSource::Inferred { .. }) {
+                        self.launches.confirmed += 1;
+                    }
+                    t.apply_remote_report(report_position, report_velocity, age, sender, current_tick());
+                    t.note_class(class);
+                    trace!("remote report from {} associated with track {}", sender, t.id);
+                } else {
+                    let id = self.new_id_gen();
+                    let mut track = RadarTrack::new(id, report_position, report_velocity, class, width, current_tick());
+                    track.source = TrackSource::Remote { sender };
+                    track.note_class(class);
+                    // the report it was made from
+                    track.plot_count = 1;
+                    self.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+                    trace!("remote report from {} created track {}", sender, id);
+                }
+            }
+
+            // drops provisional missiles nobody found, then seeds one at every launcher
+            // that cued this tick, headed our way
+            fn infer_launches(&mut self) {
+                let now = current_tick();
+                let before = self.potential_targets.len();
+                self.potential_targets.retain(|id, track| {
+                    let t = track.borrow();
+                    let stale = matches!(t.source, TrackSource::Inferred { .. }) && now.saturating_sub(t.first_tick) >= INFERRED_TRACK_TICKS;
+                    if stale {
+                        trace!("inferred launch {} never seen, dropping", id);
+                    }
+                    !stale
+                });
+                self.launches.expired += (before - self.potential_targets.len()) as u32;
+
+                let launchers: Vec<(u128, Vec2, Vec2)> = self.potential_targets.iter()
+                    .filter_map(|(id, track)| {
+                        let mut t = track.borrow_mut();
+                        std::mem::take(&mut t.launch_cue).then(|| (*id, t.position, t.velocity))
+                    })
+                    .collect();
+                for (launcher, launcher_position, launcher_velocity) in launchers {
+                    let toward = position() - launcher_position;
+                    if toward.length() < 1.0 {
+                        continue;
+                    }
+                    let id = self.new_id_gen();
+                    let velocity = launcher_velocity + toward.normalize() * INFERRED_MISSILE_SPEED;
+                    let mut track = RadarTrack::new(id, launcher_position, velocity, Class::Missile, INFERRED_GATE_RADIUS, now);
+                    track.source = TrackSource::Inferred { launcher };
+                    track.class = TrackType::Missile;
+                    self.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+                    self.launches.inferred += 1;
+                    trace!("track {} looks to have launched, provisional missile {}", launcher, id);
+                }
+            }
+
+            // everything worth a beam: friends and tracks we think are dead aren't. the
+            // designated target is due at least on its track while scan cadence
+            fn revisit_candidates(&self) -> Vec<RevisitCandidate> {
+                let own_position = position();
+                let own_velocity = velocity();
+                self.potential_targets.values()
+                    .filter_map(|track| {
+                        let t = track.borrow();
+                        if matches!(t.class, TrackType::Friend) || t.probably_dead {
+                            return None;
+                        }
+                        let line_of_sight = t.position - own_position;
+                        let range = line_of_sight.length();
+                        let relative_velocity = t.velocity - own_velocity;
+                        let cross_speed = if range > 0.0 {
+                            let radial = line_of_sight / range;
+                            (relative_velocity - radial * relative_velocity.dot(radial)).length()
+                        } else {
+                            relative_velocity.length()
+                        };
+                        let floor = if range < REVISIT_GUN_RANGE { QUALITY_GUN } else { QUALITY_ENGAGE };
+                        let mut desired_ticks = desired_revisit_ticks(range, cross_speed, t.quality, floor);
+                        if self.designated_target == Some(t.id) {
+                            desired_ticks = desired_ticks.min(designated_dwell_ticks(range));
+                        }
+                        Some(RevisitCandidate {
+                            id: t.id,
+                            last_plot_tick: t.last_plot_tick,
+                            desired_ticks,
+                        })
+                    })
+                    .collect()
+            }
+
+            // points a lock beam at one track, bracketed on its predicted range, None if
+            // the track is gone
+            fn focus_on(&self, id: u128) -> Option<LockBracket> {
+                let t = self.potential_targets.get(&id)?.borrow();
+
+                // both ends of the line of sight as they'll be when the beam is used, a
+                // hard maneuver otherwise swings the bearing out from under the lock
+                let dt = 1.0 / 60.0;
+                let own_future = self.predicted_own_position();
+                let own_future_velocity = velocity() + self.own_acceleration * dt;
+                let target_future = t.position + t.velocity * dt + 0.5 * t.acceleration * dt * dt;
+                let t_dir = target_future - own_future;
+                let t_dist = t_dir.length();
+                set_radar_heading(t_dir.angle());
+
+                // focus radar on the predicted range, opened up by how stale the last plot is
+                let ticks_since_plot = current_tick() - t.last_plot_tick;
+                let closing = closing_speed(t_dir, t.velocity - own_future_velocity);
+                let cross_range = t.gate.width + 0.5 * max_plausible_acceleration(t.contact_class) * (ticks_since_plot as f64 / 60.0).powi(2);
+                let bracket = lock_bracket(t_dist, closing, ticks_since_plot, cross_range);
+                set_radar_width(bracket.width);
+                set_radar_max_distance(bracket.max_distance);
+                set_radar_min_distance(bracket.min_distance);
+                Some(bracket)
+            }
+        }
+
+        pub trait RadarTracker {
+            // main loop for ships that don't maneuver, sense then point
+            fn radar_loop(&mut self);
+
+            // first thing in a tick: coast tracks, take this tick's plot, classify
+            fn sense(&mut self);
+
+            // last thing in a tick, once control has committed to an acceleration:
+            // aims the beam for next tick's scan from where that acceleration puts us
+            fn point(&mut self, commanded_acceleration: Vec2);
+
+            // kill inferred, the track coasts on until expiry but is no longer engageable
+            fn mark_probably_dead(&mut self, id: u128);
+
+            // counts the dry spell and escalates the sweep range when it runs long
+            fn update_search_range(&mut self, plot_bearing: Option<f64>);
+
+            // own position one tick out under the commanded acceleration
+            fn predicted_own_position(&self) -> Vec2;
+
+            // handle unique id creation
+            fn new_id_gen(&mut self) -> u128;
+
+            fn still_tracking(&self, id: u128) -> bool;
+
+            fn has_contacts(&self) -> bool;
+
+            // tracks worth engaging: not friendly, seen at least min_plots times, class passes the filter
+            fn engageable_contacts(&self, class_filter: &dyn Fn(Class) -> bool, min_plots: u32) -> Vec<u128>;
+            fn has_engageable_contacts(&self) -> bool;
+
+            // every track's state as of this tick's sense, for consumers that shouldn't
+            // hold track handles
+            fn picture(&self) -> &RadarPicture;
+
+            // a friendly ship is at position, tracks that line up with it get classified as friend
+            fn mark_friendly(&mut self, position: Vec2, velocity: Vec2);
+            fn classify_tracks(&mut self);
+
+            fn update_tracks(&mut self);
+
+            fn show_tracks(&self);
+
+            fn insert_new_potential_target(&mut self, plot: ScanResult);
+
+            // folds a local plot or a teammate's report into the one track store, each
+            // with its own noise assumptions
+            fn add_detection_point(&mut self, detection: Detection);
+
+            // closest locally observed tracks, for sharing over the radio
+            fn best_tracks(&self, count: usize) -> Vec<TrackSnapshot>;
+
+            fn get_closest_target_to_point(&self, point: Vec2) -> u128;
+
+            // best scoring track that passes the filter under the class's weights
+            fn select_target(&self, weights: &TargetWeights, filter: &dyn Fn(&RadarTrack) -> bool) -> Option<u128>;
+
+            // most threatening non-friendly track to a defended point, which is not necessarily us
+            fn select_threat(&self, defended_point: Vec2, defended_velocity: Vec2) -> Option<u128>;
+
+            // most threatening track that passes the filter, None if nothing does
+            fn select_threat_matching(&self, defended_point: Vec2, defended_velocity: Vec2, filter: &dyn Fn(&RadarTrack) -> bool) -> Option<u128>;
+
+            // closest track to point that passes the filter, None if nothing does
+            fn get_closest_target_matching(&self, point: Vec2, filter: &dyn Fn(&RadarTrack) -> bool) -> Option<u128>;
+
+            // None once the track has been pruned, ids go stale between ticks. the live
+            // handle is for the tracker itself, ships read picture() instead
+            fn get_track(&self, id: u128) -> Option<Rc<RefCell<RadarTrack>>>;
+
+            // locks radar to closest target
+            fn lock_radar_to_target(&mut self);
+
+            // beam class the ship wants, the radar decides when it actually changes
+            fn request_beam(&mut self, beam: RadarBeam);
+
+            // keep sweeps inside a sector around center, for when we know roughly where to look
+            fn bias_sweep(&mut self, center: f64, half_width: f64);
+            // and inside a range band, cleared along with the sector
+            fn bracket_sweep(&mut self, min_distance: f64, max_distance: f64);
+            fn clear_sweep_bias(&mut self);
+            fn next_sweep_heading(&mut self) -> f64;
+
+            // short range radar sweep
+            fn short_range_sweep(&self);
+            // performs a standard radar sweep
+            fn standard_radar_sweep(&self);
+            // performs a long range radar sweep
+            fn long_range_radar_sweep(&self);
+        }
+
+        // impl against Radar struct to remove dependency on Ship
+        impl RadarTracker for Radar {
+            fn radar_loop(&mut self) {
+                self.sense();
+                self.point(Vec2::new(0.0, 0.0));
+            }
+
+            fn sense(&mut self) {
+                self.check_no_outstanding_borrows("sense");
+                RADAR_UPDATING.store(true, Ordering::Relaxed);
+                self.stats = RadarStats::default();
+                self.launches = LaunchInferences::default();
+                self.stats.degraded = self.potential_targets.len() > DEGRADE_TRACK_THRESHOLD;
+                if self.stats.degraded {
+                    // drawing is the cheapest thing to give up
+                    DEBUG_DRAW_ACTIVE.store(false, Ordering::Relaxed);
+                }
+
+                self.update_tracks();
+                self.show_tracks();
+
+                let plot = scan();
+                let plot_bearing = plot.as_ref().map(|p| (p.position - position()).angle());
+                if let Some(plot) = plot {
+                    self.add_detection_point(Detection::Local(plot));
+                }
+                self.classify_tracks();
+                self.infer_launches();
+                RADAR_UPDATING.store(false, Ordering::Relaxed);
+                self.update_search_range(plot_bearing);
+
+                trace!("{} stats: tracks: {}, processed: {}, associated: {}, duplicate: {}, work: {}, degraded: {}",
+                    self.name, self.potential_targets.len(), self.stats.tracks_processed,
+                    self.stats.plots_associated, self.stats.plots_duplicate, self.stats.work_units, self.stats.degraded);
+                self.take_picture();
+                let picture = &self.picture;
+                let best_quality = picture.iter().filter(|s| !s.friendly && !s.probably_dead).map(|s| s.quality).fold(0.0, f64::max);
+                let dead = picture.iter().filter(|s| s.probably_dead).count();
+                hud!(HudLine::Radar, "{} tracks ({} probably dead), best q {:.2}, {:?}, {}{}", self.potential_targets.len(), dead, best_quality, self.state,
+                    self.beam.status(current_tick()), if self.stats.degraded { ", degraded" } else { "" });
+            }
+
+            fn point(&mut self, commanded_acceleration: Vec2) {
+                self.own_acceleration = commanded_acceleration;
+                let cued = self.cued.take();
+
+                // a cue takes the beam even from a lock, the lock picks up again next tick
+                if let Some(id) = cued.filter(|_| matches!(self.state, RadarState::TargetFocus)) {
+                    self.beam.invalidate();
+                    self.focus_on(id);
+                    return;
+                }
+
+                // a lock already sits on its track, otherwise the sweep shares the beam
+                // with whichever track is most overdue
+                if !matches!(self.state, RadarState::TargetFocus) {
+                    let candidates = self.revisit_candidates();
+                    let designated = cued.or(self.designated_target).filter(|id| self.still_tracking(*id));
+                    let choice = self.revisit.choose(current_tick(), &candidates, designated);
+                    hud!(HudLine::Revisit, "{}", self.revisit.status(choice));
+                    if let Revisit::Dwell(id) = choice {
+                        self.beam.invalidate();
+                        self.focus_on(id);
+                        return;
+                    }
+                    self.sweep_ticks += 1;
+                }
+
+                // the lock sizes its own beam
+                if matches!(self.state, RadarState::TargetFocus) {
+                    self.beam.invalidate();
+                } else {
+                    self.beam.apply(current_tick());
+                }
+
+                if !matches!(self.state, RadarState::TargetFocus) {
+                    self.sweep_heading = self.next_sweep_heading();
+                }
+                match self.state {
+                    RadarState::ShortRange => {self.short_range_sweep();},
+                    RadarState::MediumRange => {self.standard_radar_sweep();},
+                    RadarState::LongRange => {self.long_range_radar_sweep();},
+                    RadarState::TargetFocus => {self.lock_radar_to_target()},
+                }
+            }
+
+            fn mark_probably_dead(&mut self, id: u128) {
+                if let Some(track) = self.potential_targets.get(&id) {
+                    track.borrow_mut().probably_dead = true;
+                    // a kill inferred after sense shows up for the rest of this tick
+                    self.take_picture();
+                }
+            }
+
+            // the sweep only goes out to long range once it's gone quiet for a while
+            // and the contact history has faded, whichever comes last
+            fn update_search_range(&mut self, plot_bearing: Option<f64>) {
+                // friendlies don't count, a wingman on the scope shouldn't keep the radar
+                // from looking further out
+                let contact = plot_bearing.filter(|_| self.potential_targets.values().any(|t| t.borrow().engageable(0)));
+                if let Some(bearing) = contact {
+                    self.ticks_since_contact = 0;
+                    self.sectors.note_contact(bearing);
+                    if matches!(self.state, RadarState::LongRange) && self.profile.escalate_after_ticks > 0 {
+                        trace!("{} contact, back to medium range", self.name);
+                        self.state = RadarState::MediumRange;
+                    }
+                    return;
+                }
+                self.ticks_since_contact += 1;
+                let faded = self.sectors.decay(self.ticks_since_contact);
+                let escalate = self.profile.escalate_after_ticks;
+                let due = (self.ticks_since_contact == escalate && self.sectors.decayed()) || (self.ticks_since_contact > escalate && faded);
+                if matches!(self.state, RadarState::MediumRange) && escalate > 0 && due {
+                    trace!("{} no contact for {} ticks, escalating to long range", self.name, self.ticks_since_contact);
+                    self.state = RadarState::LongRange;
+                }
+            }
+
+            fn predicted_own_position(&self) -> Vec2 {
+                let dt = 1.0 / 60.0;
+                position_fixed() + velocity() * dt + 0.5 * self.own_acceleration * dt * dt
+            }
+
+            // use current value as next, then increment id counter
+            fn new_id_gen(&mut self) -> u128 {
+                let next = self.id_gen;
+                self.id_gen += 1;
+                trace!("new_id_gen: next: {}, incremented: {}", next, self.id_gen);
+                next
+            }
+
+            fn still_tracking(&self, id: u128) -> bool {
+                self.potential_targets.contains_key(&id)
+            }
+
+            fn has_contacts(&self) -> bool {
+                !self.potential_targets.is_empty()
+            }
+
+            fn insert_new_potential_target(&mut self, plot: ScanResult) {
+                let mut scans: VecDeque<TimedScanResult> = VecDeque::new();
+                trace!("insert_new_potential_target: new plot position: {}", plot.position);
+                let plot_tick = current_tick().saturating_sub(PLOT_LATENCY_TICKS);
+                scans.push_back(TimedScanResult { tick: plot_tick, scan: plot });
+                let id = self.new_id_gen();
+                // populate initial RadarTrack with baseline values, as of when the plot was
+                // taken so the first update coasts it up to now
+                let mut track = RadarTrack::new(id, plot.position, plot.velocity, plot.class, self.profile.gate_width, plot_tick);
+                track.class = track_type_for(track.contact_class, track.class_streak);
+                track.scans = scans;
+                self.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+            }
+
+            fn best_tracks(&self, count: usize) -> Vec<TrackSnapshot> {
+                // only share what we've seen ourselves so reports don't echo around the team
+                let mut tracks: Vec<TrackSnapshot> = self.potential_targets.values()
+                    .filter(|t| {
+                        let t = t.borrow();
+                        t.source.has_local() && t.engageable(ENGAGE_MIN_PLOTS)
+                    })
+                    .map(read_track)
+                    .collect();
+                tracks.sort_by(|a, b| a.distance_from(position()).total_cmp(&b.distance_from(position())));
+                tracks.truncate(count);
+                tracks
+            }
+
+            fn show_tracks(&self) {
+                if !debug_draw_active() {
+                    return;
+                }
+                for (id, track) in &self.potential_targets {
+                    let t = track.borrow();
+                    t.gate.draw_gate(*id, &t.class, t.quality);
+                    if layer_active(LAYER_TRAILS) {
+                        // fades out toward the oldest entry
+                        let n = t.history.len().max(1) as u32;
+                        for (i, (a, b)) in t.history.iter().zip(t.history.iter().skip(1)).enumerate() {
+                            let grey = 0x20 + 0x80 * (i as u32 + 1) / n;
+                            debug_line(LAYER_TRAILS, a.position, b.position, grey << 16 | grey << 8 | grey);
+                        }
+                    }
+                }
+            }
+
+            // iterate over existing tracks and call their update method
+            fn update_tracks(&mut self) {
+                self.expire_tracks(current_tick());
+                for (id, track) in &self.potential_targets {
+                    // when overloaded, distant tracks coast through alternate ticks
+                    if self.stats.degraded
+                        && (id + current_tick() as u128) % 2 == 1
+                        && track.borrow().distance_from(position()) > DISTANT_TRACK_RANGE {
+                        self.stats.work_units += 1;
+                        continue;
+                    }
+                    track.borrow_mut().update();
+                    self.stats.tracks_processed += 1;
+                    self.stats.work_units += 1;
+                }
+            }
+            fn engageable_contacts(&self, class_filter: &dyn Fn(Class) -> bool, min_plots: u32) -> Vec<u128> {
+                self.potential_targets.iter()
+                    .filter(|(_, t)| {
+                        let t = t.borrow();
+                        t.engageable(min_plots) && class_filter(t.contact_class)
+                    })
+                    .map(|(id, _)| *id)
+                    .collect()
+            }
+
+            fn has_engageable_contacts(&self) -> bool {
+                self.engageable_contacts(&|_| true, ENGAGE_MIN_PLOTS).iter()
+                    .any(|id| self.potential_targets[id].borrow().quality >= QUALITY_ENGAGE)
+            }
+
+            fn picture(&self) -> &RadarPicture {
+                &self.picture
+            }
+
+            fn mark_friendly(&mut self, position: Vec2, velocity: Vec2) {
+                self.friendlies.push((current_tick(), position, velocity));
+            }
+
+            // friend is sticky, tentative tracks become foes once confirmed by a second plot
+            fn classify_tracks(&mut self) {
+                self.friendlies.retain(|(tick, _, _)| current_tick() - tick < FRIENDLY_REPORT_TICKS);
+                for track in self.potential_targets.values() {
+                    let mut t = track.borrow_mut();
+                    if matches!(t.class, TrackType::Friend) {
+                        continue;
+                    }
+                    let friendly = self.friendlies.iter().any(|(tick, p, v)| {
+                        let predicted = *p + *v * ((current_tick() - tick) as f64 / 60.0);
+                        t.distance_from(predicted) < FRIENDLY_MATCH_RADIUS
+                    });
+                    if friendly {
+                        trace!("track {} classified friend", t.id);
+                        t.class = TrackType::Friend;
+                    } else if matches!(t.class, TrackType::Tentative) {
+                        t.class = track_type_for(t.contact_class, t.class_streak);
+                        if !matches!(t.class, TrackType::Tentative) {
+                            trace!("track {} classified {:?}", t.id, t.class);
+                        }
+                    }
+                }
+            }
+
+            fn get_closest_target_to_point(&self, point: Vec2) -> u128 {
+                self.get_closest_target_matching(point, &|_| true).unwrap_or(0)
+            }
+
+            fn select_target(&self, weights: &TargetWeights, filter: &dyn Fn(&RadarTrack) -> bool) -> Option<u128> {
+                let mut best: Option<(u128, TargetScore)> = None;
+                for (id, track) in &self.potential_targets {
+                    if !filter(&track.borrow()) {
+                        continue;
+                    }
+                    let score = self.score_track(*id, weights);
+                    if best.is_none_or(|(_, s)| score.total() > s.total()) {
+                        best = Some((*id, score));
+                    }
+                }
+                if let Some((id, score)) = best {
+                    trace!("{} best target {}: {:?}", self.name, id, score);
+                }
+                best.map(|(id, _)| id)
+            }
+
+            fn select_threat(&self, defended_point: Vec2, defended_velocity: Vec2) -> Option<u128> {
+                self.select_threat_matching(defended_point, defended_velocity, &|t| !matches!(t.class, TrackType::Friend))
+            }
+
+            fn select_threat_matching(&self, defended_point: Vec2, defended_velocity: Vec2, filter: &dyn Fn(&RadarTrack) -> bool) -> Option<u128> {
+                let mut best: Option<(u128, f64)> = None;
+                for (id, track) in &self.potential_targets {
+                    let t = track.borrow();
+                    if !t.engageable(ENGAGE_MIN_PLOTS) || !filter(&t) {
+                        continue;
+                    }
+                    let score = threat_score(&t, defended_point, defended_velocity);
+                    if score > 0.0 && best.is_none_or(|(_, s)| score > s) {
+                        best = Some((*id, score));
+                    }
+                }
+                best.map(|(id, _)| id)
+            }
+
+            fn get_closest_target_matching(&self, point: Vec2, filter: &dyn Fn(&RadarTrack) -> bool) -> Option<u128> {
+                let mut best: Option<(u128, f64)> = None;
+                for (id, track) in &self.potential_targets {
+                    let t = track.borrow();
+                    if !filter(&t) {
+                        continue;
+                    }
+                    let dist = t.distance_from(point);
+                    if best.is_none_or(|(_, d)| dist < d) {
+                        best = Some((*id, dist));
+                    }
+                }
+                best.map(|(id, _)| id)
+            }
+
+            fn get_track(&self, id: u128) -> Option<Rc<RefCell<RadarTrack>>> {
+                self.potential_targets.get(&id).map(Rc::clone)
+            }
+
+            fn add_detection_point(&mut self, detection: Detection) {
+                match detection {
+                    Detection::Local(plot) => self.associate_plot(plot),
+                    Detection::Remote { position, velocity, class, age, sender } => {
+                        self.associate_report(position, velocity, class, age, sender);
+                        self.take_picture();
+                    },
+                }
+            }
+
+            // stays on the designated track, not whatever happens to be closest, and
+            // gives up to the medium range sweep once the track has gone quiet
+            fn lock_radar_to_target(&mut self) {
+                let held = self.designated().map(|id| (id, self.plot_age(id)));
+                let id = match held {
+                    Some((id, Some(age))) if age <= LOCK_LOST_TICKS => id,
+                    _ => {
+                        trace!("{} lock lost ({:?}), back to sweeping", self.name, held);
+                        self.state = RadarState::MediumRange;
+                        self.beam.apply(current_tick());
+                        self.sweep_heading = self.next_sweep_heading();
+                        self.standard_radar_sweep();
+                        return;
+                    },
+                };
+                if let Some(bracket) = self.focus_on(id) {
+                    hud!(HudLine::Radar, "lock track {}: {:.0}-{:.0} m, width {:.3}, {} ticks since plot",
+                        id, bracket.min_distance, bracket.max_distance, bracket.width, self.plot_age(id).unwrap_or(0));
+                }
+            }
+
+            fn bias_sweep(&mut self, center: f64, half_width: f64) {
+                self.sweep_sector = Some((center, half_width));
+            }
+
+            fn bracket_sweep(&mut self, min_distance: f64, max_distance: f64) {
+                self.sweep_bracket = Some((min_distance.max(self.profile.ranges.min), max_distance));
+            }
+
+            fn clear_sweep_bias(&mut self) {
+                self.sweep_sector = None;
+                self.sweep_bracket = None;
+            }
+
+            // steps the beam one width around every dwell, restarting at the near edge
+            // of the sweep sector when stepping would leave it. counts search ticks only,
+            // revisits in between don't advance it. with no sector bias, leaving one of
+            // the history sectors lets SweepSectors pick where to go next
+            fn next_sweep_heading(&mut self) -> f64 {
+                if self.sweep_ticks % self.profile.dwell_ticks.max(1) != 0 {
+                    return self.sweep_heading;
+                }
+                let width = self.beam.width();
+                let next = self.sweep_heading + width;
+                match self.sweep_sector {
+                    Some((center, half_width)) if angle_diff(center, next).abs() > half_width => center - half_width + width / 2.0,
+                    Some(_) => next,
+                    None if SweepSectors::sector_of(next) == self.sectors.current => next,
+                    None => {
+                        let sector = self.sectors.choose();
+                        trace!("{} sweep sector {}, weight {:.2}", self.name, sector, self.sectors.weight(sector));
+                        SweepSectors::start_of(sector) + width / 2.0
+                    },
+                }
+            }
+
+            fn request_beam(&mut self, beam: RadarBeam) {
+                self.beam.request(beam);
+            }
+
+            fn short_range_sweep(&self) {
+                set_radar_heading(self.sweep_heading);
+                self.sweep_range(self.profile.ranges.short);
+            }
+
+            fn standard_radar_sweep(&self) {
+                set_radar_heading(self.sweep_heading);
+                self.sweep_range(self.profile.ranges.medium);
+            }
+
+            fn long_range_radar_sweep(&self) {
+                trace!("long range radar sweep");
+                set_radar_heading(self.sweep_heading);
+                self.sweep_range(self.profile.ranges.long);
+            }
+        }
+
+        // one track's weighted terms, kept apart so the ranking can be tuned from the log
+        #[derive(Debug, Clone, Copy)]
+        pub struct TargetScore {
+            pub distance: f64,
+            pub closing: f64,
+            pub class: f64,
+            pub aspect: f64,
+        }
+
+        impl TargetScore {
+            pub fn total(&self) -> f64 {
+                self.distance + self.closing + self.class + self.aspect
+            }
+        }
+
+        // how good a designation a track is for a ship at point, higher is better
+        pub fn target_score(track: &RadarTrack, point: Vec2, velocity: Vec2, heading: f64, weights: &TargetWeights) -> TargetScore {
+            let range = track.distance_from(point);
+            let closing = track.closing_speed_from(point, velocity);
+            let class = match track.contact_class {
+                Class::Missile | Class::Torpedo => 1.0,
+                Class::Fighter => 0.6,
+                Class::Frigate | Class::Cruiser => 0.3,
+                _ => 0.0,
+            };
+            let off_nose = angle_diff(heading, (track.position - point).angle()).abs();
+            TargetScore {
+                distance: weights.distance * TARGET_SCORE_RANGE / (range + TARGET_SCORE_RANGE),
+                closing: weights.closing * (closing / TARGET_SCORE_SPEED).clamp(-1.0, 1.0),
+                class: weights.class * class,
+                aspect: weights.aspect * (1.0 - off_nose / PI),
+            }
+        }
+
+        // how urgently a track needs attention from whoever defends defended_point
+        // closing speed on the defended point over range, weighted by what the contact is
+        // range is shortened by the distance the contact closes while it's being shot
+        // down, and a torpedo on course for the defended point outranks a missile
+        pub fn threat_score(track: &RadarTrack, defended_point: Vec2, defended_velocity: Vec2) -> f64 {
+            let closing = track.closing_speed_from(defended_point, defended_velocity);
+            let kill_distance = closing.max(0.0) * estimated_time_to_kill(track.contact_class);
+            let range = (track.distance_from(defended_point) - kill_distance).max(1.0);
+            let mut class_weight = match track.contact_class {
+                Class::Missile | Class::Torpedo => 3.0,
+                Class::Fighter => 1.0,
+                _ => 0.5,
+            };
+            if track.contact_class == Class::Torpedo
+                && track.distance_at_closest_approach(defended_point, defended_velocity) < TORPEDO_AIM_RADIUS
+            {
+                class_weight *= TORPEDO_DANGER;
+            }
+            // a contact that isn't closing still counts a little so it can be picked at all
+            (closing.max(0.0) + 1.0) / range * class_weight
+        }
+    }
+}
+
+// pure intercept math, nothing in here holds state between ticks
+// unit tested off-line, so new code takes its inputs as arguments and
+// leaves the oort_api reads to the callers where it can
+mod guidance {
+    use super::*;
+    pub use prediction::*;
+    pub use pn::*;
+    pub use turning::*;
+
+    // relative kinematics and gun lead solutions
+    mod prediction {
+        use super::*;
+
+        // relative kinematics, rel_p is target - observer position, rel_v is target - observer velocity
+        // closing speed is positive when the range is shrinking
+        pub fn closing_speed(rel_p: Vec2, rel_v: Vec2) -> f64 {
+            let range = rel_p.length();
+            if range < E {
+                return 0.0;
+            }
+            -(rel_v.dot(rel_p) / range)
+        }
+
+        // seconds until the range is smallest assuming both hold velocity, 0 if already opening
+        pub fn time_to_closest_approach(rel_p: Vec2, rel_v: Vec2) -> f64 {
+            let speed_squared = rel_v.dot(rel_v);
+            if speed_squared < E {
+                return 0.0;
+            }
+            (-(rel_p.dot(rel_v)) / speed_squared).max(0.0)
+        }
+
+        pub fn distance_at_closest_approach(rel_p: Vec2, rel_v: Vec2) -> f64 {
+            let t = time_to_closest_approach(rel_p, rel_v);
+            (rel_p + rel_v * t).length()
+        }
+
+        // relative lead vector, rel_p and rel_v are target minus shooter
+        pub fn lead_with_acceleration(rel_p: Vec2, rel_v: Vec2, target_acceleration: Vec2, projectile_speed: f64) -> Vec2 {
+            let flight_time = rel_p.length() / projectile_speed;
+            rel_p + rel_v * flight_time + 0.5 * target_acceleration * flight_time * flight_time
+        }
+
+        // the target half of a lead, as estimated by its track
+        #[derive(Debug, Clone, Copy)]
+        pub struct TargetMotion {
+            pub position: Vec2,
+            pub velocity: Vec2,
+            pub acceleration: Vec2,
+        }
+
+        // relative lead vector dt seconds from now, stepping both ships forward first:
+        // us with the acceleration we just commanded, the target with its estimate
+        pub fn predicted_lead(shooter: &Shooter, target: &TargetMotion, projectile_speed: f64, dt: f64) -> Vec2 {
+            let own_future = shooter.position + shooter.velocity * dt + 0.5 * shooter.acceleration * dt * dt;
+            let own_future_velocity = shooter.velocity + shooter.acceleration * dt;
+            let target_future = target.position + target.velocity * dt + 0.5 * target.acceleration * dt * dt;
+            let target_future_velocity = target.velocity + target.acceleration * dt;
+            lead_with_acceleration(target_future - own_future, target_future_velocity - own_future_velocity, target.acceleration, projectile_speed)
+        }
+
+        // target flown forward t seconds: along its turn when it has one, otherwise on
+        // the acceleration estimate, not both since a steady turn shows up in each
+        pub fn propagate_target(position: Vec2, velocity: Vec2, acceleration: Vec2, turn_rate: f64, t: f64) -> Vec2 {
+            if turn_rate.abs() < 1e-3 {
+                return position + velocity * t + 0.5 * acceleration * t * t;
+            }
+            // integral of the velocity rotating at turn_rate
+            let wt = turn_rate * t;
+            let (along, across) = (wt.sin() / turn_rate, (1.0 - wt.cos()) / turn_rate);
+            position + Vec2::new(velocity.x * along - velocity.y * across, velocity.x * across + velocity.y * along)
+        }
+
+        // the shooting ship when a gun solution is asked for
+        #[derive(Debug, Clone, Copy)]
+        pub struct Shooter {
+            pub position: Vec2,
+            pub velocity: Vec2,
+
+            // what we commanded this tick, world frame
+            pub acceleration: Vec2,
+            pub heading: f64,
+        }
+
+        impl Shooter {
+            pub fn own(acceleration: Vec2) -> Self {
+                Shooter { position: position(), velocity: velocity(), acceleration, heading: heading() }
+            }
+        }
+
+        // one gun's answer for one track, see intercept_for_gun
+        #[derive(Debug, Clone, Copy)]
+        pub struct FiringSolution {
+            // world frame bearing from the muzzle to the lead point
+            pub aim: f64,
+
+            // lead point relative to the muzzle
+            pub lead: Vec2,
+            pub flight_time: f64,
+
+            // how far off the lead the rounds pass fired now, m: the barrel's error
+            // for a gun along the nose plus the spread cone at that range
+            pub miss: f64,
+
+            // how far the target could be from the lead by the time the rounds get
+            // there, spending a share of its class's acceleration on a maneuver we
+            // can't see coming yet, m
+            pub dodge: f64,
+        }
+
+        // lead for one gun from its own muzzle at its own projectile speed, both ships
+        // a tick on so our maneuvering doesn't leave the solution behind, the flight
+        // timed on the target's velocity bent by half the turn it makes and the lead
+        // propagated on its full track state
+        // None for a gun without rounds or a target the rounds can't catch
+        pub fn intercept_for_gun(gun: &GunConfig, track: &TrackSnapshot, shooter: &Shooter) -> Option<FiringSolution> {
+            if gun.projectile_speed <= 0.0 {
+                return None;
+            }
+            let dt = 1.0 / 60.0;
+            let muzzle = shooter.position + gun.offset.rotate(shooter.heading) + shooter.velocity * dt + 0.5 * shooter.acceleration * dt * dt;
+            let muzzle_velocity = shooter.velocity + shooter.acceleration * dt;
+            let target = track.position + track.velocity * dt + 0.5 * track.acceleration * dt * dt;
+            let target_velocity = track.velocity + track.acceleration * dt;
+            let rel_p = target - muzzle;
+
+            let straight = projectile_intercept_time(rel_p, target_velocity - muzzle_velocity, gun.projectile_speed)?;
+            let turning = target_velocity.rotate(track.turn_rate * straight / 2.0) - muzzle_velocity;
+            let flight_time = projectile_intercept_time(rel_p, turning, gun.projectile_speed)?;
+            // the round inherits our velocity, so it's the target's motion relative to us
+            let lead = propagate_target(rel_p, target_velocity, track.acceleration, track.turn_rate, flight_time) - muzzle_velocity * flight_time;
+
+            let barrel = if gun.turret { lead.angle() } else { shooter.heading };
+            Some(FiringSolution {
+                aim: lead.angle(),
+                lead,
+                flight_time,
+                miss: predicted_miss_distance(barrel, lead) + gun.spread * lead.length(),
+                dodge: 0.5 * MANEUVER_UNCERTAINTY_SHARE * max_plausible_acceleration(track.contact_class) * flight_time * flight_time,
+            })
+        }
+
+        // a round fired now along heading against a target holding its velocity, at
+        // the moment they're closest
+        #[derive(Debug, Clone, Copy)]
+        pub struct RoundPass {
+            // separation then, m
+            pub miss: f64,
+
+            // where the round is then, world frame
+            pub impact: Vec2,
+            pub time: f64,
+        }
+
+        // the round flies at projectile_speed along heading plus the shooter's velocity,
+        // the closest approach is only looked for inside max_flight_time
+        pub fn round_pass(muzzle: Vec2, shooter_velocity: Vec2, heading: f64, projectile_speed: f64, target_position: Vec2, target_velocity: Vec2, max_flight_time: f64) -> RoundPass {
+            let round_velocity = shooter_velocity + Vec2::new(projectile_speed, 0.0).rotate(heading);
+            let rel_p = target_position - muzzle;
+            let rel_v = target_velocity - round_velocity;
+            let time = time_to_closest_approach(rel_p, rel_v).min(max_flight_time.max(0.0));
+            RoundPass { miss: (rel_p + rel_v * time).length(), impact: muzzle + round_velocity * time, time }
+        }
+
+        // how far off the lead a round fired along heading passes, m. a round fired
+        // more than a right angle off only opens from it, so it misses by the range
+        pub fn predicted_miss_distance(heading: f64, lead: Vec2) -> f64 {
+            let error = angle_diff(heading, lead.angle()).abs();
+            if error > PI / 2.0 {
+                return lead.length();
+            }
+            lead.length() * error.sin()
+        }
+
+        // a friendly, as (position, velocity), that will be ahead of us short of range
+        // and within width of the line along heading when the rounds get that far
+        // would catch the rounds meant for the target. the corridor widens downrange
+        pub fn friendly_in_corridor(origin: Vec2, origin_velocity: Vec2, heading: f64, projectile_speed: f64, range: f64,
+            friendlies: &[(Vec2, Vec2)], width: f64) -> bool {
+            let line = Vec2::new(1.0, 0.0).rotate(heading);
+            friendlies.iter().any(|(p, v)| {
+                // in our frame the rounds run out along line at projectile speed
+                let rel = *p - origin;
+                let rel_v = *v - origin_velocity;
+                let closing = projectile_speed - rel_v.dot(line);
+                if closing <= 0.0 {
+                    return false;
+                }
+                let t = rel.dot(line) / closing;
+                let rel = rel + rel_v * t;
+                let along = rel.dot(line);
+                let across = (rel.x * line.y - rel.y * line.x).abs();
+                along > 0.0 && along < range && across < width + along * FRIENDLY_CORRIDOR_SPREAD
+            })
+        }
+
+        // a friendly closer than range within half_angle of the launch bearing, the
+        // missile would fly its boost right through it
+        pub fn friendly_in_launch_cone(origin: Vec2, bearing: f64, range: f64, friendlies: &[Vec2], half_angle: f64) -> bool {
+            friendlies.iter().any(|p| {
+                let rel = *p - origin;
+                rel.length() < range && angle_diff(bearing, rel.angle()).abs() < half_angle
+            })
+        }
+
+        // discriminant is the part under the sqrt when solved for x
+        // d = b^2-4ac
+        pub fn get_smallest_quadratic_solution(a: f64, b: f64, c: f64) -> f64 {
+            let discriminant: f64 = (b * b) - (4.0 * a * c);
+
+            if discriminant < 0.0 {
+                return -1.0;
+            }
+
+            // solved for x: x = (-b +/- sqrt(b^2-4ac)) / 2a
+            // getting one or both x solutions from above
+            // only the positive solutions are useful
+            let s: f64 = discriminant.sqrt();
+            let x2: f64 = (-b + s) / (2.0 * a);
+            let x1: f64 = (-b - s) / (2.0 * a);
+            if x2 > 0.0 && x1 > 0.0 {
+                return x2.min(x1);
+            } else if x1 > 0.0 {
+                return x1;
+            } else if x2 > 0.0 {
+                return x2;
+            }
+            return -1.0; //no positive solution
+        }
+
+        // time for a projectile at projectile_speed to meet a target at rel_p moving at rel_v
+        // solves |rel_p + rel_v * t| = projectile_speed * t, None if it can never catch it
+        pub fn projectile_intercept_time(rel_p: Vec2, rel_v: Vec2, projectile_speed: f64) -> Option<f64> {
+            let a: f64 = rel_v.dot(rel_v) - (projectile_speed * projectile_speed);
+            let b: f64 = 2.0 * rel_p.dot(rel_v);
+            let c: f64 = rel_p.dot(rel_p);
+
+            // a target exactly as fast as the round leaves no t^2 term to divide by,
+            // what's left is linear and only has an answer when it's coming at us
+            let t = if a.abs() < E * c.max(1.0) {
+                if b < 0.0 { -c / b } else { -1.0 }
+            } else {
+                get_smallest_quadratic_solution(a, b, c)
+            };
+            if t <= 0.0 {
+                return None;
+            }
+            Some(t)
+        }
+
+        // seconds until a round fired now from the shooter meets the target, the
+        // round inherits the shooter's velocity, None if it can never catch up
+        pub fn time_to_intercept(shooter_position: Vec2, shooter_velocity: Vec2, target_position: Vec2, target_velocity: Vec2, projectile_speed: f64) -> Option<f64> {
+            projectile_intercept_time(target_position - shooter_position, target_velocity - shooter_velocity, projectile_speed)
+        }
+
+        // the one lead every unguided shot starts from: the aim point in the shooter's
+        // moving frame, the target carried along by the relative velocity, so minus the
+        // shooter position it is the barrel direction, not the world meeting point
+        // None if a round fired now can never catch up
+        pub fn intercept_point(shooter_position: Vec2, shooter_velocity: Vec2, target_position: Vec2, target_velocity: Vec2, projectile_speed: f64) -> Option<Vec2> {
+            let t = time_to_intercept(shooter_position, shooter_velocity, target_position, target_velocity, projectile_speed)?;
+            Some(target_position + (target_velocity - shooter_velocity) * t)
+        }
+
+        // rotating adjusts base position(), this fixes that
+        pub fn position_fixed() -> Vec2 {
+            position() + vec2(FIGHTER_GUN_OFFSET, 0.0).rotate(heading())
+        }
+    }
+
+    // missile guidance: proportional navigation, intercept planning and fusing
+    mod pn {
+        use super::*;
+
+        // lateral acceleration across the line of sight, positive to the left of it
+        // plain PN nulls the LOS rate, which a steady turn keeps regenerating, so the
+        // target's own lateral acceleration is fed forward on top, weighted by apn_weight
+        pub fn augmented_pn(rel_p: Vec2, rel_v: Vec2, target_acceleration: Vec2, nav_gain: f64, apn_weight: f64) -> f64 {
+            let range = rel_p.length();
+            if range < E {
+                return 0.0;
+            }
+            let normal = (rel_p / range).rotate(PI / 2.0);
+            let los_rate = (rel_p.x * rel_v.y - rel_p.y * rel_v.x) / (range * range);
+            let target_lateral = target_acceleration.dot(normal);
+            nav_gain * closing_speed(rel_p, rel_v) * los_rate + 0.5 * nav_gain * apn_weight * target_lateral
+        }
+
+        // world frame command for a lateral demand, the lateral part gets what's
+        // available first and whatever is left pushes down the line of sight
+        pub fn pn_command(rel_p: Vec2, lateral: f64, available: f64) -> Vec2 {
+            let range = rel_p.length();
+            if range < E {
+                return Vec2::new(0.0, 0.0);
+            }
+            let los = rel_p / range;
+            let lateral = lateral.clamp(-available, available);
+            let along = (available * available - lateral * lateral).sqrt();
+            los * along + los.rotate(PI / 2.0) * lateral
+        }
+
+        // point to swing around to so the final run comes in offset from the launcher's
+        // approach bearing (measured out from the target), None once we're on that side
+        pub fn dogleg_waypoint(target: Vec2, missile: Vec2, approach: f64, offset: f64, radius: f64) -> Option<Vec2> {
+            let axis = approach + offset;
+            let aspect = (missile - target).angle();
+            if angle_diff(aspect, axis).abs() < DOGLEG_ALIGNED {
+                return None;
+            }
+            Some(target + Vec2::new(radius, 0.0).rotate(axis))
+        }
+
+        // the smoothed acceleration estimate is slow to see a break turn, the history's
+        // turn rate sees it sooner. across the velocity the turn's centripetal term
+        // replaces the estimate, along it the estimate stays
+        pub fn break_turn_acceleration(velocity: Vec2, acceleration: Vec2, turn_rate: f64, limit: f64) -> Vec2 {
+            let speed = velocity.length();
+            if speed < E {
+                return acceleration;
+            }
+            let along = velocity / speed;
+            let turning = along * acceleration.dot(along) + velocity.rotate(PI / 2.0) * turn_rate;
+            if turning.length() > limit {
+                turning.normalize() * limit
+            } else {
+                turning
+            }
+        }
+
+        // a noisy acceleration estimate fed forward makes the missile porpoise, so the
+        // term only fades in between missile launch quality and gun quality
+        pub fn apn_weight(apn_gain: f64, quality: f64) -> f64 {
+            apn_gain * ((quality - QUALITY_MISSILE) / (QUALITY_GUN - QUALITY_MISSILE)).clamp(0.0, 1.0)
+        }
+
+        // average speed over a run of range meters starting at speed, burning at accel
+        // until the delta_v budget is spent and coasting after that
+        pub fn achievable_average_speed(range: f64, speed: f64, accel: f64, delta_v: f64) -> f64 {
+            if accel < E || range < E {
+                return speed.max(E);
+            }
+            let burn_time = delta_v.max(0.0) / accel;
+            let burn_distance = speed * burn_time + 0.5 * accel * burn_time * burn_time;
+            let time = if range <= burn_distance {
+                (-speed + (speed * speed + 2.0 * accel * range).sqrt()) / accel
+            } else {
+                burn_time + (range - burn_distance) / (speed + delta_v).max(E)
+            };
+            range / time.max(E)
+        }
+
+        // lead point for a missile that still has to accelerate, the average speed it
+        // manages over the run stands in for a projectile speed
+        pub fn missile_intercept_point(missile_position: Vec2, missile_velocity: Vec2, target_position: Vec2, target_velocity: Vec2, accel: f64, delta_v: f64) -> Vec2 {
+            let mut intercept = target_position;
+            // the run length depends on the lead point, two passes are plenty
+            for _ in 0..2 {
+                let range = (intercept - missile_position).length();
+                let speed = achievable_average_speed(range, missile_velocity.length(), accel, delta_v);
+                intercept = intercept_point(missile_position, Vec2::zero(), target_position, target_velocity, speed).unwrap_or(target_position);
+            }
+            intercept
+        }
+
+        // seconds for a missile launched from here to reach the target, None when the
+        // feasibility check says it can't get there on the fuel it has
+        pub fn missile_flight_time(missile_position: Vec2, missile_velocity: Vec2, target_position: Vec2, target_velocity: Vec2, accel: f64, delta_v: f64) -> Option<f64> {
+            if !intercept_feasible(target_position - missile_position, target_velocity - missile_velocity, accel, delta_v) {
+                return None;
+            }
+            let intercept = missile_intercept_point(missile_position, missile_velocity, target_position, target_velocity, accel, delta_v);
+            let range = (intercept - missile_position).length();
+            Some(range / achievable_average_speed(range, missile_velocity.length(), accel, delta_v))
+        }
+
+        // detonate now if inside the fuse radius, or if the closest approach falls
+        // before the next tick and would pass inside it
+        pub fn cpa_fuse(rel_p: Vec2, rel_v: Vec2, fuse_radius: f64, pass_radius: f64, dt: f64) -> bool {
+            if rel_p.length() < fuse_radius {
+                return true;
+            }
+            if time_to_closest_approach(rel_p, rel_v) <= dt && distance_at_closest_approach(rel_p, rel_v) < fuse_radius {
+                return true;
+            }
+            // the closest approach went by since last tick, inside pass_radius it's
+            // the nearest we'll get so take it
+            let previous = rel_p - rel_v * dt;
+            closing_speed(rel_p, rel_v) < 0.0 && time_to_closest_approach(previous, rel_v) <= dt
+                && distance_at_closest_approach(previous, rel_v) < pass_radius
+        }
+
+        // rough delta-v check for ordnance: cancel the crossing velocity, turn an opening
+        // target into a closing one, and get the crossing component killed before the
+        // closest approach would have happened anyway
+        pub fn intercept_feasible(rel_p: Vec2, rel_v: Vec2, max_accel: f64, fuel: f64) -> bool {
+            let range = rel_p.length();
+            if range < E {
+                return true;
+            }
+            let los = rel_p / range;
+            let closing = closing_speed(rel_p, rel_v);
+            let crossing = (rel_v - los * rel_v.dot(los)).length();
+
+            let required_delta_v = crossing + (-closing).max(0.0);
+            if required_delta_v > fuel {
+                return false;
+            }
+            if closing > 0.0 && max_accel > 0.0 {
+                return crossing / max_accel < range / closing;
+            }
+            max_accel > 0.0
+        }
+
+        // returns desired world frame acceleration, caller applies it
+        // nav_gain is N, apn_weight 0.0 is plain PN, see apn_weight() for ramping it on
+        // track quality. available is what the caller's fuel plan allows, weave is
+        // extra lateral demand on top of the PN term, m/s/s
+        pub fn seek(p: Vec2, v: Vec2, a: Vec2, nav_gain: f64, apn_weight: f64, available: f64, weave: f64) -> Vec2 {
+            let rel_p = p - position();
+            let demand = augmented_pn(rel_p, v - velocity(), a, nav_gain, apn_weight) + weave;
+            let command = pn_command(rel_p, demand, available);
+
+            // demanded against available across the line of sight, saturation shows
+            // as the yellow running past the green
+            let normal = rel_p.normalize().rotate(PI / 2.0);
+            debug_line(LAYER_LEADS, position(), position() + normal * available, 0x00ff00);
+            debug_line(LAYER_LEADS, position(), position() + normal * demand, 0xffff00);
+            hud!(HudLine::Guidance, "lateral {:.0}/{:.0}", demand, available);
+            command
+        }
+    }
+
+    // heading control: torque commands and angle helpers
+    mod turning {
+        use super::*;
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum RotationPhase {
+            Accelerate,
+            Brake,
+            Hold,
+        }
+
+        pub struct Rotator {
+            // current movement estimated ticks to accomplish desired rotation
+            pub estimated_ticks_to_angle: u32,
+            // fraction of max angular acceleration commanded last, signed
+            pub throttle: f64,
+            pub phase: RotationPhase,
+        }
+
+        impl Rotator {
+            pub fn new() -> Self {
+                Rotator {
+                    estimated_ticks_to_angle: 0,
+                    throttle: 0.0,
+                    phase: RotationPhase::Hold,
+                }
+            }
+
+            // time optimal bang-bang onto the heading, then a pd hold once the error
+            // and the spin both fit inside the hold angle. returns the torque
+            pub fn command(&mut self, heading_error: f64, angular_velocity: f64, max_angular_acceleration: f64) -> f64 {
+                let stopping_angle = angular_velocity * angular_velocity / (2.0 * max_angular_acceleration);
+                let torque = if heading_error.abs() < ROTATION_HOLD_ANGLE && stopping_angle < ROTATION_HOLD_ANGLE {
+                    self.phase = RotationPhase::Hold;
+                    TurnController::new(ROTATION_HOLD_GAIN, max_angular_acceleration).command(0.0, heading_error, angular_velocity)
+                } else {
+                    let torque = bang_bang_torque(heading_error, angular_velocity, max_angular_acceleration);
+                    self.phase = if torque * angular_velocity < 0.0 { RotationPhase::Brake } else { RotationPhase::Accelerate };
+                    torque
+                };
+                self.throttle = torque / max_angular_acceleration;
+                self.estimated_ticks_to_angle = (turn_time(heading_error, angular_velocity, max_angular_acceleration) * 60.0).ceil() as u32;
+                torque
+            }
+        }
+
+        #[derive(Debug)]
+        pub enum Quadrant {One = 1,Two = 2,Three = 3,Four = 4,}
+
+        pub trait UnitCircleQuadrant {
+            fn get_quadrant(&self) -> Quadrant;
+            fn get_relative_quadrant(&self, other: Vec2) -> Quadrant;
+        }
+
+        impl UnitCircleQuadrant for Vec2 {
+            // returns the quadrant that SELF is in
+            fn get_quadrant(&self) -> Quadrant {
+                if self.x > 0.0 && self.y > 0.0 { Quadrant::One }
+                else if self.x < 0.0 && self.y > 0.0 { Quadrant::Two }
+                else if self.x < 0.0 && self.y < 0.0 { Quadrant::Three }
+                else { Quadrant::Four }
+            }
+
+            // returns relative quadrant of SELF compared to OTHER
+            // i.e. target_position.get_relative_quadrant(ship.position)
+            fn get_relative_quadrant(&self, other: Vec2) -> Quadrant {
+                if self.x > other.x && self.y > other.y { Quadrant::One }
+                else if self.x < other.x && self.y > other.y { Quadrant::Two }
+                else if self.x < other.x && self.y < other.y { Quadrant::Three }
+                else { Quadrant::Four }
+            }
+        }
+
+        // critically damped pd onto a heading, for torque(). gains come from the hull's
+        // max angular acceleration so the same presets work on every class, and the
+        // output never asks for more than the hull can do
+        #[derive(Debug, Clone, Copy)]
+        pub struct TurnController {
+            pub kp: f64,
+            pub kd: f64,
+            pub max_torque: f64,
+        }
+
+        impl TurnController {
+            pub fn new(kp: f64, max_torque: f64) -> Self {
+                TurnController { kp, kd: 2.0 * kp.sqrt(), max_torque }
+            }
+
+            // full torque from saturation_angle of error on
+            pub fn saturating_at(saturation_angle: f64, max_angular_acceleration: f64) -> Self {
+                Self::new(max_angular_acceleration / saturation_angle, max_angular_acceleration)
+            }
+
+            pub fn for_hull(max_angular_acceleration: f64) -> Self {
+                Self::saturating_at(TURN_SATURATION_ANGLE, max_angular_acceleration)
+            }
+
+            // holding the nose on a lead while the gun fires
+            pub fn precise(max_angular_acceleration: f64) -> Self {
+                Self::saturating_at(TURN_PRECISE_SATURATION_ANGLE, max_angular_acceleration)
+            }
+
+            // getting the hull around as fast as possible
+            pub fn dodge(max_angular_acceleration: f64) -> Self {
+                Self::saturating_at(TURN_DODGE_SATURATION_ANGLE, max_angular_acceleration)
+            }
+
+            pub fn command(&self, current_heading: f64, target_heading: f64, angular_velocity: f64) -> f64 {
+                let error = angle_diff(current_heading, target_heading);
+                (self.kp * error - self.kd * angular_velocity).clamp(-self.max_torque, self.max_torque)
+            }
+        }
+
+        // full torque toward the error until the stopping angle at max deceleration
+        // reaches what's left, then full torque against the spin
+        pub fn bang_bang_torque(heading_error: f64, angular_velocity: f64, max_angular_acceleration: f64) -> f64 {
+            let stopping_angle = angular_velocity * angular_velocity / (2.0 * max_angular_acceleration);
+            let turning_toward = angular_velocity * heading_error > 0.0;
+            if turning_toward && stopping_angle >= heading_error.abs() {
+                -angular_velocity.signum() * max_angular_acceleration
+            } else {
+                heading_error.signum() * max_angular_acceleration
+            }
+        }
+
+        // seconds for a bang-bang turn to take out heading_error and arrive at rest.
+        // spinning the wrong way, or too fast to stop in time, we first stop and
+        // then turn from rest over whatever is left
+        pub fn turn_time(heading_error: f64, angular_velocity: f64, max_angular_acceleration: f64) -> f64 {
+            let error = heading_error.abs();
+            let speed = angular_velocity.abs();
+            let stopping_angle = speed * speed / (2.0 * max_angular_acceleration);
+            if angular_velocity * heading_error >= 0.0 && stopping_angle <= error {
+                // speed up to the peak then brake, area under the triangle is the error
+                let peak = (max_angular_acceleration * error + speed * speed / 2.0).sqrt();
+                (2.0 * peak - speed) / max_angular_acceleration
+            } else {
+                let stop = speed / max_angular_acceleration;
+                let remaining = if angular_velocity * heading_error >= 0.0 { stopping_angle - error } else { stopping_angle + error };
+                stop + 2.0 * (remaining / max_angular_acceleration).sqrt()
+            }
+        }
+
+        // most the heading can move over the next dt, spinning at angular_velocity
+        // and accelerating flat out the same way
+        pub fn max_heading_change(angular_velocity: f64, max_angular_acceleration: f64, dt: f64) -> f64 {
+            angular_velocity.abs() * dt + 0.5 * max_angular_acceleration * dt * dt
+        }
+
+        // steps previous toward desired by at most max_step, the short way across +/-PI
+        pub fn rate_limited_bearing(previous: f64, desired: f64, max_step: f64) -> f64 {
+            previous + angle_diff(previous, desired).clamp(-max_step, max_step)
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum RotationIntent {
+            Torque(f64),
+            Turn(f64),
+        }
+
+        // how much a behavior's requests count for when several want the controls
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+        pub enum ControlPriority {
+            #[default]
+            Routine,
+            Engage,
+            Evade,
+        }
+
+        // one tick of control as the decide stage wants it, later requests replace
+        // earlier ones of the same or lower priority and nothing reaches the game
+        // until flush
+        #[derive(Debug, Default)]
+        pub struct ControlIntents {
+            pub acceleration: Option<Vec2>,
+            pub rotation: Option<RotationIntent>,
+
+            // gun indices to fire, each at most once
+            pub fire: Vec<usize>,
+
+            // the behavior whose requests are coming in now, and who holds each control
+            submitter: (ControlPriority, &'static str),
+            pub acceleration_by: Option<(ControlPriority, &'static str)>,
+            pub rotation_by: Option<(ControlPriority, &'static str)>,
+        }
+
+        // the game's control calls, behind a trait so a tick can run against a recorder
+        pub trait ControlSink {
+            fn accelerate(&mut self, acceleration: Vec2);
+            fn torque(&mut self, amount: f64);
+            fn turn(&mut self, speed: f64);
+            fn fire(&mut self, index: usize);
+        }
+
+        pub struct GameControls;
+
+        impl ControlSink for GameControls {
+            fn accelerate(&mut self, acceleration: Vec2) {
+                accelerate(acceleration);
+            }
+
+            fn torque(&mut self, amount: f64) {
+                torque(amount);
+            }
+
+            fn turn(&mut self, speed: f64) {
+                turn(speed);
+            }
+
+            fn fire(&mut self, index: usize) {
+                fire(index);
+            }
+        }
+
+        impl ControlIntents {
+            // requests from here on are made by name at priority
+            pub fn behavior(&mut self, priority: ControlPriority, name: &'static str) {
+                self.submitter = (priority, name);
+            }
+
+            fn outranked(&self, holder: Option<(ControlPriority, &'static str)>) -> bool {
+                match holder {
+                    Some((priority, name)) if priority > self.submitter.0 => {
+                        trace!("{} outranked by {}", self.submitter.1, name);
+                        true
+                    },
+                    _ => false,
+                }
+            }
+
+            pub fn accelerate(&mut self, acceleration: Vec2) {
+                if self.outranked(self.acceleration_by) {
+                    return;
+                }
+                self.acceleration = Some(acceleration);
+                self.acceleration_by = Some(self.submitter);
+            }
+
+            fn rotate(&mut self, intent: RotationIntent) {
+                if self.outranked(self.rotation_by) {
+                    return;
+                }
+                self.rotation = Some(intent);
+                self.rotation_by = Some(self.submitter);
+            }
+
+            pub fn torque(&mut self, amount: f64) {
+                self.rotate(RotationIntent::Torque(amount));
+            }
+
+            pub fn turn(&mut self, speed: f64) {
+                self.rotate(RotationIntent::Turn(speed));
+            }
+
+            pub fn turn_to(&mut self, target_heading: f64) {
+                self.torque(TurnController::for_hull(max_angular_acceleration()).command(heading(), target_heading, angular_velocity()));
+            }
+
+            pub fn fire(&mut self, index: usize) {
+                if !self.fire.contains(&index) {
+                    self.fire.push(index);
+                }
+            }
+
+            // exactly one accelerate, zero if nobody asked since accelerate() doesn't
+            // carry over, at most one rotation, then the guns, and starts the next tick empty
+            pub fn flush(&mut self, sink: &mut dyn ControlSink) {
+                sink.accelerate(self.acceleration.unwrap_or(Vec2::new(0.0, 0.0)));
+                match self.rotation {
+                    Some(RotationIntent::Torque(amount)) => sink.torque(amount),
+                    Some(RotationIntent::Turn(speed)) => sink.turn(speed),
+                    None => {},
+                }
+                for &index in &self.fire {
+                    sink.fire(index);
+                }
+                let name = |by: Option<(ControlPriority, &'static str)>| by.map_or("-", |(_, name)| if name.is_empty() { "?" } else { name });
+                hud!(HudLine::Nav, "thrust by {}, rotation by {}", name(self.acceleration_by), name(self.rotation_by));
+                *self = ControlIntents::default();
+            }
+        }
+    }
+}
+
+// per-class behavior, the router in Ship picks one of these by class()
+// classes share the maneuvering and formation pieces and own their tick()
+mod ships {
+    use super::*;
+    pub use stats::*;
+    pub use maneuver::*;
+    pub use formation::*;
+    pub use fighter::*;
+    pub use missile::*;
+    pub use frigate::*;
+    pub use cruiser::*;
+    pub use fallback::*;
+
+    // deterministic per-ship randomness so a run can be replayed
+    // seeded from the quantized spawn position and class, or FORCED_SEED when set
+    pub struct ShipRng {
+        state: u64,
+    }
+
+    impl ShipRng {
+        pub fn from_spawn() -> Self {
+            let seed = FORCED_SEED.unwrap_or_else(|| {
+                let x = (position().x / 10.0).round() as i64 as u64;
+                let y = (position().y / 10.0).round() as i64 as u64;
+                let c = class_to_code(class()) as u64;
+                splitmix64(splitmix64(splitmix64(x) ^ y) ^ c)
+            });
+            trace!("rng seed: {}", seed);
+            ShipRng::new(seed)
+        }
+
+        pub fn new(seed: u64) -> Self {
+            // xorshift can't leave an all zero state
+            ShipRng { state: if seed == 0 { 0x9e37_79b9_7f4a_7c15 } else { seed } }
+        }
+
+        // xorshift64*
+        pub fn next_u64(&mut self) -> u64 {
+            let mut x = self.state;
+            x ^= x >> 12;
+            x ^= x << 25;
+            x ^= x >> 27;
+            self.state = x;
+            x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+        }
+
+        // uniform in [lo, hi)
+        pub fn uniform(&mut self, lo: f64, hi: f64) -> f64 {
+            let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+            lo + (hi - lo) * unit
+        }
+
+        // uniform in [-amplitude, amplitude)
+        pub fn jitter(&mut self, amplitude: f64) -> f64 {
+            self.uniform(-amplitude, amplitude)
+        }
+
+        pub fn unit_vector(&mut self) -> Vec2 {
+            vec2(1.0, 0.0).rotate(self.uniform(0.0, TAU))
+        }
+    }
+
+    pub fn splitmix64(x: u64) -> u64 {
+        let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // match long engagement counters for post-run tuning
+    mod stats {
+        use super::*;
+
+        // why the gun didn't fire on a tick it wanted to, each points at a subsystem:
+        // reload at fire rate, miss distance at turning and prediction, envelope at
+        // positioning, quality and remote at the tracker
+        #[derive(Debug, Clone, Copy)]
+        pub enum FireReject {
+            Reload,
+            MissDistance,
+            FriendlyInCorridor,
+            Envelope,
+            Quality,
+            RemoteTrack,
+        }
+
+        pub const FIRE_REJECT_COUNT: usize = 6;
+
+        // accumulated over the whole match, every method is a no-op unless MATCH_STATS
+        #[derive(Default)]
+        pub struct MatchStats {
+            // rounds fired per weapon index
+            pub shots: [u32; 4],
+            pub rejects: [u32; FIRE_REJECT_COUNT],
+
+            pub missiles_launched: u32,
+            pub missiles_hit: u32,
+            pub missiles_dud: u32,
+            pub kills: u32,
+
+            // first designation of each track, ticks from its first detection summed up
+            pub designated: Vec<u128>,
+            pub designation_delay_ticks: u64,
+
+            pub track_high_water: usize,
+
+            // coordinated launches resolved, how many arrived inside the strike window, and
+            // the arrival error summed over them in ticks
+            pub strikes: u32,
+            pub strikes_on_time: u32,
+            pub strike_error_ticks: u64,
+
+            // radio messages sent and dropped as stale, per RadioKind
+            pub radio_sent: [u32; RADIO_KIND_COUNT],
+            pub radio_dropped: [u32; RADIO_KIND_COUNT],
+
+            // enemy launches guessed from the launcher's track, and how many the radar
+            // went on to find versus never saw
+            pub launches_inferred: u32,
+            pub launches_confirmed: u32,
+            pub launches_expired: u32,
+
+            // designation moved to another track without a kill in between
+            pub churn: u32,
+            last_designation: Option<u128>,
+            kill_since_designation: bool,
+
+            final_printed: bool,
+        }
+
+        impl MatchStats {
+            pub fn shot(&mut self, weapon: usize) {
+                if MATCH_STATS {
+                    self.shots[weapon.min(self.shots.len() - 1)] += 1;
+                }
+            }
+
+            pub fn reject(&mut self, reason: FireReject) {
+                if MATCH_STATS {
+                    self.rejects[reason as usize] += 1;
+                }
+            }
+
+            pub fn missile_launched(&mut self) {
+                if MATCH_STATS {
+                    self.missiles_launched += 1;
+                }
+            }
+
+            pub fn missile_hit(&mut self) {
+                if MATCH_STATS {
+                    self.missiles_hit += 1;
+                }
+            }
+
+            pub fn missile_dud(&mut self) {
+                if MATCH_STATS {
+                    self.missiles_dud += 1;
+                }
+            }
+
+            pub fn kill(&mut self) {
+                if MATCH_STATS {
+                    self.kills += 1;
+                    self.kill_since_designation = true;
+                }
+            }
+
+            // arrival is the last heartbeat heard, within a heartbeat interval of the real one
+            pub fn strike_arrival(&mut self, planned_tick: u32, arrival_tick: u32) {
+                if MATCH_STATS {
+                    let error = planned_tick.abs_diff(arrival_tick);
+                    self.strikes += 1;
+                    self.strike_error_ticks += error as u64;
+                    if error <= STRIKE_WINDOW_TICKS {
+                        self.strikes_on_time += 1;
+                    }
+                }
+            }
+
+            pub fn radio(&mut self, traffic: &RadioTraffic) {
+                if MATCH_STATS {
+                    if let Some(kind) = traffic.sent {
+                        self.radio_sent[kind as usize] += 1;
+                    }
+                    for kind in &traffic.dropped {
+                        self.radio_dropped[*kind as usize] += 1;
+                    }
+                }
+            }
+
+            pub fn launches(&mut self, launches: &LaunchInferences) {
+                if MATCH_STATS {
+                    self.launches_inferred += launches.inferred;
+                    self.launches_confirmed += launches.confirmed;
+                    self.launches_expired += launches.expired;
+                }
+            }
+
+            pub fn tracks(&mut self, count: usize) {
+                if MATCH_STATS {
+                    self.track_high_water = self.track_high_water.max(count);
+                }
+            }
+
+            // called on every selection, only a change of track counts
+            pub fn designate(&mut self, id: u128, first_tick: u32, now: u32) {
+                if !MATCH_STATS || self.last_designation == Some(id) {
+                    return;
+                }
+                if self.last_designation.is_some() && !self.kill_since_designation {
+                    self.churn += 1;
+                }
+                if !self.designated.contains(&id) {
+                    self.designated.push(id);
+                    self.designation_delay_ticks += now.saturating_sub(first_tick) as u64;
+                }
+                self.last_designation = Some(id);
+                self.kill_since_designation = false;
+            }
+
+            pub fn summary(&self) -> String {
+                let designate = if self.designated.is_empty() {
+                    0.0
+                } else {
+                    self.designation_delay_ticks as f64 / self.designated.len() as f64 / 60.0
+                };
+                let strike_error = if self.strikes == 0 {
+                    0.0
+                } else {
+                    self.strike_error_ticks as f64 / self.strikes as f64 / 60.0
+                };
+                format!(
+                    "shots {:?} | held: reload {} miss {} friendly {} envelope {} quality {} remote {} | missiles {} launched {} hit {} lost | strikes {} on time of {}, off by {:.2}s | kills {} | designate {:.2}s over {} | tracks max {} | churn {} | radio sent {:?} dropped {:?} | launch cues {} confirmed {} expired {}",
+                    self.shots,
+                    self.rejects[FireReject::Reload as usize],
+                    self.rejects[FireReject::MissDistance as usize],
+                    self.rejects[FireReject::FriendlyInCorridor as usize],
+                    self.rejects[FireReject::Envelope as usize],
+                    self.rejects[FireReject::Quality as usize],
+                    self.rejects[FireReject::RemoteTrack as usize],
+                    self.missiles_launched, self.missiles_hit, self.missiles_dud,
+                    self.strikes_on_time, self.strikes, strike_error,
+                    self.kills,
+                    designate, self.designated.len(),
+                    self.track_high_water,
+                    self.churn,
+                    self.radio_sent, self.radio_dropped,
+                    self.launches_inferred, self.launches_confirmed, self.launches_expired,
+                )
+            }
+
+            // periodic summary, plus one more when the match looks over, oort has no end
+            // of match hook so that's best effort
+            pub fn report(&mut self, looks_over: bool) {
+                if !MATCH_STATS {
+                    return;
+                }
+                if current_tick() % STATS_SUMMARY_INTERVAL == 0 {
+                    trace!("stats: {}", self.summary());
+                }
+                if looks_over && !self.final_printed {
+                    trace!("final stats: {}", self.summary());
+                    self.final_printed = true;
+                }
+            }
+        }
+    }
+
+    // thrust planning, attack patterns, search legs and the arena boundary
+    mod maneuver {
+        use super::*;
+
+        // circling attack pattern, keeps transverse velocity on the target while the
+        // nose tracks the lead point so we are harder to hit than a head-on run
+        pub struct OrbitPattern {
+            // start orbiting once inside this range
+            pub engage_range: f64,
+
+            // distance we try to hold from the target while orbiting
+            pub standoff_radius: f64,
+
+            // reverse orbit if the target's pursuit pulls it inside this radius
+            min_radius: f64,
+
+            // closing behavior range bands used outside the orbit, [0, close], [close, far], [far, +]
+            pub close_range: f64,
+            pub far_range: f64,
+
+            // m/s/s drifting along the line of sight in the close band, and closing in the far one
+            pub drift_acceleration: f64,
+            pub closing_acceleration: f64,
+
+            // tangential speed relative to the target in m/s
+            tangential_speed: f64,
+
+            // m/s of radial correction per meter of standoff error
+            radial_gain: f64,
+
+            // acceleration per m/s of velocity error
+            velocity_gain: f64,
+
+            // opening speed above which the target is considered fleeing
+            pub flee_speed: f64,
+
+            // periodic orbit reversal, and minimum ticks between reversals
+            reverse_interval: u32,
+            reverse_cooldown: u32,
+
+            // 1.0 counter clockwise, -1.0 clockwise
+            direction: f64,
+            ticks_since_reverse: u32,
+            active: bool,
+
+            // bearing out from the target to keep our aspect moving away from, set
+            // while our own missiles come in from that side
+            avoid_bearing: Option<f64>,
+        }
+
+        impl OrbitPattern {
+            pub fn new(close_range: f64, far_range: f64) -> Self {
+                OrbitPattern {
+                    engage_range: 900.0,
+                    standoff_radius: 600.0,
+                    min_radius: 300.0,
+                    close_range,
+                    far_range,
+                    drift_acceleration: 10.0,
+                    closing_acceleration: 100.0,
+                    tangential_speed: 150.0,
+                    radial_gain: 0.5,
+                    velocity_gain: 2.0,
+                    flee_speed: 50.0,
+                    reverse_interval: 300,
+                    reverse_cooldown: 60,
+                    direction: 1.0,
+                    ticks_since_reverse: 0,
+                    active: false,
+                    avoid_bearing: None,
+                }
+            }
+
+            // a new side to avoid re-picks the direction on the next tick
+            pub fn set_avoid_bearing(&mut self, bearing: Option<f64>) {
+                if bearing.is_some() && self.avoid_bearing.is_none() {
+                    self.active = false;
+                }
+                self.avoid_bearing = bearing;
+            }
+
+            // orbit inside the weapon's standoff band, engage at its far edge
+            pub fn fit_to_envelope(&mut self, envelope: &EngagementEnvelope) {
+                self.min_radius = envelope.standoff_min;
+                self.standoff_radius = envelope.standoff();
+                self.engage_range = envelope.standoff_max;
+            }
+
+            // acceleration taking out relative_velocity (target velocity - own velocity)
+            // in the middle band, where we sit on the target's motion
+            pub fn match_velocity(&self, relative_velocity: Vec2) -> Vec2 {
+                relative_velocity * self.velocity_gain
+            }
+
+            // closing behavior outside the orbit, unit_dir points at the target: float in
+            // the close band drifting the way the range is going, sit on the target's
+            // motion in the middle band and close hard past the far edge
+            pub fn band_acceleration(&self, distance: f64, unit_dir: Vec2, relative_velocity: Vec2, opening: bool) -> Vec2 {
+                if distance < self.close_range {
+                    if opening { self.drift_acceleration * unit_dir } else { -self.drift_acceleration * unit_dir }
+                } else if distance < self.far_range {
+                    self.match_velocity(relative_velocity)
+                } else {
+                    self.closing_acceleration * unit_dir
+                }
+            }
+
+            pub fn reverse(&mut self) {
+                trace!("orbit reversing direction");
+                self.direction = -self.direction;
+                self.ticks_since_reverse = 0;
+            }
+
+            // leaving the orbit envelope, next entry picks a fresh direction
+            pub fn disengage(&mut self) {
+                self.active = false;
+            }
+
+            // to_target is target position - own position, relative_velocity is target velocity - own velocity
+            // returns desired world frame acceleration to hold the orbit
+            pub fn orbit_acceleration(&mut self, to_target: Vec2, relative_velocity: Vec2) -> Vec2 {
+                let distance = to_target.length();
+                let radial = to_target.normalize();
+                let own_relative_velocity = -relative_velocity;
+
+                if !self.active {
+                    self.direction = match self.avoid_bearing {
+                        // counter clockwise tangent swings our aspect clockwise, away from a bearing counter clockwise of it
+                        Some(avoid) => if angle_diff((-radial).angle(), avoid) >= 0.0 { 1.0 } else { -1.0 },
+                        None => {
+                            // keep whatever transverse motion we already have instead of reversing it
+                            let cross = radial.x * own_relative_velocity.y - radial.y * own_relative_velocity.x;
+                            if cross >= 0.0 { 1.0 } else { -1.0 }
+                        },
+                    };
+                    self.ticks_since_reverse = 0;
+                    self.active = true;
+                }
+
+                self.ticks_since_reverse += 1;
+                if self.ticks_since_reverse >= self.reverse_interval && self.avoid_bearing.is_none() {
+                    self.reverse();
+                } else if distance < self.min_radius && self.ticks_since_reverse > self.reverse_cooldown {
+                    // target is pressing in on us, cut back across its nose
+                    self.reverse();
+                }
+
+                let tangent = vec2(-radial.y, radial.x) * self.direction;
+
+                // positive error means we're too far out and need to move in
+                let radial_error = distance - self.standoff_radius;
+                let desired_velocity = tangent * self.tangential_speed + radial * (radial_error * self.radial_gain);
+
+                trace!("orbit distance: {}, direction: {}, radial error: {}", distance, self.direction, radial_error);
+                (desired_velocity - own_relative_velocity) * self.velocity_gain
+            }
+        }
+
+        // plans the closing burn so we arrive at the standoff radius without overshooting
+        pub struct ApproachPlanner {
+            // multiplier on the computed stopping distance to start braking a little early
+            braking_margin: f64,
+
+            // closing speed we're happy to arrive with, m/s
+            arrival_speed: f64,
+
+            // currently braking along the closing axis
+            braking: bool,
+
+            // braking with the main engine, hull is flipped away from the target
+            pub flip_and_burn: bool,
+        }
+
+        impl ApproachPlanner {
+            pub fn new() -> Self {
+                ApproachPlanner {
+                    braking_margin: 1.2,
+                    arrival_speed: 20.0,
+                    braking: false,
+                    flip_and_burn: false,
+                }
+            }
+
+            // range_to_standoff is distance left until the desired standoff radius
+            // closing_speed is positive when we're approaching the target
+            // returns the brake acceleration along the closing axis, or None to keep closing
+            pub fn plan(&mut self, range_to_standoff: f64, closing_speed: f64, to_target_unit: Vec2) -> Option<Vec2> {
+                self.braking = false;
+                self.flip_and_burn = false;
+
+                if closing_speed <= self.arrival_speed {
+                    return None;
+                }
+
+                let excess_speed = closing_speed - self.arrival_speed;
+                let (stop_distance, flip) = stopping_distance(excess_speed);
+                trace!("approach: range to standoff: {}, stop distance: {}, flip: {}", range_to_standoff, stop_distance, flip);
+
+                if range_to_standoff > stop_distance * self.braking_margin {
+                    return None;
+                }
+
+                self.braking = true;
+                self.flip_and_burn = flip;
+                let decel = if flip { max_forward_acceleration() } else { max_backward_acceleration() };
+
+                // just enough to be down to arrival speed at the standoff, what the flip
+                // itself would travel is kept in reserve so the burn isn't planned over it
+                let burn_distance = excess_speed * excess_speed / (2.0 * decel);
+                let room = (range_to_standoff - (stop_distance - burn_distance)).max(1.0);
+                let decel = (excess_speed * excess_speed / (2.0 * room)).min(decel);
+
+                // only cancel the closing component, lateral velocity is left alone
+                Some(-to_target_unit * decel)
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum EnergyDecision {
+            // inside the combat band, pursuit gets everything
+            InBand,
+            // slow enough, the pursuit command speeds us up on its own
+            BelowBand,
+            // over the band, braking along the relative velocity first
+            Shedding,
+            // over the band, but braking would let the target get away
+            HoldingSpeed,
+        }
+
+        // energy layer between pursuit and the thrusters: fast relative to the target
+        // the nose can't be brought around, so excess speed is shed along the relative
+        // velocity and pursuit only gets what's left of max_accel
+        // rel_p/rel_v are target - own, escape_range is where the envelope ends
+        pub fn manage_energy(rel_p: Vec2, rel_v: Vec2, pursuit: Vec2, band: (f64, f64), max_accel: f64, escape_range: f64) -> (Vec2, EnergyDecision) {
+            let (band_min, band_max) = band;
+            let speed = rel_v.length();
+            if band_max <= 0.0 || speed <= band_max {
+                let decision = if speed < band_min { EnergyDecision::BelowBand } else { EnergyDecision::InBand };
+                return (pursuit, decision);
+            }
+
+            // where braking to the top of the band leaves the geometry
+            let braked = rel_v * (band_max / speed);
+            let range = rel_p.length();
+            let opening = -closing_speed(rel_p, braked);
+            let escapes = if opening > 0.0 {
+                range + opening * opening / (2.0 * max_accel.max(E)) > escape_range
+            } else {
+                !intercept_feasible(rel_p, braked, max_accel, f64::INFINITY)
+            };
+            if escapes {
+                return (pursuit, EnergyDecision::HoldingSpeed);
+            }
+
+            // slowing down relative to the target is accelerating along rel_v
+            let brake_magnitude = ((speed - band_max) * ENERGY_BRAKE_GAIN).min(max_accel);
+            let brake = rel_v / speed * brake_magnitude;
+            let remaining = max_accel - brake_magnitude;
+            let pursuit = if pursuit.length() > remaining { pursuit.normalize() * remaining } else { pursuit };
+            (brake + pursuit, EnergyDecision::Shedding)
+        }
+
+        // lawnmower sweep across the arena interior for when nothing is on radar
+        pub struct SearchPattern {
+            // current leg, 0 is the run in to the map center, after that even legs run
+            // toward +x and odd legs back toward -x
+            pub leg: u32,
+
+            // how close to a waypoint counts as arrived
+            arrival_radius: f64,
+
+            // cruise speed while searching, m/s
+            pub cruise_speed: f64,
+        }
+
+        impl SearchPattern {
+            pub fn new() -> Self {
+                SearchPattern {
+                    leg: 0,
+                    arrival_radius: 500.0,
+                    cruise_speed: 300.0,
+                }
+            }
+
+            // end point of the current leg, kept inside the boundary margin
+            pub fn waypoint(&self) -> Vec2 {
+                search_waypoint(self.leg, world_size() / 2.0 - BOUNDARY_MARGIN)
+            }
+
+            // returns desired world frame acceleration toward the current waypoint
+            // the leg only advances on arrival, so breaking off for a contact and coming
+            // back later picks the pattern up where it was left
+            pub fn search_acceleration(&mut self) -> Vec2 {
+                let mut to_waypoint = self.waypoint() - position();
+                if to_waypoint.length() < self.arrival_radius {
+                    self.leg += 1;
+                    trace!("search pattern advancing to leg {}", self.leg);
+                    to_waypoint = self.waypoint() - position();
+                }
+                debug_line(LAYER_NAV, position(), self.waypoint(), 0x808080);
+
+                let desired_velocity = to_waypoint.normalize() * self.cruise_speed;
+                desired_velocity - velocity()
+            }
+        }
+
+        // what we're steering clear of, a friendly track or one of our own missiles by salvo
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Obstacle {
+            Friend(u128),
+            Missile(u32),
+        }
+
+        #[derive(Debug, Clone, Copy)]
+        pub struct Avoidance {
+            pub acceleration: Vec2,
+
+            // the closest call driving the command, with its miss distance and time to it
+            pub obstacle: Obstacle,
+            pub miss: f64,
+            pub time: f64,
+        }
+
+        // repulsion from everything on a collision course, summed over obstacles as
+        // (obstacle, position, velocity). each push is across the relative velocity,
+        // away from the side it passes on, and grows from 0 at AVOID_RADIUS to
+        // max_accel at contact. None when nothing closes inside AVOID_HORIZON
+        pub fn collision_avoidance(own_p: Vec2, own_v: Vec2, obstacles: &[(Obstacle, Vec2, Vec2)], max_accel: f64) -> Option<Avoidance> {
+            let mut total = Vec2::new(0.0, 0.0);
+            let mut worst: Option<(f64, Obstacle, f64, f64)> = None;
+            for &(obstacle, p, v) in obstacles {
+                let rel_p = p - own_p;
+                let rel_v = v - own_v;
+                let time = time_to_closest_approach(rel_p, rel_v);
+                let miss = distance_at_closest_approach(rel_p, rel_v);
+                if time > AVOID_HORIZON || miss > AVOID_RADIUS {
+                    continue;
+                }
+
+                // pushed off the closest approach point, straight away if we're
+                // drifting alongside it and there's no relative velocity to cross
+                let offset = rel_p + rel_v * time;
+                let away = if rel_v.length() < E {
+                    -rel_p
+                } else {
+                    let across = rel_v.normalize().rotate(PI / 2.0);
+                    if offset.dot(across) > 0.0 { -across } else { across }
+                };
+                if away.length() < E {
+                    continue;
+                }
+                let strength = max_accel * (1.0 - miss / AVOID_RADIUS);
+                total += away.normalize() * strength;
+                if worst.is_none_or(|(s, ..)| strength > s) {
+                    worst = Some((strength, obstacle, miss, time));
+                }
+            }
+            worst.map(|(_, obstacle, miss, time)| Avoidance { acceleration: total, obstacle, miss, time })
+        }
+
+        // decouples where the hull points from where the ship accelerates
+        pub struct ThrustPlanner {
+            // last achievable world frame acceleration sent to accelerate()
+            pub last_command: Vec2,
+
+            // fraction of what the main engine could deliver nose on that the current
+            // attitude has to manage, short of it the hull is rotated
+            rotation_threshold: f64,
+
+            // steer requests away from the arena edge, off for ordnance chasing a target
+            respect_boundary: bool,
+
+            // world frame offset added to every request, used to jink under fire
+            jink: Vec2,
+
+            // world frame offset steering off friendly hulls and our own missiles
+            avoidance: Vec2,
+        }
+
+        impl ThrustPlanner {
+            pub fn new(respect_boundary: bool) -> Self {
+                ThrustPlanner {
+                    last_command: Vec2::new(0.0, 0.0),
+                    rotation_threshold: THRUST_ROTATION_THRESHOLD,
+                    respect_boundary,
+                    jink: Vec2::new(0.0, 0.0),
+                    avoidance: Vec2::new(0.0, 0.0),
+                }
+            }
+
+            // nothing is commanded until something asks, accelerate() doesn't carry over
+            pub fn begin_tick(&mut self) {
+                self.last_command = Vec2::new(0.0, 0.0);
+            }
+
+            pub fn set_jink(&mut self, jink: Vec2) {
+                self.jink = jink;
+            }
+
+            pub fn set_avoidance(&mut self, avoidance: Vec2) {
+                self.avoidance = avoidance;
+            }
+
+            // takes a desired world frame acceleration, decomposes it into the body frame
+            // and scales it down until forward/backward/lateral limits are all respected
+            // if allowed, rotates the hull so the main engine can deliver the rest
+            pub fn apply_desired_acceleration(&mut self, desired: Vec2, allow_rotation: bool, controls: &mut ControlIntents) -> Vec2 {
+                // avoidance goes in ahead of the boundary margin so it can't push us out
+                let desired = desired + self.jink + self.avoidance;
+                let desired = if self.respect_boundary { boundary_adjusted_acceleration(desired) } else { desired };
+                if desired.length() < E {
+                    controls.accelerate(Vec2::new(0.0, 0.0));
+                    self.last_command = Vec2::new(0.0, 0.0);
+                    return self.last_command;
+                }
+
+                // body frame: x is along the nose, y is out the left side
+                let body = desired.rotate(-heading());
+                let body = clamp_to_thrust_limits(body);
+                let command = body.rotate(heading());
+
+                // this attitude falls well short of the request, bring main engine to bear
+                if allow_rotation && rotation_pays_off(desired, command, max_forward_acceleration(), self.rotation_threshold) {
+                    controls.turn_to(desired.angle());
+                }
+
+                trace!("thrust planner desired: {}, commanded: {}", desired, command);
+                hud!(HudLine::Nav, "desired {:.1} m/s/s at {:.2}, commanded {:.1}", desired.length(), desired.angle(), command.length());
+                controls.accelerate(command);
+                self.last_command = command;
+                command
+            }
+        }
+
+        // scales a body frame acceleration so every axis is within its thruster limit
+        // uniform scaling keeps the requested direction intact
+        pub fn clamp_to_thrust_limits(body: Vec2) -> Vec2 {
+            let forward_limit = if body.x >= 0.0 { max_forward_acceleration() } else { max_backward_acceleration() };
+            let lateral_limit = max_lateral_acceleration();
+
+            let mut scale: f64 = 1.0;
+            if body.x.abs() > forward_limit {
+                scale = scale.min(forward_limit / body.x.abs());
+            }
+            if body.y.abs() > lateral_limit {
+                scale = scale.min(lateral_limit / body.y.abs());
+            }
+            body * scale
+        }
+
+        // turning nose on would get meaningfully more of desired than command, what
+        // the thrusters manage in the current attitude
+        pub fn rotation_pays_off(desired: Vec2, command: Vec2, max_forward: f64, threshold: f64) -> bool {
+            command.length() < desired.length().min(max_forward) * threshold
+        }
+
+        // leg 0 heads for the map center, where the radar sees the most of the arena,
+        // then a lawnmower whose rows are SEARCH_LEG_SPACING apart inside +-half
+        pub fn search_waypoint(leg: u32, half: f64) -> Vec2 {
+            if leg == 0 {
+                return Vec2::new(0.0, 0.0);
+            }
+            let leg = leg - 1;
+            let rows = ((2.0 * half) / SEARCH_LEG_SPACING).floor().max(1.0) as u32;
+            let row = (leg / 2) % (rows + 1);
+            let y = -half + row as f64 * SEARCH_LEG_SPACING;
+            let x = if leg % 2 == 0 { half } else { -half };
+            Vec2::new(x, y.min(half))
+        }
+
+        // distance needed to shed speed along the closing axis
+        // compares reverse thrusters against flipping the hull and burning the main engine
+        // returns (distance, true if flip and burn is shorter)
+        pub fn stopping_distance(speed: f64) -> (f64, bool) {
+            let reverse_distance = (speed * speed) / (2.0 * max_backward_acceleration());
+
+            // bang-bang half turn from rest: accelerate for half the angle, decelerate for the other half
+            let flip_time = 2.0 * (PI / max_angular_acceleration()).sqrt();
+            let flip_distance = speed * flip_time + (speed * speed) / (2.0 * max_forward_acceleration());
+
+            if flip_distance < reverse_distance {
+                (flip_distance, true)
+            } else {
+                (reverse_distance, false)
+            }
+        }
+
+        // keeps desired acceleration from pinning us against the arena edge
+        // inside the margin band the outward component is reflected and an inward push
+        // grows with depth, the band is drawn whenever it changes the command
+        pub fn boundary_adjusted_acceleration(desired: Vec2) -> Vec2 {
+            let half = world_size() / 2.0;
+            let inner = half - BOUNDARY_MARGIN;
+            let p = position();
+            let mut adjusted = desired;
+            let mut influenced = false;
+
+            // depth into the margin band along each axis, 0 at the inner edge, 1 at the wall
+            let depth_x = ((p.x.abs() - inner) / BOUNDARY_MARGIN).clamp(0.0, 1.0);
+            let depth_y = ((p.y.abs() - inner) / BOUNDARY_MARGIN).clamp(0.0, 1.0);
+
+            if depth_x > 0.0 {
+                let outward = p.x.signum();
+                if adjusted.x * outward > 0.0 {
+                    adjusted.x = -adjusted.x;
+                }
+                adjusted.x -= outward * depth_x * max_lateral_acceleration();
+                influenced = true;
+            }
+            if depth_y > 0.0 {
+                let outward = p.y.signum();
+                if adjusted.y * outward > 0.0 {
+                    adjusted.y = -adjusted.y;
+                }
+                adjusted.y -= outward * depth_y * max_lateral_acceleration();
+                influenced = true;
+            }
+
+            if influenced {
+                trace!("boundary margin adjusting acceleration: {} -> {}", desired, adjusted);
+                draw_boundary_margin();
+            }
+            adjusted
+        }
+
+        // escape direction for fleeing, slides along the wall instead of into it
+        pub fn boundary_escape_vector(away: Vec2) -> Vec2 {
+            let half = world_size() / 2.0;
+            let inner = half - BOUNDARY_MARGIN;
+            let p = position();
+            let mut escape = away;
+
+            if p.x.abs() > inner && escape.x * p.x.signum() > 0.0 {
+                escape.x = 0.0;
+            }
+            if p.y.abs() > inner && escape.y * p.y.signum() > 0.0 {
+                escape.y = 0.0;
+            }
+
+            if escape.length() < E {
+                // cornered, head back toward the middle of the arena
+                escape = -p;
+                draw_boundary_margin();
+            }
+            escape.normalize()
+        }
+
+        pub fn draw_boundary_margin() {
+            let inner = world_size() / 2.0 - BOUNDARY_MARGIN;
+            let c1 = Vec2::new(inner, inner);
+            let c2 = Vec2::new(-inner, inner);
+            let c3 = Vec2::new(-inner, -inner);
+            let c4 = Vec2::new(inner, -inner);
+            debug_line(LAYER_NAV, c1, c2, 0xffa500);
+            debug_line(LAYER_NAV, c2, c3, 0xffa500);
+            debug_line(LAYER_NAV, c3, c4, 0xffa500);
+            debug_line(LAYER_NAV, c4, c1, 0xffa500);
+        }
+    }
+
+    // wingman slot keeping and capital escort, both driven by radio beacons
+    mod formation {
+        use super::*;
+
+        #[derive(Debug, PartialEq)]
+        pub enum FormationRole {
+            Undecided,
+            Leader,
+            Wingman,
+        }
+
+        // last leader beacon heard by a wingman
+        pub struct LeaderState {
+            tick: u32,
+            position: Vec2,
+            velocity: Vec2,
+            heading: f64,
+            engaged: bool,
+        }
+
+        // leader/wingman slot keeping for multi-ship scenarios
+        // the ship that spawned with the lowest x (then y) leads, decided over the radio
+        // in the first FORMATION_ELECTION_TICKS ticks
+        pub struct Formation {
+            role: FormationRole,
+            spawn_position: Vec2,
+
+            // slot in the leader's body frame, x along the leader's nose
+            slot_offset: Vec2,
+
+            // formation breaks when an engagement gets inside this range
+            pub break_range: f64,
+
+            // beyond this distance from the slot use the braking approach instead of slot keeping
+            pub rejoin_range: f64,
+
+            // slot keeping gains, acceleration per meter and per m/s of error
+            pub position_gain: f64,
+            pub velocity_gain: f64,
+
+            leader: Option<LeaderState>,
+        }
+
+        impl Formation {
+            pub fn new() -> Self {
+                Formation {
+                    role: FormationRole::Undecided,
+                    spawn_position: position(),
+                    slot_offset: Vec2::new(-300.0, -300.0),
+                    break_range: 1_000.0,
+                    rejoin_range: 1_000.0,
+                    position_gain: 0.5,
+                    velocity_gain: 1.5,
+                    leader: None,
+                }
+            }
+
+            // lexicographic on spawn position so every ship reaches the same answer
+            fn outranks(a: Vec2, b: Vec2) -> bool {
+                a.x < b.x || (a.x == b.x && a.y < b.y)
+            }
+
+            pub fn hear_leader(&mut self, position: Vec2, velocity: Vec2, heading: f64, engaged: bool) {
+                if self.role == FormationRole::Undecided && Formation::outranks(position, self.spawn_position) {
+                    trace!("formation: heard a higher ranked ship, flying wing");
+                    self.role = FormationRole::Wingman;
+                }
+                if self.role == FormationRole::Wingman {
+                    self.leader = Some(LeaderState { tick: current_tick(), position, velocity, heading, engaged });
+                }
+            }
+
+            // candidates beacon during the election, the survivors keep leading
+            pub fn update_role(&mut self) {
+                if self.role == FormationRole::Undecided && current_tick() >= FORMATION_ELECTION_TICKS {
+                    trace!("formation: nobody outranks us, leading");
+                    self.role = FormationRole::Leader;
+                }
+            }
+
+            pub fn should_beacon(&self) -> bool {
+                self.role != FormationRole::Wingman
+            }
+
+            // the leader's slot for us, None when there's no formation to keep
+            pub fn slot(&self, own_engaged: bool) -> Option<(Vec2, Vec2)> {
+                if self.role != FormationRole::Wingman || own_engaged {
+                    return None;
+                }
+                let leader = self.leader.as_ref()?;
+                let age = current_tick() - leader.tick;
+                if age > FORMATION_BEACON_TIMEOUT || leader.engaged {
+                    return None;
+                }
+                let leader_position = leader.position + leader.velocity * (age as f64 / 60.0);
+                Some((leader_position + self.slot_offset.rotate(leader.heading), leader.velocity))
+            }
+        }
+
+        // escorting a capital ship that identifies itself with a CapitalBeacon
+        pub struct EscortConfig {
+            // distance from the capital we patrol at
+            pub patrol_radius: f64,
+
+            // center and half width of the patrolled arc, relative to the capital's heading
+            preferred_sector: f64,
+            sector_half_width: f64,
+
+            // patrol point sweep rate across the sector, rad/s
+            sweep_rate: f64,
+
+            // enemy fighters are only chased while they're this close to the capital
+            pub leash_distance: f64,
+
+            // last capital beacon heard: tick, position, velocity
+            capital: Option<(u32, Vec2, Vec2)>,
+        }
+
+        impl EscortConfig {
+            pub fn new() -> Self {
+                EscortConfig {
+                    patrol_radius: 1_500.0,
+                    preferred_sector: 0.0,
+                    sector_half_width: PI / 3.0,
+                    sweep_rate: 0.1,
+                    leash_distance: 4_000.0,
+                    capital: None,
+                }
+            }
+
+            pub fn hear_capital(&mut self, position: Vec2, velocity: Vec2) {
+                self.capital = Some((current_tick(), position, velocity));
+            }
+
+            // dead reckoned capital position and velocity, None once the beacon goes quiet
+            pub fn capital_state(&self) -> Option<(Vec2, Vec2)> {
+                let (tick, position, velocity) = self.capital?;
+                let age = current_tick() - tick;
+                if age > CAPITAL_BEACON_TIMEOUT {
+                    return None;
+                }
+                Some((position + velocity * (age as f64 / 60.0), velocity))
+            }
+
+            // point on the patrol circle, sweeping back and forth across the preferred sector
+            pub fn patrol_point(&self, capital_position: Vec2, capital_velocity: Vec2) -> Vec2 {
+                let capital_heading = if capital_velocity.length() > 1.0 { capital_velocity.angle() } else { 0.0 };
+                let sweep = (current_tick() as f64 / 60.0 * self.sweep_rate).sin() * self.sector_half_width;
+                capital_position + vec2(self.patrol_radius, 0.0).rotate(capital_heading + self.preferred_sector + sweep)
+            }
+        }
+    }
+
+    // fighter: target selection, gun and missile employment, combat state
+    mod fighter {
+        use super::*;
+
+        // ticks our gun rounds fired at one track should arrive over
+        pub struct ImpactWindow {
+            track_id: u128,
+            first_tick: u32,
+            last_tick: u32,
+        }
+
+        // used to drive general ship behavior
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum ShipState {
+            NoTarget,
+            Searching,
+            Engaged,
+            OutOfTargetRange,
+            OutOfRadarRange,
+            Escorting,
+            PointDefense,
+        }
+
+        // how the first seconds are flown, see choose_opening
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Opening {
+            // alone near the middle, tutorial style: the targets are close, go and find them
+            AggressiveRush,
+            // teammates on the radio: form up and sweep the arena together
+            StandardSearch,
+            // alone out by the edge, a duel: look far, come in slow and keep energy in hand
+            CautiousApproach,
+            // a capital ship is beaconing: stay with it
+            DefensiveEscort,
+        }
+
+        // reads the scenario off what's known once the spawn listening window closes,
+        // FORCED_OPENING wins over all of it
+        pub fn choose_opening(class: Class, spawn: Vec2, half_world: f64, heard_friend: bool, heard_capital: bool) -> Opening {
+            if let Some(opening) = FORCED_OPENING {
+                return opening;
+            }
+            if class != Class::Fighter {
+                return Opening::StandardSearch;
+            }
+            if heard_capital {
+                Opening::DefensiveEscort
+            } else if heard_friend {
+                Opening::StandardSearch
+            } else if spawn.x.abs().max(spawn.y.abs()) > half_world * OPENING_EDGE_SHARE {
+                Opening::CautiousApproach
+            } else {
+                Opening::AggressiveRush
+            }
+        }
+
+        // what an opening changes from the class defaults
+        #[derive(Debug, Clone, Copy)]
+        pub struct OpeningProfile {
+            pub opening: Opening,
+            pub state: ShipState,
+
+            pub radar_state: RadarState,
+            pub beam: RadarBeam,
+
+            // half width of the sweep sector toward the arena center until first
+            // contact, None sweeps all round
+            pub sweep_toward_center: Option<f64>,
+
+            // multiplies the search pattern's cruise speed
+            pub search_speed_scale: f64,
+
+            // replaces the config's combat speed band, m/s
+            pub combat_speed: (f64, f64),
+        }
+
+        impl OpeningProfile {
+            pub fn for_opening(opening: Opening, config: &ShipConfig) -> Self {
+                let standard = OpeningProfile {
+                    opening,
+                    state: ShipState::Searching,
+                    radar_state: config.radar.initial_state,
+                    beam: config.radar.initial_beam,
+                    sweep_toward_center: None,
+                    search_speed_scale: 1.0,
+                    combat_speed: (config.combat_speed_min, config.combat_speed_max),
+                };
+                match opening {
+                    Opening::AggressiveRush => OpeningProfile {
+                        radar_state: RadarState::MediumRange,
+                        beam: RadarBeam::Wide,
+                        sweep_toward_center: Some(PI / 2.0),
+                        search_speed_scale: 1.5,
+                        combat_speed: (config.combat_speed_min, config.combat_speed_max * 1.5),
+                        ..standard
+                    },
+                    Opening::CautiousApproach => OpeningProfile {
+                        radar_state: RadarState::LongRange,
+                        beam: RadarBeam::Narrow,
+                        sweep_toward_center: Some(PI / 4.0),
+                        search_speed_scale: 0.6,
+                        combat_speed: (config.combat_speed_min * 2.0, config.combat_speed_max * 0.7),
+                        ..standard
+                    },
+                    Opening::DefensiveEscort => OpeningProfile { state: ShipState::Escorting, ..standard },
+                    Opening::StandardSearch => standard,
+                }
+            }
+        }
+
+        // used to drive engaged state behavior
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum CombatState {
+            Attack,
+            Evade,
+            Flee,
+        }
+
+        // what point defense preempted, put back once the missile is dealt with
+        #[derive(Debug, Clone, Copy)]
+        pub struct PointDefense {
+            pub missile: u128,
+            pub resume_target: Option<u128>,
+            pub resume_state: ShipState,
+        }
+
+        // breaking across a terminal missile's line of sight, reversed on a random
+        // cadence so its proportional navigation keeps chasing a rate it can't null
+        #[derive(Debug, Clone, Copy)]
+        pub struct MissileEvasion {
+            pub missile: u128,
+            sign: f64,
+            next_flip_tick: u32,
+        }
+
+        impl MissileEvasion {
+            pub fn new(missile: u128, rng: &mut ShipRng, now: u32) -> Self {
+                let sign = if rng.uniform(0.0, 1.0) < 0.5 { -1.0 } else { 1.0 };
+                MissileEvasion { missile, sign, next_flip_tick: now + Self::flip_wait(rng) }
+            }
+
+            fn flip_wait(rng: &mut ShipRng) -> u32 {
+                rng.uniform(EVADE_FLIP_TICKS.0 as f64, EVADE_FLIP_TICKS.1 as f64 + 1.0) as u32
+            }
+
+            // full acceleration square to the line of sight to the missile
+            pub fn acceleration(&mut self, line_of_sight: Vec2, magnitude: f64, rng: &mut ShipRng, now: u32) -> Vec2 {
+                if now >= self.next_flip_tick {
+                    self.sign = -self.sign;
+                    self.next_flip_tick = now + Self::flip_wait(rng);
+                }
+                line_of_sight.normalize().rotate(PI / 2.0) * self.sign * magnitude
+            }
+        }
+
+        // a pursuer we can't shed: even burning away flat out, its closure carries it
+        // inside gun range
+        pub fn outpaced(range: f64, closing: f64, acceleration: f64) -> bool {
+            closing > 0.0 && closing * closing > 2.0 * acceleration * (range - FLEE_GUN_RANGE).max(0.0)
+        }
+
+        // hit points a foe ship spawns with, we can't see what it has taken since
+        pub fn assumed_health(class: Class) -> f64 {
+            match class {
+                Class::Frigate => 10_000.0,
+                Class::Cruiser => 20_000.0,
+                _ => 100.0,
+            }
+        }
+
+        // our hit points against everything chasing us, each assumed undamaged
+        pub fn relative_health(own_health: f64, foes: &[Class]) -> f64 {
+            let theirs: f64 = foes.iter().map(|c| assumed_health(*c)).sum();
+            if theirs <= 0.0 {
+                return f64::INFINITY;
+            }
+            own_health / theirs
+        }
+
+        // run once hurt and outnumbered or outrun, come back only after a while and
+        // once contact is broken, the odds in hit points are back in our favour, or
+        // nobody is left at all. the different ways in and out keep it from flapping
+        pub fn should_flee(ticks_fleeing: Option<u32>, health_fraction: f64, flee_health_fraction: f64, relative_health: f64, foes: usize, nearest_range: f64, outpaced: bool) -> bool {
+            match ticks_fleeing {
+                None => health_fraction < flee_health_fraction && (foes > 1 || outpaced),
+                Some(ticks) if ticks < FLEE_MIN_TICKS => true,
+                Some(_) => foes > 0 && nearest_range < FLEE_REENGAGE_RANGE && relative_health < FLEE_REENGAGE_RELATIVE_HEALTH,
+            }
+        }
+
+        // where the pursuit is coming from, nearer hostiles count for more
+        pub fn hostile_centroid(point: Vec2, hostiles: &[Vec2]) -> Option<Vec2> {
+            let weights: Vec<f64> = hostiles.iter().map(|h| 1.0 / (*h - point).length().max(1.0)).collect();
+            let total: f64 = weights.iter().sum();
+            if hostiles.is_empty() || total <= 0.0 {
+                return None;
+            }
+            Some(hostiles.iter().zip(&weights).fold(Vec2::new(0.0, 0.0), |sum, (h, w)| sum + *h * *w) / total)
+        }
+
+        // an engagement drops to closing at full burn once the target is well past the
+        // longest weapon we have on it, and comes back a bit inside, so a target
+        // hovering on the edge doesn't flap the state
+        pub fn next_engaged_state(state: ShipState, range: f64, weapon_range: f64) -> ShipState {
+            match state {
+                ShipState::Engaged if range > weapon_range * OUT_OF_RANGE_MARGIN => ShipState::OutOfTargetRange,
+                ShipState::OutOfTargetRange if range < weapon_range * BACK_IN_RANGE_FRACTION => ShipState::Engaged,
+                state => state,
+            }
+        }
+
+        // a long dry spell moves the search out to the long range sweep, any contact
+        // at all brings it back
+        pub fn next_search_state(state: ShipState, ticks_since_contact: u32) -> ShipState {
+            match state {
+                ShipState::Searching if ticks_since_contact >= RADAR_DRY_SPELL_TICKS => ShipState::OutOfRadarRange,
+                ShipState::OutOfRadarRange if ticks_since_contact == 0 => ShipState::Searching,
+                state => state,
+            }
+        }
+
+        // seconds until a contact closing at closing reaches us, infinite if it isn't
+        pub fn time_to_impact(range: f64, closing: f64) -> f64 {
+            if closing <= 0.0 {
+                f64::INFINITY
+            } else {
+                range / closing
+            }
+        }
+
+        // a hostile missile inside gun reach closing fast enough that it's coming for
+        // us, rather than passing by or chasing someone else
+        pub fn incoming_missile(track: &TrackSnapshot, point: Vec2, velocity: Vec2) -> bool {
+            matches!(track.class, TrackType::Missile)
+                && !track.probably_dead
+                && track.distance_from(point) < POINT_DEFENSE_RANGE
+                && track.closing_speed_from(point, velocity) > POINT_DEFENSE_CLOSING_SPEED
+        }
+
+        // launcher side record of one of our missiles in flight
+        pub struct MissileStatus {
+            salvo: u32,
+            launch_tick: u32,
+            launch_heading: f64,
+
+            // our own state at launch, the missile starts out right on top of us
+            launch_position: Vec2,
+            launch_velocity: Vec2,
+
+            // track assigned at launch, refreshed from the picture while it lasts so it
+            // keeps its last state after it drops
+            target: TrackSnapshot,
+
+            // last heartbeat heard as (tick, missile position)
+            heartbeat: Option<(u32, Vec2)>,
+
+            // arrival tick agreed with a wingman, None for independent launches
+            planned_arrival: Option<u32>,
+
+            // bearing out from the target we launched along, kept so the missile's
+            // dogleg and our orbit's side don't wander with the geometry
+            approach: f64,
+        }
+
+        impl MissileStatus {
+            // leaving the rails now from our own state
+            pub fn launched(salvo: u32, target: TrackSnapshot, planned_arrival: Option<u32>) -> Self {
+                MissileStatus {
+                    salvo,
+                    launch_tick: current_tick(),
+                    launch_heading: heading(),
+                    launch_position: position(),
+                    launch_velocity: velocity(),
+                    target,
+                    heartbeat: None,
+                    planned_arrival,
+                    approach: (position() - target.position).angle(),
+                }
+            }
+
+            pub fn hear_heartbeat(&mut self, tick: u32, position: Vec2) {
+                self.heartbeat = Some((tick, position));
+            }
+
+            // where it is as far as we know, the boost estimate until it reports in and
+            // its last heartbeat while that's recent, None once it's gone quiet
+            pub fn estimate(&self, now: u32) -> Option<(Vec2, Vec2)> {
+                if let Some(boost) = self.launch_estimate(now) {
+                    return Some(boost);
+                }
+                self.heartbeat
+                    .filter(|(tick, _)| now.saturating_sub(*tick) <= 2 * MISSILE_HEARTBEAT_INTERVAL)
+                    .map(|(_, p)| (p, Vec2::zero()))
+            }
+
+            // heard from recently inside terminal range of target, which it's assigned
+            // to, another launch at it would only arrive at the debris
+            pub fn terminal_on(&self, target: &TrackSnapshot, now: u32) -> bool {
+                self.target.id == target.id && self.heartbeat.is_some_and(|(tick, p)| {
+                    now.saturating_sub(tick) <= 2 * MISSILE_HEARTBEAT_INTERVAL && (p - target.position).length() < MISSILE_TERMINAL_RANGE
+                })
+            }
+
+            // ticks since we last heard from it, counted from launch until the first heartbeat
+            pub fn silence(&self) -> u32 {
+                current_tick() - self.heartbeat.map_or(self.launch_tick, |(tick, _)| tick)
+            }
+
+            // boosting along the launch heading from our launch state, only while it
+            // hasn't reported in, by the first heartbeat it's well clear of us
+            pub fn launch_estimate(&self, now: u32) -> Option<(Vec2, Vec2)> {
+                if self.heartbeat.is_some() {
+                    return None;
+                }
+                let t = now.saturating_sub(self.launch_tick) as f64 / 60.0;
+                let boost = Vec2::new(MISSILE_BOOST_ACCELERATION, 0.0).rotate(self.launch_heading);
+                Some((self.launch_position + self.launch_velocity * t + boost * (0.5 * t * t), self.launch_velocity + boost * t))
+            }
+
+            // the assigned track died right after a heartbeat near it
+            pub fn probable_kill(&self, radar: &Radar) -> bool {
+                let t = &self.target;
+                if radar.still_tracking(t.id) {
+                    return false;
+                }
+                match self.heartbeat {
+                    Some((tick, p)) => current_tick() - tick <= MISSILE_HEARTBEAT_INTERVAL && (p - t.position).length() < PROBABLE_KILL_RADIUS,
+                    None => false,
+                }
+            }
+
+            // a coordinated missile that never sent a heartbeat has no arrival to score
+            pub fn record_strike(&self, stats: &mut MatchStats) {
+                if let (Some(planned), Some((tick, _))) = (self.planned_arrival, self.heartbeat) {
+                    stats.strike_arrival(planned, tick);
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum StrikeClearance {
+            // nobody to coordinate with, or they went quiet
+            Independent,
+            // waiting on the wingman's answer or our own launch tick
+            Hold,
+            // launch now to arrive on the agreed tick
+            Coordinated(u32),
+        }
+
+        // time on target negotiation with a wingman: both offer their missile flight
+        // time on the target, the shorter one proposes an arrival tick both can make
+        // and each holds launch until its own flight time lands it on that tick
+        pub struct StrikeCoordinator {
+            // first tick we were ready to launch this time around
+            waiting_since: Option<u32>,
+
+            // what we're offering: target position and our flight time to it, ticks
+            offer: Option<(Vec2, u32)>,
+
+            // wingman's offer: tick heard, target position, flight ticks, sender
+            peer_offer: Option<(u32, Vec2, u32, u8)>,
+
+            // agreed target position and arrival tick, and whether we proposed it
+            arrival: Option<(Vec2, u32)>,
+            proposing: bool,
+
+            // last tick a wingman's strike traffic was heard, on any target
+            peer_heard: Option<u32>,
+
+            // target we gave up waiting on and launched at alone, not held for again
+            // until the wingman offers on it
+            gave_up: Option<Vec2>,
+        }
+
+        impl StrikeCoordinator {
+            pub fn new() -> Self {
+                StrikeCoordinator {
+                    waiting_since: None,
+                    offer: None,
+                    peer_offer: None,
+                    arrival: None,
+                    proposing: false,
+                    peer_heard: None,
+                    gave_up: None,
+                }
+            }
+
+            pub fn hear_peer(&mut self, now: u32) {
+                self.peer_heard = Some(now);
+            }
+
+            pub fn hear_offer(&mut self, target: Vec2, flight_ticks: u32, sender: u8, now: u32) {
+                self.peer_offer = Some((now, target, flight_ticks, sender));
+                self.hear_peer(now);
+            }
+
+            pub fn hear_proposal(&mut self, target: Vec2, arrival_tick: u32, now: u32) {
+                if !self.proposing {
+                    self.arrival = Some((target, arrival_tick));
+                }
+                self.hear_peer(now);
+            }
+
+            // called every tick we'd launch at target if it were up to us alone
+            // once an arrival is agreed the timeout no longer applies, we just wait for it
+            pub fn clearance(&mut self, target: Vec2, flight_ticks: u32, own_id: u8, now: u32) -> StrikeClearance {
+                let near = |p: Vec2| (p - target).length() < ASSIGNMENT_MATCH_RADIUS;
+                let agreed = self.arrival.is_some_and(|a| near(a.0));
+                let teammate = self.peer_heard.is_some_and(|tick| now - tick <= STRIKE_TIMEOUT_TICKS);
+                let peer_offer = self.peer_offer.filter(|o| near(o.1));
+                let given_up = self.gave_up.is_some_and(near) && peer_offer.is_none();
+                if !COORDINATED_STRIKE || (!teammate && !agreed) || (given_up && !agreed) {
+                    self.reset();
+                    return StrikeClearance::Independent;
+                }
+                let waiting_since = *self.waiting_since.get_or_insert(now);
+                self.offer = Some((target, flight_ticks));
+                // only traffic about this target keeps us waiting, a wingman busy on
+                // another one would otherwise hold every launch
+                let heard = peer_offer.map_or(waiting_since, |o| o.0.max(waiting_since));
+                if !agreed && now - heard > STRIKE_TIMEOUT_TICKS {
+                    trace!("strike: wingman went quiet, launching alone");
+                    self.reset();
+                    self.gave_up = Some(target);
+                    return StrikeClearance::Independent;
+                }
+
+                if !agreed {
+                    if let Some((_, peer_target, peer_flight, peer_id)) = peer_offer {
+                        if (flight_ticks, own_id) < (peer_flight, peer_id) {
+                            self.arrival = Some((peer_target, now + flight_ticks.max(peer_flight)));
+                            self.proposing = true;
+                        }
+                    }
+                }
+                match self.arrival.filter(|a| near(a.0)) {
+                    Some((_, arrival_tick)) if now + flight_ticks >= arrival_tick => {
+                        self.waiting_since = None;
+                        self.offer = None;
+                        self.peer_offer = None;
+                        StrikeClearance::Coordinated(arrival_tick)
+                    },
+                    _ => StrikeClearance::Hold,
+                }
+            }
+
+            // the proposal keeps going out until its arrival so a wingman that missed
+            // it still hears it, otherwise our offer while we hold
+            pub fn message(&mut self, own_id: u8, now: u32) -> Option<RadioMessage> {
+                match self.arrival {
+                    Some((target, arrival_tick)) if self.proposing && now < arrival_tick => {
+                        return Some(RadioMessage::StrikeProposal { target, arrival_tick, sender: own_id });
+                    },
+                    Some((_, arrival_tick)) if now >= arrival_tick => {
+                        self.arrival = None;
+                        self.proposing = false;
+                    },
+                    _ => {},
+                }
+                self.waiting_since?;
+                self.offer.map(|(target, flight_ticks)| RadioMessage::StrikeOffer { target, flight_ticks, sender: own_id })
+            }
+
+            fn reset(&mut self) {
+                *self = StrikeCoordinator { peer_heard: self.peer_heard, gave_up: self.gave_up, ..StrikeCoordinator::new() };
+            }
+        }
+
+        // inverse of the time to close distance starting at closing speed under a
+        // steady acceleration, the sooner we can be on a target the stronger our claim
+        pub fn claim_strength(distance: f64, closing: f64, acceleration: f64) -> f64 {
+            let time = if acceleration < E {
+                distance / closing.max(E)
+            } else {
+                (-closing + (closing * closing + 2.0 * acceleration * distance.max(0.0)).sqrt()) / acceleration
+            };
+            1.0 / (1.0 + time)
+        }
+
+        // teammates' claims on the targets they're engaging, so two fighters don't
+        // pile onto one foe while another goes unwatched
+        pub struct TargetClaims {
+            // tick heard, target position, strength and sender, one per teammate
+            peers: Vec<(u32, Vec2, f64, u8)>,
+        }
+
+        impl TargetClaims {
+            pub fn new() -> Self {
+                TargetClaims { peers: Vec::new() }
+            }
+
+            pub fn hear(&mut self, target: Vec2, strength: f64, sender: u8, now: u32) {
+                self.peers.retain(|c| c.3 != sender);
+                self.peers.push((now, target, strength, sender));
+            }
+
+            // a dead teammate's claim mustn't orphan its target
+            pub fn expire(&mut self, now: u32) {
+                self.peers.retain(|c| now.saturating_sub(c.0) < CLAIM_EXPIRY_TICKS);
+            }
+
+            // a teammate holds a stronger claim on whatever is at target, equal strengths
+            // go to the higher radio id so exactly one of us yields
+            pub fn yields(&self, target: Vec2, strength: f64, own_id: u8) -> bool {
+                let strength = (strength * CLAIM_STRENGTH_SCALE).round();
+                self.peers.iter().any(|&(_, position, peer_strength, sender)| {
+                    (position - target).length() < ASSIGNMENT_MATCH_RADIUS
+                        && ((peer_strength * CLAIM_STRENGTH_SCALE).round(), sender) > (strength, own_id)
+                })
+            }
+        }
+
+        // why a weapon the ship wanted to use stayed quiet this tick
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum WeaponHold {
+            Reloading,
+            BurstPause,
+            LaunchInterval,
+            NotFoe,
+            Infeasible,
+            TerminalOnTrack,
+        }
+
+        #[derive(Debug, Default)]
+        pub struct GunState {
+            pub reloading: bool,
+            pub rounds_this_burst: u32,
+            pause_until: u32,
+            pub last_hold: Option<WeaponHold>,
+        }
+
+        #[derive(Debug, Default)]
+        pub struct LauncherState {
+            pub reloading: bool,
+            last_launch_tick: Option<u32>,
+            pub last_hold: Option<WeaponHold>,
+        }
+
+        // every fire() the fighter makes goes through here: nothing is sent to a
+        // weapon that's still reloading, the gun fires in bursts and a launch has to
+        // pass the launch policy first. the state is kept for the hud
+        #[derive(Debug)]
+        pub struct WeaponsManager {
+            pub gun: GunState,
+            pub launcher: LauncherState,
+
+            burst_rounds: u32,
+            burst_pause_ticks: u32,
+            launch_interval: u32,
+        }
+
+        impl WeaponsManager {
+            pub fn new() -> Self {
+                WeaponsManager {
+                    gun: GunState::default(),
+                    launcher: LauncherState::default(),
+                    burst_rounds: GUN_BURST_ROUNDS,
+                    burst_pause_ticks: GUN_BURST_PAUSE_TICKS,
+                    launch_interval: MISSILE_LAUNCH_INTERVAL,
+                }
+            }
+
+            // weapon 0 with reload ticks to go, fires unless reloading or between bursts
+            pub fn fire_gun(&mut self, reload: u32, now: u32, controls: &mut ControlIntents) -> Result<(), WeaponHold> {
+                self.gun.reloading = reload > 0;
+                let result = if now < self.gun.pause_until {
+                    Err(WeaponHold::BurstPause)
+                } else if self.gun.reloading {
+                    Err(WeaponHold::Reloading)
+                } else {
+                    controls.fire(0);
+                    self.gun.rounds_this_burst += 1;
+                    if self.gun.rounds_this_burst >= self.burst_rounds {
+                        self.gun.rounds_this_burst = 0;
+                        self.gun.pause_until = now + self.burst_pause_ticks;
+                    }
+                    Ok(())
+                };
+                self.gun.last_hold = result.err();
+                result
+            }
+
+            // launch policy for weapon 1: loaded, spaced from the last launch, at a
+            // confirmed foe a missile can actually reach, and not one of ours is
+            // already terminal on it
+            pub fn clear_to_launch(&mut self, reload: u32, now: u32, target: &TrackSnapshot, feasible: bool, terminal_on_track: bool) -> Result<(), WeaponHold> {
+                self.launcher.reloading = reload > 0;
+                let result = if self.launcher.reloading {
+                    Err(WeaponHold::Reloading)
+                } else if self.launcher.last_launch_tick.is_some_and(|t| now.saturating_sub(t) < self.launch_interval) {
+                    Err(WeaponHold::LaunchInterval)
+                } else if target.class != TrackType::Foe {
+                    Err(WeaponHold::NotFoe)
+                } else if !feasible {
+                    Err(WeaponHold::Infeasible)
+                } else if terminal_on_track {
+                    Err(WeaponHold::TerminalOnTrack)
+                } else {
+                    Ok(())
+                };
+                self.launcher.last_hold = result.err();
+                result
+            }
+
+            pub fn launch(&mut self, now: u32, controls: &mut ControlIntents) {
+                controls.fire(1);
+                self.launcher.last_launch_tick = Some(now);
+            }
+
+            pub fn status(&self) -> String {
+                format!("gun {}{}/{}, launcher {}{}",
+                    if self.gun.reloading { "reloading " } else { "" }, self.gun.rounds_this_burst, self.burst_rounds,
+                    if self.launcher.reloading { "reloading" } else { "ready" },
+                    self.launcher.last_hold.map_or(String::new(), |h| format!(", held: {:?}", h)))
+            }
+        }
+
+        pub struct Fighter {
+            // does the ship have a target
+            target_lock: bool,
+
+            target: Option<TrackSnapshot>,
+
+            // current ship state
+            state: ShipState,
+
+            radio: Radio,
+
+            // ship radar component
+            radar: Radar,
+
+            rotation: Rotator,
+
+            // maps desired acceleration onto the ship's thrusters
+            thrust: ThrustPlanner,
+
+            // what this tick wants done, sent once by act
+            controls: ControlIntents,
+
+            // reload, burst and launch policy in front of every fire()
+            weapons: WeaponsManager,
+
+            // close range attack pattern
+            orbit: OrbitPattern,
+
+            // braking plan for long range intercepts
+            approach: ApproachPlanner,
+
+            // movement pattern while nothing is on radar
+            search: SearchPattern,
+
+            // missiles fired so far, the next launch gets this salvo index
+            missiles_launched: u32,
+
+            // most recent launch and the track it was assigned
+            last_launch: Option<(u32, u128)>,
+
+            // our missiles believed to still be flying
+            own_missiles: Vec<MissileStatus>,
+
+            // time on target launches with a wingman
+            strike: StrikeCoordinator,
+
+            // wingmen's claims on their targets
+            claims: TargetClaims,
+
+            // leader/wingman slot keeping with friendly fighters
+            formation: Formation,
+
+            // capital ship protection, active while a capital beacon is heard
+            escort: EscortConfig,
+
+            // patrol orbit around the escorted capital
+            escort_patrol: OrbitPattern,
+
+            // inferred gunfire from enemy fighters pointed at us
+            fire_warning: FireWarning,
+
+            // friendly hull or own missile we're steering off this tick
+            avoidance: Option<Avoidance>,
+
+            // picked once the spawn listening window closes, None until then
+            opening: Option<OpeningProfile>,
+            spawn_position: Vec2,
+            heard_friend: bool,
+
+            // the opening's sweep bias is still on, it comes off at first contact
+            opening_bias: bool,
+
+            // ticks our rounds should reach the track they were fired at
+            gun_impacts: Option<ImpactWindow>,
+
+            // rate limited lead bearing while inside knife fight range, None outside it
+            knife_lead_bearing: Option<f64>,
+
+            // positions our missiles reported going terminal on, with the tick heard
+            terminal_claims: Vec<(u32, Vec2)>,
+
+            // designated target that dropped off radar, chased until reacquired or expired
+            lost: Option<LostContact>,
+
+            // how the current engagement is being fought
+            combat_state: CombatState,
+
+            // set while an incoming missile has the gun, with what to go back to
+            point_defense: Option<PointDefense>,
+
+            // set while a terminal missile has the thrusters
+            evasion: Option<MissileEvasion>,
+
+            // tick a retreat started, None while we're willing to fight
+            fleeing_since: Option<u32>,
+            spawn_health: f64,
+
+            // direction of the opening burn, picked once
+            initial_burn: Option<Vec2>,
+
+            config: ShipConfig,
+
+            // the fighter's only source of randomness
+            rng: ShipRng,
+
+            // match long engagement counters, see MATCH_STATS
+            stats: MatchStats,
+
+            // TODO:
+            // lateral_throttle
+            // longitudinal_throttle
+        }
+
+        // what assess hands decide
+        pub struct Assessment {
+            // offset under fire, zero when nobody's shooting
+            jink: Vec2,
+
+            avoidance: Option<Avoidance>,
+        }
+
+        // guesses when an enemy fighter's guns are on us from its track geometry,
+        // there's no way to see bullets so this is all inference
+        pub struct FireWarning {
+            // how far off our predicted position a shooter's heading can be, radians
+            aim_tolerance: f64,
+
+            // shooters further out than this are ignored
+            gun_range: f64,
+
+            // lateral acceleration of the jink, m/s/s
+            jink_magnitude: f64,
+
+            // jink direction flips after a random wait in [min, max] ticks
+            min_flip_ticks: u32,
+            max_flip_ticks: u32,
+
+            // ticks under fire before combat state reacts
+            sustain_ticks: u32,
+
+            under_fire: bool,
+
+            // world bearing from us to the shooter
+            threat_bearing: Option<f64>,
+
+            ticks_under_fire: u32,
+            jink_sign: f64,
+            next_flip_tick: u32,
+        }
+
+        impl FireWarning {
+            pub fn new() -> Self {
+                FireWarning {
+                    aim_tolerance: 0.1,
+                    gun_range: 1_500.0,
+                    jink_magnitude: 30.0,
+                    min_flip_ticks: 10,
+                    max_flip_ticks: 20,
+                    sustain_ticks: 60,
+                    under_fire: false,
+                    threat_bearing: None,
+                    ticks_under_fire: 0,
+                    jink_sign: 1.0,
+                    next_flip_tick: 0,
+                }
+            }
+
+            // looks for the closest enemy fighter in gun range whose heading points at
+            // where its bullets would find us
+            pub fn assess(&mut self, radar: &Radar, own_position: Vec2, own_velocity: Vec2) {
+                let mut closest: Option<(f64, Vec2)> = None;
+                for t in radar.picture().iter() {
+                    if t.contact_class != Class::Fighter || !t.source.has_local() || !t.engageable(ENGAGE_MIN_PLOTS) {
+                        continue;
+                    }
+                    let range = t.distance_from(own_position);
+                    if range > self.gun_range {
+                        continue;
+                    }
+                    let predicted = own_position + own_velocity * (range / BULLET_SPEED);
+                    let aim_error = angle_diff(t.smoothed_heading, (predicted - t.position).angle());
+                    if aim_error.abs() < self.aim_tolerance && closest.is_none_or(|(r, _)| range < r) {
+                        closest = Some((range, t.position));
+                    }
+                }
+
+                self.under_fire = closest.is_some();
+                self.threat_bearing = closest.map(|(_, p)| (p - own_position).angle());
+                self.ticks_under_fire = if self.under_fire { self.ticks_under_fire + 1 } else { 0 };
+            }
+
+            pub fn sustained(&self) -> bool {
+                self.ticks_under_fire >= self.sustain_ticks
+            }
+
+            // lateral to the threat bearing, side flips on a randomized cadence
+            pub fn jink(&mut self, rng: &mut ShipRng, scale: f64) -> Vec2 {
+                let bearing = match self.threat_bearing {
+                    Some(bearing) if self.under_fire => bearing,
+                    _ => return Vec2::new(0.0, 0.0),
+                };
+                if current_tick() >= self.next_flip_tick {
+                    self.jink_sign = -self.jink_sign;
+                    self.next_flip_tick = current_tick() + rng.uniform(self.min_flip_ticks as f64, self.max_flip_ticks as f64) as u32;
+                }
+                Vec2::new(0.0, self.jink_magnitude * scale * self.jink_sign).rotate(bearing)
+            }
+
+            pub fn draw(&self) {
+                if let Some(bearing) = self.threat_bearing {
+                    debug_line(LAYER_THREATS, position(), position() + Vec2::new(500.0, 0.0).rotate(bearing), 0xff00ff);
+                    debug_text!(LAYER_THREATS, position() + Vec2::new(0.0, 60.0), 0xff00ff, "under fire {} ticks, bearing {:.2}", self.ticks_under_fire, bearing);
+                }
+            }
+        }
+
+        pub trait FigherGeometry {
+            fn shoot(&mut self);
+
+            fn fly_to_target(&mut self);
+
+            fn engage_target(&mut self);
+
+            // fires the launcher and records which track the new missile is assigned
+            fn launch_missile(&mut self);
+
+            fn turn_to_lead_target(&mut self, lead: Vec2);
+            fn turn_to_lead_target_aggressive(&mut self, lead: Vec2);
+
+            // close range gunnery, the lead swings faster than the hull can follow
+            fn knife_fight(&mut self, lead: Vec2, target: &TrackSnapshot);
+
+            fn heading_to_target(&mut self, target: Vec2);
+
+            fn basic_maneuver_to_target(&mut self);
+
+            fn set_current_target(&mut self, target: TrackSnapshot);
+        }
+
+        impl FigherGeometry for Fighter {
+            fn shoot(&mut self) {
+                let target = match self.target_or_search("shoot") {
+                    Some(target) => target,
+                    None => return,
+                };
+                // remote only tracks are good enough to cue missiles, not to aim guns
+                if !target.source.has_local() {
+                    hud!(HudLine::Weapons, "gun reload {}, holding: remote track", reload_ticks(0));
+                    self.stats.reject(FireReject::RemoteTrack);
+                    return;
+                }
+                // marked friendly since it was designated
+                if target.friendly {
+                    hud!(HudLine::Weapons, "gun reload {}, holding: friendly target", reload_ticks(0));
+                    self.stats.reject(FireReject::FriendlyInCorridor);
+                    return;
+                }
+                let class = target.contact_class;
+                let range = target.distance_from(position_fixed());
+                let quality = target.quality;
+                if quality < QUALITY_GUN {
+                    hud!(HudLine::Weapons, "gun reload {}, holding: quality {:.2}", reload_ticks(0), quality);
+                    self.stats.reject(FireReject::Quality);
+                    return;
+                }
+                let friendlies = self.friendlies();
+                if friendly_in_corridor(position(), velocity(), heading(), self.config.projectile_speed(0), range, &friendlies, FRIENDLY_CORRIDOR_WIDTH) {
+                    hud!(HudLine::Weapons, "gun reload {}, holding: friendly in corridor", reload_ticks(0));
+                    self.stats.reject(FireReject::FriendlyInCorridor);
+                    return;
+                }
+                let envelope = self.config.envelope(Weapon::Gun, class);
+                match envelope.map(|e| e.check(range)) {
+                    Some(Ok(())) => {
+                        // last word goes to where the round actually passes the hull
+                        let speed = self.config.projectile_speed(0);
+                        let horizon = envelope.map_or(0.0, |e| e.max_range) / speed.max(E);
+                        let pass = round_pass(position_fixed(), velocity(), heading(), speed, target.position, target.velocity, horizon);
+                        trace!("gun miss {:.1} m against a {:.0} m hull in {:.2}s", pass.miss, hull_radius(class), pass.time);
+                        debug_square(LAYER_LEADS, pass.impact, 2.0 * hull_radius(class), if pass.miss < hull_radius(class) { 0x00ff00 } else { 0xff0000 });
+                        if pass.miss >= hull_radius(class) {
+                            hud!(HudLine::Weapons, "gun reload {}, holding: miss {:.0} m", reload_ticks(0), pass.miss);
+                            self.stats.reject(FireReject::MissDistance);
+                            return;
+                        }
+                        match self.weapons.fire_gun(reload_ticks(0), current_tick(), &mut self.controls) {
+                            Ok(()) => {
+                                hud!(HudLine::Weapons, "{}, firing: on lead", self.weapons.status());
+                                self.record_gun_impact(target.id, range);
+                                self.stats.shot(0);
+                            },
+                            Err(hold) => {
+                                hud!(HudLine::Weapons, "{}, holding: {:?}", self.weapons.status(), hold);
+                                if hold == WeaponHold::Reloading {
+                                    self.stats.reject(FireReject::Reload);
+                                }
+                            },
+                        }
+                    },
+                    Some(Err(limit)) => {
+                        hud!(HudLine::Weapons, "gun reload {}, holding: {:?} vs {:?} at {:.0}", reload_ticks(0), limit, class, range);
+                        self.stats.reject(FireReject::Envelope);
+                    },
+                    None => {
+                        hud!(HudLine::Weapons, "gun holding: no envelope vs {:?}", class);
+                        self.stats.reject(FireReject::Envelope);
+                    },
+                }
+            }
+
+            fn fly_to_target(&mut self) {
+                if self.approach.flip_and_burn {
+                    // hull is pointed away for the braking burn, leave it there
+                    return;
+                }
+                if let Some(target) = self.target_or_search("fly_to_target") {
+                    self.turn_to_lead_target(target.get_target_direction(position_fixed()));
+                }
+            }
+
+            // engage fighter geometry with target
+            // TODO: this maybe should be changed to setup an attack orbit
+            fn engage_target(&mut self) {
+                if let Some(target) = self.target {
+
+                    // bias the lead by the target's turn, rotating its velocity by half the
+                    // heading change expected over the bullet flight time
+                    let flight_time = target.distance_from(position_fixed()) / self.config.projectile_speed(0);
+                    let turning_velocity = target.velocity.rotate(target.turn_rate * flight_time / 2.0);
+                    // aim at next tick's lead so our own maneuvering doesn't leave the solution behind
+                    let shooter = Shooter { position: position_fixed(), ..Shooter::own(self.thrust.last_command) };
+                    let motion = TargetMotion { position: target.position, velocity: turning_velocity, acceleration: target.acceleration };
+                    let lead_point = predicted_lead(&shooter, &motion, self.config.projectile_speed(0), 1.0 / 60.0);
+                    debug_triangle(LAYER_THREATS, target.position, 50.0, 0x00ff00);
+                    self.draw_lead_correction(&target, lead_point);
+
+                    // keep the nose on the lead while the orbit pattern handles translation
+                    let range = target.distance_from(position_fixed());
+                    if range < self.config.knife_fight_range {
+                        self.knife_fight(lead_point, &target);
+                    } else if range < self.orbit.far_range {
+                        self.knife_lead_bearing = None;
+                        self.turn_to_lead_target_aggressive(lead_point);
+                    } else {
+                        self.knife_lead_bearing = None;
+                        self.fly_to_target();
+                    }
+                    self.launch_missile();
+                }
+            }
+
+            fn launch_missile(&mut self) {
+                // no launch inside the arming range or at something not worth a missile
+                let target = match self.target_or_search("launch_missile") {
+                    Some(target) => target,
+                    None => return,
+                };
+                let feasible = target.intercept_feasible(position(), velocity(), MISSILE_BOOST_ACCELERATION, MISSILE_DELTA_V);
+                // a terminal claim can be missed on the radio, our own heartbeats say as much
+                let terminal_on_track = self.terminal_claims.iter()
+                    .any(|(tick, p)| current_tick() - tick < TERMINAL_CLAIM_TICKS && (*p - target.position).length() < PROBABLE_KILL_RADIUS)
+                    || self.active_missiles().iter().any(|m| m.terminal_on(&target, current_tick()));
+                if let Err(hold) = self.weapons.clear_to_launch(reload_ticks(1), current_tick(), &target, feasible, terminal_on_track) {
+                    if hold != WeaponHold::Reloading {
+                        hud!(HudLine::Weapons, "missile holding: {:?}", hold);
+                    }
+                    return;
+                }
+                let class = target.contact_class;
+                let range = target.distance_from(position_fixed());
+                let quality = target.quality;
+                if quality < QUALITY_MISSILE {
+                    hud!(HudLine::Weapons, "missile holding: quality {:.2}", quality);
+                    return;
+                }
+                match self.config.envelope(Weapon::Missile, class).map(|e| e.check(range)) {
+                    Some(Ok(())) => {},
+                    Some(Err(limit)) => {
+                        hud!(HudLine::Weapons, "missile holding: {:?} vs {:?} at {:.0}", limit, class, range);
+                        return;
+                    },
+                    None => {
+                        hud!(HudLine::Weapons, "missile holding: no envelope vs {:?}", class);
+                        return;
+                    },
+                }
+
+                // spread a salvo across the foe ships we could launch at, whichever has the
+                // fewest of our missiles already on the way to it, nearest first
+                let config = &self.config;
+                let mut tracks: Vec<TrackSnapshot> = self.radar.best_tracks(MISSILE_CHANNEL_COUNT as usize).into_iter()
+                    .filter(|t| matches!(t.class, TrackType::Foe) && !matches!(t.contact_class, Class::Missile | Class::Torpedo))
+                    .filter(|t| t.quality >= QUALITY_MISSILE && config.envelope(Weapon::Missile, t.contact_class)
+                        .is_some_and(|e| e.check(t.distance_from(position_fixed())).is_ok()))
+                    .collect();
+                if tracks.is_empty() {
+                    tracks.push(target);
+                }
+                let salvo = self.missiles_launched;
+                let in_flight: Vec<u128> = self.own_missiles.iter().map(|m| m.target.id).collect();
+                let assigned = match salvo_assignment(&tracks, &in_flight, Some(target.id)) {
+                    Some(track) => track,
+                    None => return,
+                };
+
+                // salvo spreading only applies to independent launches, a coordinated
+                // strike goes at the designated target
+                let flight_time = missile_flight_time(position(), velocity(), target.position, target.velocity,
+                    MISSILE_BOOST_ACCELERATION, MISSILE_DELTA_V);
+                let clearance = match flight_time {
+                    Some(t) => self.strike.clearance(target.position, (t * 60.0).ceil() as u32, self.radio.id, current_tick()),
+                    None => StrikeClearance::Independent,
+                };
+                let (assigned, planned_arrival) = match clearance {
+                    StrikeClearance::Independent => (assigned, None),
+                    StrikeClearance::Hold => {
+                        hud!(HudLine::Weapons, "missile holding: strike coordination");
+                        return;
+                    },
+                    StrikeClearance::Coordinated(arrival) => (self.target.unwrap_or(assigned), Some(arrival)),
+                };
+                // the boost goes out along the bearing, keep it clear of our own
+                let bearing = (assigned.position - position()).angle();
+                let friendlies: Vec<Vec2> = self.friendlies().iter().map(|(p, _)| *p).collect();
+                if friendly_in_launch_cone(position(), bearing, assigned.distance_from(position()), &friendlies, FRIENDLY_LAUNCH_CONE) {
+                    hud!(HudLine::Weapons, "missile holding: friendly in launch cone");
+                    self.stats.reject(FireReject::FriendlyInCorridor);
+                    return;
+                }
+                trace!("launching salvo {} at track {}, arrival {:?}", salvo, assigned.id, planned_arrival);
+
+                self.weapons.launch(current_tick(), &mut self.controls);
+                self.stats.missile_launched();
+                self.stats.shot(1);
+                self.missiles_launched += 1;
+                self.own_missiles.push(MissileStatus::launched(salvo, assigned, planned_arrival));
+                self.last_launch = Some((salvo, assigned.id));
+            }
+
+            fn set_current_target(&mut self, target: TrackSnapshot) {
+                self.stats.designate(target.id, target.first_tick, current_tick());
+                self.target = Some(target);
+            }
+
+            // NOTE: just write a new method, this works well enough now
+            fn turn_to_lead_target(&mut self, lead: Vec2) {
+                let current_diff = angle_diff(heading(), lead.angle());
+                if current_diff.abs() > 0.1 {
+                    self.snap_to_heading(lead.angle());
+                    self.stats.reject(FireReject::MissDistance);
+                } else {
+                    let torque = TurnController::precise(max_angular_acceleration()).command(heading(), lead.angle(), angular_velocity());
+                    trace!("firing torque: {}", torque);
+                    self.controls.torque(torque);
+                    self.shoot();
+                }
+            }
+            // NOTE: just write a new method, this works well enough now
+            fn turn_to_lead_target_aggressive(&mut self, lead: Vec2) {
+                // gate on where the round would pass next tick, not on raw heading error
+                let future_heading = heading() + angular_velocity() / 60.0;
+                let miss = predicted_miss_distance(future_heading, lead);
+                trace!("predicted miss distance: {}", miss);
+                if miss > GUN_MISS_TOLERANCE {
+                    let torque = TurnController::dodge(max_angular_acceleration()).command(heading(), lead.angle(), angular_velocity());
+                    trace!("turning torque: {}", torque);
+                    self.controls.torque(torque);
+                    self.stats.reject(FireReject::MissDistance);
+                } else {
+                    let torque = TurnController::precise(max_angular_acceleration()).command(heading(), lead.angle(), angular_velocity());
+                    trace!("firing torque: {}", torque);
+                    self.controls.torque(torque);
+                    self.shoot();
+                }
+            }
+
+            // the raw lead bearing is stepped no faster than the hull can turn so the
+            // controller stops chasing it back and forth, and the gun only fires on the
+            // predicted miss of the real lead since the nose sweeps through it at speed
+            fn knife_fight(&mut self, lead: Vec2, target: &TrackSnapshot) {
+                let max_step = max_heading_change(angular_velocity(), max_angular_acceleration(), 1.0 / 60.0);
+                let previous = self.knife_lead_bearing.unwrap_or(heading());
+                let commanded = rate_limited_bearing(previous, lead.angle(), max_step);
+                self.knife_lead_bearing = Some(commanded);
+                self.controls.torque(TurnController::for_hull(max_angular_acceleration()).command(heading(), commanded, angular_velocity()));
+                debug_line(LAYER_LEADS, position(), position() + Vec2::new(target.distance_from(position()), 0.0).rotate(commanded), 0xff8000);
+
+                // keep the approach's push along the line of sight, swap its lateral part
+                // for one that cancels the crossing velocity so there's less to turn through
+                let rel_p = target.position - position();
+                let los = rel_p.normalize();
+                let normal = los.rotate(PI / 2.0);
+                let crossing = (target.velocity - velocity()).dot(normal);
+                let along = los * self.thrust.last_command.dot(los);
+                self.thrust.apply_desired_acceleration(along + normal * crossing * KNIFE_LATERAL_GAIN, false, &mut self.controls);
+
+                let future_heading = heading() + angular_velocity() / 60.0;
+                let miss = predicted_miss_distance(future_heading, lead);
+                hud!(HudLine::Guidance, "knife fight, lead {:.3} rad behind, miss {:.1}", angle_diff(commanded, lead.angle()), miss);
+                if miss < GUN_MISS_TOLERANCE {
+                    self.shoot();
+                } else {
+                    self.stats.reject(FireReject::MissDistance);
+                }
+            }
+
+            fn heading_to_target(&mut self, target: Vec2) {
+                self.controls.torque(TurnController::for_hull(max_angular_acceleration()).command(heading(), target.angle(), angular_velocity()));
+            }
+            fn basic_maneuver_to_target(&mut self) {
+                let contact = match self.target_or_search("basic_maneuver_to_target") {
+                    Some(contact) => contact,
+                    None => return,
+                };
+                let contact_distance: f64 = contact.distance_from(position_fixed());
+                let contact_direction: Vec2 = contact.get_target_direction(position_fixed());
+                let contact_velocity: Vec2 = contact.velocity;
+                let contact_position: Vec2 = contact.position;
+                let contact_future = contact_position + (contact_velocity);
+                let contact_future_distance = (position_fixed() - contact_future).length();
+                let mut target_distance_increasing = false;
+
+                let tti = contact.time_to_closest_approach(position_fixed(), velocity());
+                let cpa_distance = contact.distance_at_closest_approach(position_fixed(), velocity());
+                trace!("time to closest approach: {}, distance at closest approach: {}", tti, cpa_distance);
+
+                if contact_future_distance > contact_distance {
+                    // target moving relatively away
+                    trace!("target distance increasing!");
+                    target_distance_increasing = true;
+                } else {
+                    // target moving relatively closer
+                    trace!("target distance decreasing!");
+                    target_distance_increasing = false;
+                }
+
+                let unit_dir = contact_direction.normalize();
+
+                let relative_quadrant = contact_position.get_relative_quadrant(position_fixed());
+                trace!("target in relative quadrant {:?}!", relative_quadrant);
+
+                let closing_speed = contact.closing_speed_from(position_fixed(), velocity());
+
+                trace!("closing speed: {}", closing_speed);
+
+                let normal_vec_y = Vec2::new(-contact_direction.x, contact_direction.y);
+                let normal_vec_x = Vec2::new(contact_direction.x, -contact_direction.y);
+
+                debug_line(LAYER_NAV, position(), normal_vec_y, 0xff0000);
+                debug_line(LAYER_NAV, position(), normal_vec_x, 0xff0000);
+
+                // orbit radius comes from the gun's envelope against this kind of target
+                if let Some(envelope) = self.config.envelope(Weapon::Gun, contact.contact_class) {
+                    self.orbit.fit_to_envelope(&envelope);
+                }
+
+                // our missiles swing around to come in offset from our aspect at launch, so
+                // stay off that side
+                let missile_axis = self.own_missiles.iter().find(|m| m.target.id == contact.id)
+                    .map(|m| m.approach + OrdnanceProfile::for_class(Class::Missile).approach_offset);
+                self.orbit.set_avoid_bearing(missile_axis);
+
+                // a target opening faster than flee_speed gets chased rather than orbited
+                let target_fleeing = closing_speed < -self.orbit.flee_speed;
+                if contact_distance < self.orbit.engage_range && !target_fleeing {
+                    let desired = self.orbit.orbit_acceleration(contact_direction, contact_velocity - velocity());
+                    let desired = self.energy_managed(desired, &contact);
+                    debug_line(LAYER_NAV, position(), position() + desired, 0x00ffff);
+                    self.thrust.apply_desired_acceleration(desired, false, &mut self.controls);
+                    return;
+                }
+                self.orbit.disengage();
+
+                // brake so we arrive at the standoff radius instead of blowing through it
+                let range_to_standoff = contact_distance - self.orbit.standoff_radius;
+                if let Some(brake) = self.approach.plan(range_to_standoff, closing_speed, unit_dir) {
+                    trace!("braking on approach, flip and burn: {}", self.approach.flip_and_burn);
+                    self.thrust.apply_desired_acceleration(brake, self.approach.flip_and_burn, &mut self.controls);
+                } else {
+                    // not braking yet, keep closing
+                    // range bands come from the orbit pattern, defaults [0, 500], [500, 1000], [1000, +]
+                    let desired = self.orbit.band_acceleration(contact_distance, unit_dir, contact_velocity - velocity(), target_distance_increasing);
+                    let desired = self.energy_managed(desired, &contact);
+                    self.thrust.apply_desired_acceleration(desired, false, &mut self.controls);
+                }
+            }
+        }
+
+        impl Fighter {
+            pub fn default_config() -> ShipConfig {
+                ShipConfig {
+                    guns: vec![GunConfig { index: 0, projectile_speed: BULLET_SPEED, hit_radius: GUN_MISS_TOLERANCE, offset: vec2(FIGHTER_GUN_OFFSET, 0.0), spread: 0.0, turret: false }],
+                    radar: RadarProfile::for_class(Class::Fighter),
+                    close_range: 500.0,
+                    far_range: 1_000.0,
+                    envelopes: vec![
+                        // ordnance is small and fast, start shooting at it early
+                        (Weapon::Gun, Some(Class::Missile), EngagementEnvelope { min_range: 0.0, max_range: 3_000.0, standoff_min: 300.0, standoff_max: 900.0 }),
+                        (Weapon::Gun, Some(Class::Torpedo), EngagementEnvelope { min_range: 0.0, max_range: 3_000.0, standoff_min: 300.0, standoff_max: 900.0 }),
+                        (Weapon::Gun, Some(Class::Fighter), EngagementEnvelope { min_range: 0.0, max_range: 1_500.0, standoff_min: 300.0, standoff_max: 900.0 }),
+                        (Weapon::Gun, None, EngagementEnvelope { min_range: 0.0, max_range: 2_000.0, standoff_min: 500.0, standoff_max: 1_200.0 }),
+                        (Weapon::Missile, Some(Class::Fighter), EngagementEnvelope { min_range: 500.0, max_range: 15_000.0, standoff_min: 300.0, standoff_max: 900.0 }),
+                        (Weapon::Missile, None, EngagementEnvelope { min_range: 500.0, max_range: 20_000.0, standoff_min: 500.0, standoff_max: 1_200.0 }),
+                    ],
+                    knife_fight_range: 300.0,
+                    nav_gain: 0.0,
+                    apn_gain: 0.0,
+                    combat_speed_min: 50.0,
+                    combat_speed_max: 300.0,
+                    flee_health_fraction: 0.3,
+                    target_weights: TargetWeights::for_class(Class::Fighter),
+                }
+            }
+
+            pub fn new(config: ShipConfig) -> Self {
+                Fighter {
+                    target_lock: false,
+                    target: None,
+                    state: ShipState::NoTarget,
+                    radio: fighter_radio(),
+                    radar: Radar::new(config.radar, "fighter_radar"),
+                    rotation: Rotator::new(),
+                    thrust: ThrustPlanner::new(true),
+                    controls: ControlIntents::default(),
+                    weapons: WeaponsManager::new(),
+                    orbit: OrbitPattern::new(config.close_range, config.far_range),
+                    approach: ApproachPlanner::new(),
+                    search: SearchPattern::new(),
+                    missiles_launched: 0,
+                    last_launch: None,
+                    own_missiles: Vec::new(),
+                    strike: StrikeCoordinator::new(),
+                    claims: TargetClaims::new(),
+                    formation: Formation::new(),
+                    escort: EscortConfig::new(),
+                    escort_patrol: OrbitPattern::new(config.close_range, config.far_range),
+                    fire_warning: FireWarning::new(),
+                    avoidance: None,
+                    opening: None,
+                    spawn_position: position(),
+                    heard_friend: false,
+                    opening_bias: false,
+                    gun_impacts: None,
+                    knife_lead_bearing: None,
+                    terminal_claims: Vec::new(),
+                    lost: None,
+                    combat_state: CombatState::Attack,
+                    point_defense: None,
+                    evasion: None,
+                    fleeing_since: None,
+                    spawn_health: health(),
+                    initial_burn: None,
+                    config,
+                    rng: ShipRng::from_spawn(),
+                    stats: MatchStats::default(),
+                }
+            }
+
+            pub fn set_state(&mut self, state: ShipState) {
+                self.state = state;
+            }
+
+            pub fn get_state(&self) -> &ShipState {
+                &self.state
+            }
+
+            // the opening burn on a random vector, then start sweeping the arena
+            pub fn no_target(&mut self) {
+                let dir = match self.initial_burn {
+                    Some(dir) => dir,
+                    None => {
+                        let dir = self.rng.unit_vector();
+                        trace!("random dir: {}", dir);
+                        self.initial_burn = Some(dir);
+                        dir
+                    },
+                };
+                let mag = 42.20;
+                self.thrust.apply_desired_acceleration(dir * mag, false, &mut self.controls);
+
+                if current_tick() >= INITIAL_BURN_TICKS {
+                    trace!("initial burn done, searching");
+                    self.set_state(ShipState::Searching);
+                }
+            }
+
+            pub fn active_missiles(&self) -> &[MissileStatus] {
+                &self.own_missiles
+            }
+
+            // our claim on the designated target while engaged
+            pub fn claim(&self) -> Option<RadioMessage> {
+                if !matches!(self.get_state(), ShipState::Engaged | ShipState::OutOfTargetRange) {
+                    return None;
+                }
+                let t = self.target?;
+                let strength = claim_strength(t.distance_from(position_fixed()), t.closing_speed_from(position_fixed(), velocity()), max_forward_acceleration());
+                Some(RadioMessage::TargetClaim { target: t.position, strength, sender: self.radio.id })
+            }
+
+            pub fn designated(&self) -> Option<u128> {
+                self.target.map(|t| t.id)
+            }
+
+            pub fn radar_mut(&mut self) -> &mut Radar {
+                &mut self.radar
+            }
+
+            pub fn set_radio_id(&mut self, id: u8) {
+                self.radio.id = id;
+            }
+
+            // drop missiles that went quiet or probably hit, then mark the rest
+            fn update_missile_status(&mut self) {
+                let radar = &self.radar;
+                for m in &mut self.own_missiles {
+                    m.target = radar.picture().refresh(m.target);
+                }
+                let stats = &mut self.stats;
+                self.own_missiles.retain(|m| {
+                    if m.probable_kill(radar) {
+                        trace!("salvo {} probable kill on track {}", m.salvo, m.target.id);
+                        stats.missile_hit();
+                        m.record_strike(stats);
+                        return false;
+                    }
+                    if m.silence() > MISSILE_HEARTBEAT_TIMEOUT {
+                        trace!("salvo {} silent, written off", m.salvo);
+                        stats.missile_dud();
+                        m.record_strike(stats);
+                        return false;
+                    }
+                    true
+                });
+
+                for m in &self.own_missiles {
+                    if let Some((_, p)) = m.heartbeat {
+                        debug_triangle(LAYER_LEADS, p, 20.0, 0x00ffff);
+                        debug_line(LAYER_LEADS, p, m.target.position, 0x00ffff);
+                    }
+                }
+                hud!(HudLine::Weapons, "{} missiles in flight", self.own_missiles.len());
+            }
+
+            // inside the gun envelope, trade pursuit for slowing down when we're too fast
+            // relative to the target to get the nose around, see manage_energy
+            fn energy_managed(&self, pursuit: Vec2, contact: &TrackSnapshot) -> Vec2 {
+                let envelope = match self.config.envelope(Weapon::Gun, contact.contact_class) {
+                    Some(envelope) => envelope,
+                    None => return pursuit,
+                };
+                let rel_p = contact.position - position();
+                if rel_p.length() > envelope.max_range {
+                    return pursuit;
+                }
+                let band = (self.config.combat_speed_min, self.config.combat_speed_max);
+                let rel_v = contact.velocity - velocity();
+                let (desired, decision) = manage_energy(rel_p, rel_v, pursuit, band, max_forward_acceleration(), envelope.max_range);
+                hud!(HudLine::Nav, "energy: {:.0} m/s vs band {:.0}-{:.0}, {:?}", rel_v.length(), band.0, band.1, decision);
+                desired
+            }
+
+            // remember where the designated target was when its track dies
+            fn note_lost_target(&mut self) {
+                let dropped = self.target.is_some_and(|t| !self.radar.still_tracking(t.id));
+                if dropped && !matches!(self.get_state(), ShipState::Engaged | ShipState::OutOfTargetRange) {
+                    // nothing to dead reckon toward, just let go of the expired track
+                    self.target = None;
+                    return;
+                }
+                if dropped && self.infer_kill(true) {
+                    return;
+                }
+                if dropped {
+                    trace!("target track dropped, dead reckoning");
+                    self.lost = self.target.take().map(|t| LostContact::from_track(&t));
+                }
+            }
+
+            // friendly tracks and our own missiles that have checked in lately, as
+            // (position, velocity), the missiles without a velocity to go on
+            fn friendlies(&self) -> Vec<(Vec2, Vec2)> {
+                let tracks = self.radar.picture().iter()
+                    .filter(|t| t.friendly || t.class == TrackType::Friend)
+                    .map(|t| (t.position, t.velocity));
+                let missiles = self.active_missiles().iter().filter_map(|m| m.estimate(current_tick()));
+                tracks.chain(missiles).collect()
+            }
+
+            // temporary: the lead as it was solved before our own velocity went into it
+            // in magenta against the shooter relative one in yellow, they part as soon
+            // as we strafe
+            fn draw_lead_correction(&self, target: &TrackSnapshot, lead: Vec2) {
+                if !layer_active(LAYER_LEADS) {
+                    return;
+                }
+                let speed = self.config.projectile_speed(0);
+                if let Some(uncorrected) = intercept_point(position_fixed(), Vec2::zero(), target.position, target.velocity, speed) {
+                    debug_line(LAYER_LEADS, position_fixed(), uncorrected, 0xff00f0);
+                }
+                debug_line(LAYER_LEADS, position_fixed(), position_fixed() + lead, 0xffff00);
+            }
+
+            // the designated target as a snapshot, a missing one clears the designation and
+            // drops an engagement back to searching rather than taking the ship down
+            fn target_or_search(&mut self, caller: &str) -> Option<TrackSnapshot> {
+                if self.target.is_some() {
+                    return self.target;
+                }
+                trace!("{}: no target, back to searching", caller);
+                self.radar.clear_designation();
+                if matches!(self.get_state(), ShipState::Engaged | ShipState::OutOfTargetRange) {
+                    self.set_state(ShipState::Searching);
+                }
+                None
+            }
+
+            // stretches the window while we keep firing at the same track
+            fn record_gun_impact(&mut self, track_id: u128, range: f64) {
+                let impact = current_tick() + (range / self.config.projectile_speed(0) * 60.0).ceil() as u32;
+                match self.gun_impacts.as_mut() {
+                    Some(window) if window.track_id == track_id => window.last_tick = impact,
+                    _ => self.gun_impacts = Some(ImpactWindow { track_id, first_tick: impact, last_tick: impact }),
+                }
+            }
+
+            // the designated track went quiet as our rounds or a terminal missile got
+            // there, drop it and let selection run now instead of orbiting the corpse
+            // until expiry, dropped is set when the radar already let the track go
+            fn infer_kill(&mut self, dropped: bool) -> bool {
+                self.terminal_claims.retain(|(tick, _)| current_tick() - tick < TERMINAL_CLAIM_TICKS);
+                let target = match self.target {
+                    Some(t) => t,
+                    None => return false,
+                };
+                let gun = self.gun_impacts.as_ref().filter(|w| w.track_id == target.id).map(|w| (w.first_tick, w.last_tick));
+                let missile = self.terminal_claims.iter()
+                    .filter(|(_, p)| (*p - target.position).length() < PROBABLE_KILL_RADIUS)
+                    .map(|(tick, _)| (*tick, *tick))
+                    .last();
+                // an expired track has nothing left to be confident about
+                let quality = if dropped { 0.0 } else { target.quality };
+                if !kill_inferred(target.last_plot_tick, current_tick(), gun.or(missile), quality) {
+                    return false;
+                }
+
+                trace!("track {} probably dead, last plot {}", target.id, target.last_plot_tick);
+                hud!(HudLine::Weapons, "track {} probably dead", target.id);
+                self.radar.mark_probably_dead(target.id);
+                self.stats.kill();
+                self.target = None;
+                self.gun_impacts = None;
+                self.radar.clear_designation();
+                if !self.radar.has_engageable_contacts() {
+                    self.set_state(ShipState::Searching);
+                }
+                true
+            }
+
+            // fly at the dead reckoned position with the radar looking at it, false once
+            // there's nothing to recover
+            fn pursue_lost_target(&mut self) -> bool {
+                let lost = match self.lost.as_ref() {
+                    Some(lost) => lost,
+                    None => return false,
+                };
+
+                // a foe a wingman has a stronger claim on isn't ours to pick back up
+                let (claims, own_id) = (&self.claims, self.radio.id);
+                let (origin, own_velocity) = (position_fixed(), velocity());
+                let claimed = |t: &RadarTrack| {
+                    let strength = claim_strength(t.distance_from(origin), t.closing_speed_from(origin, own_velocity), max_forward_acceleration());
+                    claims.yields(t.position, strength, own_id)
+                };
+                if let Some((id, track)) = lost.reacquire(&self.radar, &claimed).and_then(|id| Some((id, self.radar.picture().get(id)?))) {
+                    trace!("reacquired lost target as track {}", id);
+                    self.set_current_target(track);
+                    self.radar.designate(id);
+                    self.lost = None;
+                    self.radar.clear_sweep_bias();
+                    return false;
+                }
+
+                if lost.expired() {
+                    trace!("lost target not reacquired, searching");
+                    self.lost = None;
+                    self.radar.clear_sweep_bias();
+                    if !self.radar.has_engageable_contacts() {
+                        self.set_state(ShipState::Searching);
+                    }
+                    return false;
+                }
+
+                let predicted = lost.predicted_position();
+                self.radar.bias_sweep((predicted - position()).angle(), lost.search_half_width(position()));
+                lost.draw();
+                let desired = (predicted - position()).normalize() * max_forward_acceleration() + (lost.velocity - velocity());
+                self.thrust.apply_desired_acceleration(desired, true, &mut self.controls);
+                true
+            }
+
+            // listens through the formation election, then commits to an opening once
+            fn pick_opening(&mut self) {
+                if self.opening_bias && self.radar.has_engageable_contacts() {
+                    self.radar.clear_sweep_bias();
+                    self.opening_bias = false;
+                }
+                if self.opening.is_some() || current_tick() < FORMATION_ELECTION_TICKS {
+                    return;
+                }
+                let heard_capital = self.escort.capital_state().is_some();
+                let opening = choose_opening(class(), self.spawn_position, world_size() / 2.0, self.heard_friend, heard_capital);
+                let profile = OpeningProfile::for_opening(opening, &self.config);
+                trace!("opening: {:?}", profile);
+
+                if matches!(self.state, ShipState::NoTarget | ShipState::Searching) {
+                    self.set_state(profile.state);
+                }
+                self.radar.set_search_state(profile.radar_state);
+                self.radar.request_beam(profile.beam);
+                if let Some(half_width) = profile.sweep_toward_center {
+                    // spawned on the center, the nose is as good a guess as any
+                    let toward = if position().length() > BOUNDARY_MARGIN { (-position()).angle() } else { heading() };
+                    self.radar.bias_sweep(toward, half_width);
+                    self.opening_bias = true;
+                }
+                self.search.cruise_speed *= profile.search_speed_scale;
+                self.config.combat_speed_min = profile.combat_speed.0;
+                self.config.combat_speed_max = profile.combat_speed.1;
+                self.opening = Some(profile);
+            }
+
+            // friendly tracks and our missiles still on their launch boost
+            fn collision_threat(&self) -> Option<Avoidance> {
+                let mut obstacles: Vec<(Obstacle, Vec2, Vec2)> = self.radar.picture().iter()
+                    .filter(|t| t.friendly)
+                    .map(|t| (Obstacle::Friend(t.id), t.position, t.velocity))
+                    .collect();
+                for m in &self.own_missiles {
+                    if let Some((p, v)) = m.launch_estimate(current_tick()) {
+                        obstacles.push((Obstacle::Missile(m.salvo), p, v));
+                    }
+                }
+                let avoidance = collision_avoidance(position(), velocity(), &obstacles, max_lateral_acceleration());
+                if let Some(a) = avoidance {
+                    trace!("avoiding {:?}, cpa {:.0} m in {:.1}s", a.obstacle, a.miss, a.time);
+                    debug_line(LAYER_NAV, position(), position() + a.acceleration, 0xff00ff);
+                }
+                avoidance
+            }
+
+            // sustained fire at close range on a damaged hull stops being a fair fight
+            fn update_combat_state(&mut self) {
+                let close = self.target.is_some_and(|t| t.distance_from(position_fixed()) < self.orbit.close_range);
+                self.combat_state = if self.fire_warning.sustained() && close && health() < LOW_HEALTH / 2.0 {
+                    CombatState::Flee
+                } else if self.fire_warning.sustained() && close && health() < LOW_HEALTH {
+                    CombatState::Evade
+                } else {
+                    CombatState::Attack
+                };
+                trace!("combat state: {:?}", self.combat_state);
+            }
+
+            fn foe_ships(&self) -> Vec<TrackSnapshot> {
+                self.radar.picture().iter()
+                    .filter(|t| matches!(t.class, TrackType::Foe) && !t.probably_dead)
+                    .copied()
+                    .collect()
+            }
+
+            // decides whether we're running, the fight picks up where select_target
+            // leaves it once we stop
+            fn update_retreat(&mut self) {
+                let foes = self.foe_ships();
+                let nearest = foes.iter().min_by(|a, b| a.distance_from(position()).total_cmp(&b.distance_from(position())));
+                let (range, outrun) = nearest.map_or((f64::INFINITY, false), |t| {
+                    let range = t.distance_from(position());
+                    (range, outpaced(range, t.closing_speed_from(position(), velocity()), max_forward_acceleration()))
+                });
+                let health_fraction = if self.spawn_health > 0.0 { health() / self.spawn_health } else { 1.0 };
+                let classes: Vec<Class> = foes.iter().map(|t| t.contact_class).collect();
+                let odds = relative_health(health(), &classes);
+                let ticks = self.fleeing_since.map(|since| current_tick().saturating_sub(since));
+                let flee = should_flee(ticks, health_fraction, self.config.flee_health_fraction, odds, foes.len(), range, outrun);
+                match (self.fleeing_since, flee) {
+                    (None, true) => {
+                        trace!("retreating, health {:.0}% against {} foes", health_fraction * 100.0, foes.len());
+                        self.fleeing_since = Some(current_tick());
+                    },
+                    (Some(since), false) => {
+                        trace!("retreat over after {} ticks, nearest foe {:.0} m", current_tick().saturating_sub(since), range);
+                        self.fleeing_since = None;
+                    },
+                    _ => {},
+                }
+                if let Some(since) = self.fleeing_since {
+                    self.combat_state = CombatState::Flee;
+                    hud!(HudLine::Nav, "RETREAT {} ticks, health {:.0}%, odds {:.2}, {} foes, nearest {:.0} m{}", current_tick().saturating_sub(since),
+                        health_fraction * 100.0, odds, foes.len(), range, if outrun { ", outrun" } else { "" });
+                }
+            }
+
+            // burn away from where the pursuit is centered, or from the shooter or the
+            // target with no foe ships on the scope, without leaving the arena. the
+            // closest pursuer is designated so the radar watches it and a loaded
+            // launcher gets a parting shot
+            fn retreat(&mut self) {
+                let foes = self.foe_ships();
+                let nearest = foes.iter().min_by(|a, b| a.distance_from(position()).total_cmp(&b.distance_from(position())));
+                if let Some(track) = nearest.and_then(|nearest| self.radar.picture().get(nearest.id)) {
+                    self.set_current_target(track);
+                    self.radar.designate(track.id);
+                    self.launch_missile();
+                }
+
+                let positions: Vec<Vec2> = foes.iter().map(|t| t.position).collect();
+                let threat = match (hostile_centroid(position(), &positions), self.fire_warning.threat_bearing, self.target.as_ref()) {
+                    (Some(centroid), _, _) => {
+                        debug_line(LAYER_NAV, position(), centroid, 0xff0000);
+                        (centroid - position()).normalize()
+                    },
+                    (None, Some(bearing), _) => Vec2::new(1.0, 0.0).rotate(bearing),
+                    (None, None, Some(t)) => t.get_target_direction(position()).normalize(),
+                    (None, None, None) => return,
+                };
+                let escape = boundary_escape_vector(-threat);
+                self.thrust.apply_desired_acceleration(escape * max_forward_acceleration(), true, &mut self.controls);
+            }
+
+            // engaged inside gun range, formation gives way to the fight
+            fn engaged_in_gun_range(&self) -> bool {
+                match (self.get_state(), self.target.as_ref()) {
+                    (ShipState::Engaged, Some(t)) => t.distance_from(position_fixed()) < self.formation.break_range,
+                    _ => false,
+                }
+            }
+
+            // slot keeping for a wingman, None when not in formation
+            // far from the slot this reuses the approach planner so rejoining doesn't overshoot
+            fn formation_acceleration(&mut self) -> Option<Vec2> {
+                let (slot, leader_velocity) = self.formation.slot(self.engaged_in_gun_range())?;
+                let to_slot = slot - position();
+                let relative_velocity = leader_velocity - velocity();
+                debug_line(LAYER_NAV, position(), slot, 0x00ff00);
+
+                let range = to_slot.length();
+                if range > self.formation.rejoin_range {
+                    let closing = closing_speed(to_slot, relative_velocity);
+                    if let Some(brake) = self.approach.plan(range, closing, to_slot.normalize()) {
+                        return Some(brake);
+                    }
+                    return Some(to_slot.normalize() * max_forward_acceleration());
+                }
+                Some(to_slot * self.formation.position_gain + relative_velocity * self.formation.velocity_gain)
+            }
+
+            pub fn searching_for_target(&mut self) {
+                trace!("searching for target");
+
+                if let Some(desired) = self.formation_acceleration() {
+                    trace!("keeping formation slot");
+                    self.thrust.apply_desired_acceleration(desired, true, &mut self.controls);
+                    return;
+                }
+
+                // anything on the scope, even a single tentative plot, beats the pattern:
+                // close on it while the radar firms it up and the engagement logic takes
+                // over once it's engageable, a spurious one drops out and the leg resumes
+                let anything = |t: &RadarTrack| t.engageable(0);
+                if let Some(contact) = self.radar.get_closest_target_matching(position_fixed(), &anything).and_then(|id| self.radar.picture().get(id)) {
+                    trace!("search paused on leg {} for track {}", self.search.leg, contact.id);
+                    hud!(HudLine::Nav, "search leg {} paused, investigating {}", self.search.leg, contact.id);
+                    let to_contact = contact.get_target_direction(position_fixed()).normalize();
+                    let desired = to_contact * self.search.cruise_speed + contact.velocity - velocity();
+                    self.thrust.apply_desired_acceleration(desired, true, &mut self.controls);
+                    return;
+                }
+
+                // fly the center run then the lawnmower legs so the radar horizon covers the interior
+                let desired = self.search.search_acceleration();
+                hud!(HudLine::Nav, "search leg {}, {} ticks without contact", self.search.leg, self.radar.ticks_since_contact);
+                self.thrust.apply_desired_acceleration(desired, true, &mut self.controls);
+            }
+
+            pub fn engaging_target(&mut self) {
+                trace!("engaging target");
+
+                if self.target.is_some() {
+                    if self.combat_state == CombatState::Flee {
+                        self.retreat();
+                        return;
+                    }
+                    self.basic_maneuver_to_target();
+                    self.engage_target();
+                }
+            }
+
+            // patrol around the capital, break off only for threats to the capital and
+            // never chase fighters past the leash
+            pub fn escorting(&mut self) {
+                let (capital_position, capital_velocity) = match self.escort.capital_state() {
+                    Some(state) => state,
+                    None => {
+                        trace!("capital beacon lost, leaving escort");
+                        self.set_state(ShipState::Searching);
+                        return;
+                    },
+                };
+
+                let threat = self.radar.select_threat(capital_position, capital_velocity)
+                    .and_then(|id| self.radar.picture().get(id))
+                    .filter(|t| {
+                        match t.contact_class {
+                            Class::Missile | Class::Torpedo => t.closing_speed_from(capital_position, capital_velocity) > 0.0,
+                            _ => t.distance_from(capital_position) < self.escort.leash_distance,
+                        }
+                    });
+
+                if let Some(track) = threat {
+                    trace!("escort engaging threat {} to capital", track.id);
+                    self.set_current_target(track);
+                    self.basic_maneuver_to_target();
+                    self.engage_target();
+                    return;
+                }
+
+                let patrol_point = self.escort.patrol_point(capital_position, capital_velocity);
+                debug_line(LAYER_NAV, capital_position, patrol_point, 0x00ff00);
+                let to_patrol = patrol_point - position();
+                self.escort_patrol.standoff_radius = self.escort.patrol_radius;
+                let desired = self.escort_patrol.orbit_acceleration(capital_position - position(), capital_velocity - velocity());
+                // blend the orbit with a pull toward the swept patrol point so we stay in sector
+                self.thrust.apply_desired_acceleration(desired + to_patrol * 0.1, true, &mut self.controls);
+            }
+
+            // everything on the main engine toward where the target will be, nothing to
+            // shoot at until the range comes down
+            pub fn out_of_range_target(&mut self) {
+                let target = match self.target {
+                    Some(target) => target,
+                    None => {
+                        self.set_state(ShipState::Searching);
+                        return;
+                    },
+                };
+                let range = target.distance_from(position_fixed());
+                let closing = target.closing_speed_from(position_fixed(), velocity()).max(1.0);
+                let lead = target.position + target.velocity * (range / closing).min(10.0);
+                debug_line(LAYER_NAV, position_fixed(), lead, 0xffff00);
+                hud!(HudLine::Nav, "closing on {}, range {:.0}, closing {:.0}", target.id, range, closing);
+                let desired = (lead - position_fixed()).normalize() * max_forward_acceleration();
+                self.thrust.apply_desired_acceleration(desired, true, &mut self.controls);
+            }
+
+            // nothing seen for a long while, sweep at long range and fly the search
+            // pattern in case the contact is just past the horizon. the radar drops
+            // back to medium range by itself on the first plot
+            pub fn out_of_radar_range(&mut self) {
+                self.radar.set_search_state(RadarState::LongRange);
+                let desired = self.search.search_acceleration();
+                hud!(HudLine::Nav, "long range sweep, leg {}, {} ticks without contact", self.search.leg, self.radar.ticks_since_contact);
+                self.thrust.apply_desired_acceleration(desired, true, &mut self.controls);
+            }
+
+            // longest reach any weapon has on the target's class
+            fn weapon_range(&self, class: Class) -> f64 {
+                [Weapon::Gun, Weapon::Missile].iter()
+                    .filter_map(|weapon| self.config.envelope(*weapon, class))
+                    .map(|envelope| envelope.max_range)
+                    .fold(0.0, f64::max)
+            }
+
+            fn update_range_state(&mut self) {
+                let state = *self.get_state();
+                let next = match (state, self.target) {
+                    (ShipState::Engaged | ShipState::OutOfTargetRange, Some(target)) => {
+                        next_engaged_state(state, target.distance_from(position_fixed()), self.weapon_range(target.contact_class))
+                    },
+                    (ShipState::Searching | ShipState::OutOfRadarRange, _) => next_search_state(state, self.radar.ticks_since_contact),
+                    _ => state,
+                };
+                if next != state {
+                    trace!("{:?} -> {:?}", state, next);
+                    self.set_state(next);
+                }
+            }
+
+            pub fn ship_control(&mut self) {
+                self.update_retreat();
+                if self.fleeing_since.is_some() && !matches!(self.get_state(), ShipState::PointDefense) {
+                    self.controls.behavior(ControlPriority::Evade, "retreat");
+                    self.retreat();
+                    return;
+                }
+                let behavior = match self.get_state() {
+                    ShipState::NoTarget => (ControlPriority::Routine, "no_target"),
+                    ShipState::Searching => (ControlPriority::Routine, "search"),
+                    ShipState::Engaged => (ControlPriority::Engage, "engage"),
+                    ShipState::OutOfTargetRange => (ControlPriority::Engage, "out_of_range"),
+                    ShipState::OutOfRadarRange => (ControlPriority::Routine, "out_of_radar_range"),
+                    ShipState::Escorting => (ControlPriority::Routine, "escort"),
+                    ShipState::PointDefense => (ControlPriority::Evade, "point_defense"),
+                };
+                self.controls.behavior(behavior.0, behavior.1);
+                match self.get_state() {
+                    ShipState::NoTarget => self.no_target(),
+                    ShipState::Searching => self.searching_for_target(),
+                    ShipState::Engaged => self.engaging_target(),
+                    ShipState::OutOfTargetRange => self.out_of_range_target(),
+                    ShipState::OutOfRadarRange => self.out_of_radar_range(),
+                    ShipState::Escorting => self.escorting(),
+                    ShipState::PointDefense => self.defending_point(),
+                }
+            }
+
+            // nose and gun on the incoming missile's lead, the jink keeps flying us
+            pub fn defending_point(&mut self) {
+                let missile = match self.target {
+                    Some(missile) => missile,
+                    None => return,
+                };
+                let shooter = Shooter::own(self.thrust.last_command);
+                let lead = self.config.gun(0)
+                    .and_then(|gun| intercept_for_gun(&gun, &missile, &shooter))
+                    .map_or(missile.position - position_fixed(), |solution| solution.lead);
+                debug_triangle(LAYER_THREATS, missile.position, 50.0, 0xff0000);
+                hud!(HudLine::Guidance, "point defense on {}, range {:.0}, closing {:.0}",
+                    missile.id, missile.distance_from(position_fixed()), missile.closing_speed_from(position_fixed(), velocity()));
+                self.knife_lead_bearing = None;
+                self.turn_to_lead_target_aggressive(lead);
+            }
+
+            // time optimal turn onto angle, rotation.estimated_ticks_to_angle says
+            // how long it has left
+            pub fn snap_to_heading(&mut self, angle: f64) {
+                let error = angle_diff(heading(), angle);
+                let torque = self.rotation.command(error, angular_velocity(), max_angular_acceleration());
+                trace!("snap to {:.3}: error {:.3}, {:?}, {} ticks", angle, error, self.rotation.phase, self.rotation.estimated_ticks_to_angle);
+                self.controls.torque(torque);
+            }
+
+            pub fn tick(&mut self) {
+                // uncomment for squadron tutorial 11 for wall of bullets
+                // TODO: figure out how to use this strategically
+                // fire(0);
+                self.tick_with(&mut GameControls);
+            }
+
+            // sense, assess, decide, act, report, each stage hands the next what it
+            // needs and only act touches the ship's controls and the radar, so the
+            // command doesn't depend on which helper happened to call accelerate last.
+            // everything between sense() and act() reads tracks from the radar's
+            // picture, so the radar never meets an outstanding borrow
+            pub fn tick_with(&mut self, controls: &mut dyn ControlSink) {
+                let heard = self.sense();
+                let assessment = self.assess(heard);
+                self.decide(&assessment);
+                self.act(controls);
+                self.report();
+            }
+
+            fn sense(&mut self) -> Option<RadioMessage> {
+                self.thrust.begin_tick();
+                self.radar.sense();
+                self.radio.poll()
+            }
+
+            // what the picture means: who's friendly, who's shooting at us and which
+            // track we're fighting
+            fn assess(&mut self, heard: Option<RadioMessage>) -> Assessment {
+                self.handle_radio(heard);
+                // the target keeps its last copy once the track is gone, note_lost_target
+                // dead reckons from it
+                self.target = self.target.map(|t| self.radar.picture().refresh(t));
+                self.update_missile_status();
+                self.formation.update_role();
+                self.pick_opening();
+
+                self.fire_warning.assess(&self.radar, position(), velocity());
+                self.update_combat_state();
+                let jink_scale = if self.combat_state == CombatState::Attack { 1.0 } else { max_lateral_acceleration() / self.fire_warning.jink_magnitude };
+
+                if self.escort.capital_state().is_some() {
+                    self.set_state(ShipState::Escorting);
+                }
+                self.infer_kill(false);
+                self.note_lost_target();
+                self.select_target();
+                self.update_point_defense();
+                self.update_evasion();
+
+                Assessment {
+                    // the evasive break replaces the gunfire jink
+                    jink: if self.evasion.is_some() { Vec2::new(0.0, 0.0) } else { self.fire_warning.jink(&mut self.rng, jink_scale) },
+                    // evading and fleeing outrank not bumping into friends
+                    avoidance: if self.combat_state == CombatState::Attack { self.collision_threat() } else { None },
+                }
+            }
+
+            // maneuver and weapons intents, nothing here reaches the game until act
+            fn decide(&mut self, assessment: &Assessment) {
+                self.avoidance = assessment.avoidance;
+                self.thrust.set_jink(assessment.jink);
+                self.thrust.set_avoidance(assessment.avoidance.map_or(Vec2::new(0.0, 0.0), |a| a.acceleration));
+                self.controls.behavior(ControlPriority::Engage, "lost_target");
+                if matches!(self.get_state(), ShipState::PointDefense) || !self.pursue_lost_target() {
+                    self.ship_control();
+                }
+                // the break outranks whatever the state asked of the thrusters, the hull
+                // is left where it was pointed for the gun
+                self.controls.behavior(ControlPriority::Evade, "evasion");
+                if let Some(acceleration) = self.evasive_acceleration() {
+                    self.thrust.apply_desired_acceleration(acceleration, false, &mut self.controls);
+                }
+            }
+
+            // the one place this tick's controls go out, then the radar is pointed
+            // for what we just committed to
+            fn act(&mut self, controls: &mut dyn ControlSink) {
+                self.controls.flush(controls);
+                // a missile we're dodging gets the beam, we need to see it pass or die
+                match self.target.as_ref() {
+                    Some(t) => self.radar.designate(t.id),
+                    None => self.radar.clear_designation(),
+                }
+                if let Some(evasion) = self.evasion {
+                    self.radar.cue(evasion.missile);
+                }
+                // track while scan: the designated target gets its dwells from the revisit
+                // schedule and the sweep keeps looking for a second attacker, a full lock
+                // would leave us blind to it
+                self.radar.release_lock();
+                self.radar.point(self.thrust.last_command);
+            }
+
+            fn report(&mut self) {
+                self.transmit_radio();
+                self.fire_warning.draw();
+
+                if let Some(t) = self.target {
+                    hud!(HudLine::Guidance, "track {}, range {:.0}, closing {:.0}", t.id, t.distance_from(position_fixed()), t.closing_speed_from(position_fixed(), velocity()));
+                }
+                let avoiding = self.avoidance.map_or(String::new(), |a| format!(", AVOIDING {:?} cpa {:.0} m in {:.1}s", a.obstacle, a.miss, a.time));
+                hud!(HudLine::Nav, "{:?}/{:?}, accel {:.1}{}", self.state, self.combat_state, self.thrust.last_command.length(), avoiding);
+                match self.opening {
+                    Some(p) => hud!(HudLine::Profile, "{:?}{}, combat speed {:.0}-{:.0}, search x{:.1}", p.opening,
+                        if FORCED_OPENING.is_some() { " (forced)" } else { "" }, p.combat_speed.0, p.combat_speed.1, p.search_speed_scale),
+                    None => hud!(HudLine::Profile, "listening for teammates, {} ticks to opening", FORMATION_ELECTION_TICKS.saturating_sub(current_tick())),
+                }
+
+                self.stats.tracks(self.radar.picture().tracks.len());
+                self.stats.launches(&self.radar.launch_inferences());
+                let looks_over = health() < STATS_FINAL_HEALTH || (self.stats.kills > 0 && self.radar.ticks_since_contact >= STATS_IDLE_TICKS);
+                self.stats.report(looks_over);
+            }
+
+            // a missile about to arrive gets dodged whatever else is going on, until its
+            // track dies, goes stale or it starts opening
+            fn update_evasion(&mut self) {
+                let (point, own_velocity) = (position(), velocity());
+                let terminal = |t: &RadarTrack| {
+                    incoming_missile(&t.snapshot(), point, own_velocity)
+                        && t.quality() >= QUALITY_ENGAGE
+                        && time_to_impact(t.distance_from(point), t.closing_speed_from(point, own_velocity)) < EVADE_TIME_TO_IMPACT
+                };
+                if let Some(evasion) = self.evasion {
+                    let defeated = self.radar.picture().get(evasion.missile).is_none_or(|t| {
+                        t.probably_dead || t.quality < QUALITY_ENGAGE || t.closing_speed_from(point, own_velocity) <= 0.0
+                    });
+                    if defeated {
+                        trace!("missile {} defeated or passed, evasion over", evasion.missile);
+                        self.evasion = None;
+                    }
+                }
+                if self.evasion.is_none() {
+                    if let Some(missile) = self.radar.select_threat_matching(point, own_velocity, &terminal) {
+                        trace!("missile {} terminal, evading", missile);
+                        self.evasion = Some(MissileEvasion::new(missile, &mut self.rng, current_tick()));
+                    }
+                }
+                if self.evasion.is_some() {
+                    self.combat_state = CombatState::Evade;
+                }
+            }
+
+            fn evasive_acceleration(&mut self) -> Option<Vec2> {
+                let mut evasion = self.evasion?;
+                let missile = self.radar.picture().get(evasion.missile)?;
+                let range = missile.distance_from(position_fixed());
+                let acceleration = evasion.acceleration(missile.position - position_fixed(), max_forward_acceleration(), &mut self.rng, current_tick());
+                self.evasion = Some(evasion);
+                hud!(HudLine::Guidance, "evading missile {}, impact in {:.1}s", missile.id,
+                    time_to_impact(range, missile.closing_speed_from(position_fixed(), velocity())));
+                Some(acceleration)
+            }
+
+            // an incoming missile takes the gun from whatever we were doing, and once its
+            // track dies or it stops closing the old target and state come back, or a
+            // search if the old target expired in the meantime. the designation stats
+            // only see the old target, the missile isn't a change of mind
+            pub fn update_point_defense(&mut self) {
+                let (point, own_velocity) = (position(), velocity());
+                let incoming = |t: &RadarTrack| incoming_missile(&t.snapshot(), point, own_velocity);
+                match self.point_defense {
+                    None => {
+                        let missile = match self.radar.select_threat_matching(point, own_velocity, &incoming) {
+                            Some(id) => id,
+                            None => return,
+                        };
+                        trace!("point defense on missile track {}", missile);
+                        self.point_defense = Some(PointDefense {
+                            missile,
+                            resume_target: self.target.map(|t| t.id),
+                            resume_state: *self.get_state(),
+                        });
+                        self.target = self.radar.picture().get(missile);
+                        self.set_state(ShipState::PointDefense);
+                    },
+                    Some(defense) => {
+                        let engaged = self.radar.picture().get(defense.missile).is_some_and(|t| incoming_missile(&t, point, own_velocity));
+                        if engaged {
+                            self.set_state(ShipState::PointDefense);
+                            return;
+                        }
+                        trace!("point defense on {} done", defense.missile);
+                        self.point_defense = None;
+                        match defense.resume_target.and_then(|id| self.radar.picture().get(id)) {
+                            Some(track) => {
+                                self.target = Some(track);
+                                self.set_state(defense.resume_state);
+                            },
+                            None => {
+                                self.target = None;
+                                self.set_state(match defense.resume_state {
+                                    ShipState::Engaged | ShipState::OutOfTargetRange => ShipState::Searching,
+                                    state => state,
+                                });
+                            },
+                        }
+                    },
+                }
+            }
+
+            // ships only, incoming ordnance is the escort's and the flak's business
+            fn designatable(t: &RadarTrack) -> bool {
+                t.engageable(ENGAGE_MIN_PLOTS) && matches!(t.class, TrackType::Foe) && t.quality() >= QUALITY_ENGAGE
+            }
+
+            // engaged while there's a confirmed foe, the designated one is followed until
+            // it dies or a clearly better foe turns up
+            pub fn select_target(&mut self) {
+                if matches!(self.get_state(), ShipState::PointDefense) {
+                    return;
+                }
+                let foe = self.radar.get_closest_target_matching(position_fixed(), &Self::designatable).is_some();
+                let engaged = matches!(self.get_state(), ShipState::Engaged | ShipState::OutOfTargetRange);
+                if !foe && self.lost.is_none() && engaged {
+                    // only friendlies left on the scope, keep looking (the formation slot handles joining up)
+                    trace!("no confirmed foes, back to searching");
+                    self.target = None;
+                    self.set_state(ShipState::Searching);
+                }
+                if foe && self.lost.is_none() && !matches!(self.get_state(), ShipState::Escorting) {
+                    if !engaged {
+                        self.set_state(ShipState::Engaged);
+                    }
+
+                    // a foe a wingman has a stronger claim on is theirs, unless every foe is
+                    self.claims.expire(current_tick());
+                    let (claims, own_id) = (&self.claims, self.radio.id);
+                    let (origin, own_velocity) = (position_fixed(), velocity());
+                    let unclaimed = |t: &RadarTrack| {
+                        let strength = claim_strength(t.distance_from(origin), t.closing_speed_from(origin, own_velocity), max_forward_acceleration());
+                        Self::designatable(t) && !claims.yields(t.position, strength, own_id)
+                    };
+                    let keep: &dyn Fn(&RadarTrack) -> bool = if self.radar.get_closest_target_matching(origin, &unclaimed).is_some() {
+                        &unclaimed
+                    } else {
+                        &Self::designatable
+                    };
+                    let best = self.radar.select_target(&self.config.target_weights, keep);
+                    let current = self.target.map(|t| t.id);
+                    match self.radar.reselect(best, &self.config.target_weights, keep) {
+                        Some(id) if current != Some(id) => {
+                            trace!("setting new target {}", id);
+                            if let Some(track) = self.radar.picture().get(id) {
+                                self.set_current_target(track);
+                            }
+                        },
+                        _ => (),
+                    }
+                }
+                self.update_range_state();
+            }
+
+            pub fn handle_radio(&mut self, message: Option<RadioMessage>) {
+                match message {
+                    Some(RadioMessage::ContactReport { position, velocity, class, age, sender }) => {
+                        self.radar.add_detection_point(Detection::Remote { position, velocity, class, age, sender });
+                        if sender != self.radio.id {
+                            self.heard_friend = true;
+                        }
+                    },
+                    Some(RadioMessage::StrikeOffer { target, flight_ticks, sender }) if sender != self.radio.id => {
+                        self.strike.hear_offer(target, flight_ticks, sender, current_tick());
+                    },
+                    Some(RadioMessage::StrikeProposal { target, arrival_tick, sender }) if sender != self.radio.id => {
+                        self.strike.hear_proposal(target, arrival_tick, current_tick());
+                    },
+                    Some(RadioMessage::TargetClaim { target, strength, sender }) if sender != self.radio.id => {
+                        self.claims.hear(target, strength, sender, current_tick());
+                    },
+                    Some(RadioMessage::FormationLead { position, velocity, heading, engaged }) => {
+                        self.formation.hear_leader(position, velocity, heading, engaged);
+                        self.radar.mark_friendly(position, velocity);
+                        self.heard_friend = true;
+                    },
+                    Some(RadioMessage::CapitalBeacon { position, velocity }) => {
+                        self.escort.hear_capital(position, velocity);
+                        self.radar.mark_friendly(position, velocity);
+                    },
+                    Some(RadioMessage::TerminalOn { position }) => {
+                        self.terminal_claims.push((current_tick(), position));
+                    },
+                    Some(RadioMessage::MissileHeartbeat { position, target, salvo }) => {
+                        // salvo indexes aren't unique across the team, the target has to line up too
+                        let ours = self.own_missiles.iter_mut().find(|m| {
+                            m.salvo == salvo && m.target.distance_from(target) < ASSIGNMENT_MATCH_RADIUS
+                        });
+                        if let Some(missile) = ours {
+                            missile.hear_heartbeat(current_tick(), position);
+                        }
+                    },
+                    _ => {},
+                }
+            }
+
+            // queues everything worth saying and lets the scheduler pick one per tick:
+            // strike negotiation on odd ticks while it's running, the formation beacon
+            // on even ticks, a contact sharing burst every CONTACT_SHARE_INTERVAL ticks
+            // and our target re-broadcast since listeners may miss any single message
+            fn transmit_radio(&mut self) {
+                if current_tick() % CONTACT_SHARE_INTERVAL == 0 {
+                    for t in self.radar.best_tracks(CONTACT_SHARE_COUNT) {
+                        let report = RadioMessage::ContactReport {
+                            position: t.position,
+                            velocity: t.velocity,
+                            class: t.contact_class,
+                            age: current_tick() - t.last_plot_tick,
+                            sender: self.radio.id,
+                        };
+                        self.radio.enqueue(report, RadioPriority::Low, CONTACT_REPORT_DEADLINE);
+                    }
+                }
+                // the handoff carries the newest missile's assignment so it can latch onto it
+                let handoff = self.last_launch
+                    .filter(|(_, id)| self.radar.still_tracking(*id))
+                    .and_then(|(salvo, id)| Some((salvo, self.radar.picture().get(id)?)))
+                    .or_else(|| self.target.map(|t| (self.missiles_launched, t)));
+                // the claim goes out every CLAIM_INTERVAL while engaged, a lost one is just repeated
+                if let Some(claim) = self.claim() {
+                    self.radio.enqueue(claim, RadioPriority::Low, RADIO_STATE_DEADLINE);
+                }
+                let strike = self.strike.message(self.radio.id, current_tick()).filter(|_| current_tick() % 2 == 1);
+                if let Some(strike) = strike {
+                    self.radio.enqueue(strike, RadioPriority::High, RADIO_STATE_DEADLINE);
+                }
+                if self.formation.should_beacon() && (current_tick() % 2 == 0 || handoff.is_none()) {
+                    let beacon = RadioMessage::FormationLead {
+                        position: position(),
+                        velocity: velocity(),
+                        heading: heading(),
+                        engaged: self.engaged_in_gun_range(),
+                    };
+                    self.radio.enqueue(beacon, RadioPriority::Normal, RADIO_STATE_DEADLINE);
+                }
+                if let Some((salvo, track)) = handoff {
+                    let intercept = missile_intercept_point(position(), velocity(), track.position, track.velocity,
+                        MISSILE_BOOST_ACCELERATION, MISSILE_DELTA_V);
+                    // once it's off the rails the approach is the one it launched with
+                    let approach = self.own_missiles.iter().find(|m| m.salvo == salvo)
+                        .map_or((position() - track.position).angle(), |m| m.approach);
+                    let handoff = RadioMessage::TargetHandoff {
+                        position: track.position,
+                        velocity: track.velocity,
+                        salvo,
+                        bearing: (intercept - position()).angle(),
+                        overrides: handoff_overrides(track.contact_class),
+                        approach,
+                        sender: self.radio.id,
+                    };
+                    self.radio.enqueue(handoff, RadioPriority::Low, RADIO_STATE_DEADLINE);
+                }
+                let traffic = self.radio.flush();
+                self.stats.radio(&traffic);
+            }
+        }
+
+        // the track with the fewest of our missiles already headed for it, ties go to
+        // the designated target, then to the earlier one in tracks, the nearest when
+        // the caller passes them nearest first as best_tracks does
+        pub fn salvo_assignment(tracks: &[TrackSnapshot], in_flight: &[u128], designated: Option<u128>) -> Option<TrackSnapshot> {
+            tracks.iter()
+                .min_by_key(|t| (in_flight.iter().filter(|id| **id == t.id).count(), Some(t.id) != designated))
+                .copied()
+        }
+
+        // a track whose last plot landed around when our ordnance arrived, that has
+        // been quiet since and whose quality fell away, one missed plot isn't enough
+        pub fn kill_inferred(last_plot_tick: u32, now: u32, impact_window: Option<(u32, u32)>, quality: f64) -> bool {
+            let (first, last) = match impact_window {
+                Some(window) => window,
+                None => return false,
+            };
+            let quiet = now.saturating_sub(last_plot_tick) >= KILL_SILENCE_TICKS;
+            let at_impact = last_plot_tick + KILL_WINDOW_TICKS >= first && last_plot_tick <= last + KILL_WINDOW_TICKS;
+            quiet && at_impact && quality < KILL_QUALITY
+        }
+    }
+
+    // missile: handoff, launch boost, soft launch and terminal guidance
+    mod missile {
+        use super::*;
+
+        pub struct Missile {
+            target: Option<TrackSnapshot>,
+            radar: Radar,
+            radio: Radio,
+            target_heading_delay_ticks: u32,
+            acceleration_delay_ticks: u32,
+            thrust: ThrustPlanner,
+            controls: ControlIntents,
+
+            // tick the missile came alive, used to time out an unconfirmed assignment
+            spawn_tick: u32,
+
+            // heading inherited from the launcher, where its targets usually are
+            launch_heading: f64,
+
+            // designated target that dropped off radar, chased until reacquired or expired
+            lost: Option<LostContact>,
+
+            // set once the body is aligned for the boost, or alignment was skipped
+            soft_launch_done: bool,
+
+            // target assigned by the launching fighter, latched from the first handoff heard
+            assignment: Option<MissileAssignment>,
+
+            // positions other missiles reported going terminal on, with the tick heard
+            terminal_claims: Vec<(u32, Vec2)>,
+
+            config: ShipConfig,
+
+            // how this ordnance class flies, before the launcher's handoff overrides
+            profile: OrdnanceProfile,
+
+            // fuel at spawn, the terminal reserve is a share of it
+            launch_fuel: f64,
+
+            // weave phase offset so a salvo doesn't weave in lockstep, rad
+            weave_phase: f64,
+
+            // set once the midcourse dogleg is flown, skipped or unaffordable
+            dogleg_done: bool,
+
+            // the missile's only source of randomness
+            rng: ShipRng,
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum FlightPhase {
+            Boost,
+            Coast,
+            Terminal,
+        }
+
+        // per-class flight behavior, missiles and torpedoes share the guidance code
+        // and differ only in these numbers
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub struct OrdnanceProfile {
+            // share of max_forward_acceleration midcourse guidance may use, terminal gets all of it
+            pub midcourse_acceleration: f64,
+
+            // share of the launch fuel held back for the terminal phase, midcourse
+            // coasts once it's down to this
+            pub terminal_fuel_reserve: f64,
+
+            // share of the launch fuel the boost may spend getting up to speed before
+            // midcourse coasts on lateral corrections alone
+            pub boost_fuel_fraction: f64,
+
+            // range to the target the terminal phase starts at, m
+            pub terminal_range: f64,
+
+            // detonation distance, m
+            pub fuse_radius: f64,
+
+            // having just gone past the closest approach inside this, m, detonate anyway
+            pub pass_radius: f64,
+
+            // lateral weave across the line of sight, m/s/s and ticks per cycle,
+            // 0.0 flies straight
+            pub weave_acceleration: f64,
+            pub weave_period: u32,
+
+            // keep weaving inside terminal range, otherwise the terminal run is plain PN
+            pub weave_terminal: bool,
+
+            // the weave runs between these ranges to the target, m, the last stretch
+            // inside the inner one is always flown clean
+            pub weave_outer_range: f64,
+            pub weave_inner_range: f64,
+
+            // channel the launcher's handoffs arrive on
+            pub channel: u8,
+
+            // midcourse swings this far around the target from the launcher's approach
+            // bearing before turning in, rad, 0.0 comes straight in
+            pub approach_offset: f64,
+        }
+
+        impl OrdnanceProfile {
+            // anything that isn't a torpedo flies as a missile
+            pub fn for_class(class: Class) -> Self {
+                match class {
+                    // slow and fat, so it paces its fuel and weaves through point defense
+                    Class::Torpedo => OrdnanceProfile {
+                        midcourse_acceleration: 0.6,
+                        terminal_fuel_reserve: 0.25,
+                        boost_fuel_fraction: 0.5,
+                        terminal_range: 3_000.0,
+                        fuse_radius: 30.0,
+                        pass_radius: 60.0,
+                        weave_acceleration: 50.0,
+                        weave_period: 120,
+                        weave_terminal: true,
+                        weave_outer_range: f64::MAX,
+                        weave_inner_range: 300.0,
+                        channel: CHANNEL_TORPEDO,
+                        approach_offset: 0.0,
+                    },
+                    _ => OrdnanceProfile {
+                        midcourse_acceleration: 1.0,
+                        terminal_fuel_reserve: 0.0,
+                        boost_fuel_fraction: 0.6,
+                        terminal_range: MISSILE_TERMINAL_RANGE,
+                        fuse_radius: 15.0,
+                        pass_radius: 40.0,
+                        weave_acceleration: 40.0,
+                        weave_period: 90,
+                        weave_terminal: true,
+                        weave_outer_range: 4_000.0,
+                        weave_inner_range: 500.0,
+                        channel: CHANNEL_SANCHEZ,
+                        approach_offset: 5.0 * PI / 12.0,
+                    },
+                }
+            }
+
+            // overrides only take behavior away, so applying them twice is harmless
+            pub fn with_overrides(mut self, overrides: u8) -> Self {
+                if overrides & HANDOFF_NO_WEAVE != 0 {
+                    self.weave_acceleration = 0.0;
+                }
+                if overrides & HANDOFF_STRAIGHT_TERMINAL != 0 {
+                    self.weave_terminal = false;
+                }
+                if overrides & HANDOFF_FULL_BURN != 0 {
+                    self.midcourse_acceleration = 1.0;
+                    self.terminal_fuel_reserve = 0.0;
+                }
+                self
+            }
+
+            // boost while under the commanded closing speed and inside the boost's share
+            // of the fuel, then coast, and anything goes inside terminal range.
+            // distance is to the predicted intercept, closing is positive closing in
+            pub fn phase(&self, distance: f64, closing: f64, fuel: f64, launch_fuel: f64) -> FlightPhase {
+                if distance < self.terminal_range {
+                    FlightPhase::Terminal
+                } else if fuel > launch_fuel * (1.0 - self.boost_fuel_fraction) && closing < distance / MISSILE_COAST_SECONDS {
+                    FlightPhase::Boost
+                } else {
+                    FlightPhase::Coast
+                }
+            }
+
+            // acceleration the fuel plan allows this tick, m/s/s
+            pub fn available_acceleration(&self, terminal: bool, fuel: f64, launch_fuel: f64, max: f64) -> f64 {
+                if fuel <= 0.0 {
+                    0.0
+                } else if terminal {
+                    max
+                } else if fuel <= launch_fuel * self.terminal_fuel_reserve {
+                    // coast on the reserve until the terminal phase
+                    0.0
+                } else {
+                    max * self.midcourse_acceleration
+                }
+            }
+
+            // lateral weave demand age ticks into the flight, m/s/s
+            pub fn weave(&self, age: u32, phase: f64, terminal: bool) -> f64 {
+                if self.weave_acceleration <= 0.0 || self.weave_period == 0 || (terminal && !self.weave_terminal) {
+                    return 0.0;
+                }
+                self.weave_acceleration * (TAU * age as f64 / self.weave_period as f64 + phase).sin()
+            }
+
+            // inside the weave band, with fuel to spare and an intercept that stays
+            // feasible on a share of it. feasible is that check, done by the caller
+            pub fn weave_allowed(&self, range: f64, fuel: f64, launch_fuel: f64, feasible: bool) -> bool {
+                range < self.weave_outer_range && range > self.weave_inner_range
+                    && fuel > launch_fuel * WEAVE_MIN_FUEL_FRACTION && feasible
+            }
+        }
+
+        // launcher side: targets without point defense don't need weaving or a
+        // fuel reserve, anything unknown gets the full profile
+        pub fn handoff_overrides(target: Class) -> u8 {
+            match target {
+                Class::Frigate | Class::Cruiser | Class::Unknown => 0,
+                _ => HANDOFF_NO_WEAVE | HANDOFF_STRAIGHT_TERMINAL | HANDOFF_FULL_BURN,
+            }
+        }
+
+        // handoffs don't carry the class, but the overrides tell the launcher's small
+        // targets from capital ships, which is what the track's maneuver bound needs
+        pub fn handoff_class(overrides: u8) -> Class {
+            if overrides == 0 { Class::Unknown } else { Class::Fighter }
+        }
+
+        // launch handoff as last heard, fields age with every tick
+        pub struct MissileAssignment {
+            salvo: u32,
+            position: Vec2,
+            velocity: Vec2,
+
+            // intercept bearing the launcher worked out for us
+            bearing: f64,
+
+            // HANDOFF_* flags applied on top of our profile
+            overrides: u8,
+
+            // bearing out from the target the launcher is attacking along
+            approach: f64,
+
+            // the launcher's radio id, another launcher's salvo numbers run just like its own
+            sender: u8,
+            tick: u32,
+        }
+
+        impl MissileAssignment {
+            // dead reckoned position of the assigned target
+            pub fn predicted_position(&self) -> Vec2 {
+                self.position + self.velocity * ((current_tick() - self.tick) as f64 / 60.0)
+            }
+        }
+
+        impl Missile {
+            // missiles have no gun, projectile speeds stay out of their intercept math
+            pub fn default_config() -> ShipConfig {
+                ShipConfig {
+                    guns: Vec::new(),
+                    radar: RadarProfile::for_class(Class::Missile),
+                    close_range: 500.0,
+                    far_range: 1_000.0,
+                    envelopes: Vec::new(),
+                    knife_fight_range: 0.0,
+                    nav_gain: MISSILE_NAV_GAIN,
+                    apn_gain: 1.0,
+                    combat_speed_min: 0.0,
+                    combat_speed_max: 0.0,
+                    flee_health_fraction: 0.0,
+                    target_weights: TargetWeights::for_class(Class::Missile),
+                }
+            }
+
+            pub fn new(config: ShipConfig, profile: OrdnanceProfile) -> Self {
+                let mut radar = Radar::new(config.radar, "missile_radar");
+                // the launcher is right here at spawn and is the nearest return we'll get
+                radar.mark_friendly(position(), velocity());
+                let mut radio = Radio::new(profile.channel);
+                radio.set_interval(RadioKind::MissileHeartbeat, MISSILE_HEARTBEAT_INTERVAL);
+                trace!("ordnance profile: {:?}", profile);
+                let mut rng = ShipRng::from_spawn();
+                Missile {
+                    target: None,
+                    target_heading_delay_ticks: MISSILE_TARGET_HEADING_DELAY,
+                    acceleration_delay_ticks: MISSILE_ACCELERATION_DELAY,
+                    radar,
+                    radio,
+                    thrust: ThrustPlanner::new(false),
+                    controls: ControlIntents::default(),
+                    spawn_tick: current_tick(),
+                    launch_heading: heading(),
+                    lost: None,
+                    soft_launch_done: false,
+                    assignment: None,
+                    terminal_claims: Vec::new(),
+                    config,
+                    profile,
+                    launch_fuel: fuel(),
+                    weave_phase: rng.uniform(0.0, TAU),
+                    dogleg_done: false,
+                    rng,
+                }
+            }
+
+            // our class profile with the launcher's overrides for this target
+            fn active_profile(&self) -> OrdnanceProfile {
+                self.assignment.as_ref().map_or(self.profile, |a| self.profile.with_overrides(a.overrides))
+            }
+
+            // midcourse aim point off to the side of the launcher's approach, None once
+            // we've turned in for good
+            fn dogleg(&mut self, target_position: Vec2, target_velocity: Vec2, profile: &OrdnanceProfile, terminal: bool) -> Option<Vec2> {
+                if self.dogleg_done {
+                    return None;
+                }
+                let approach = match self.assignment.as_ref() {
+                    Some(a) if profile.approach_offset != 0.0 && !terminal => a.approach,
+                    _ => {
+                        self.dogleg_done = terminal;
+                        return None;
+                    },
+                };
+                let waypoint = match dogleg_waypoint(target_position, position(), approach, profile.approach_offset, profile.terminal_range) {
+                    Some(waypoint) => waypoint,
+                    None => {
+                        trace!("dogleg done, turning in");
+                        self.dogleg_done = true;
+                        return None;
+                    },
+                };
+                // the detour can't eat the fuel the run in still needs
+                let budget = fuel() * DOGLEG_FUEL_SHARE;
+                if !intercept_feasible(waypoint - position(), target_velocity - velocity(), max_forward_acceleration(), budget) {
+                    trace!("dogleg unaffordable with fuel {}, coming straight in", fuel());
+                    self.dogleg_done = true;
+                    return None;
+                }
+                debug_line(LAYER_LEADS, position(), waypoint, 0x8080ff);
+                Some(waypoint)
+            }
+
+            fn available_acceleration(&self, terminal: bool) -> f64 {
+                self.active_profile().available_acceleration(terminal, fuel(), self.launch_fuel, max_forward_acceleration())
+            }
+
+            fn handle_radio(&mut self, message: Option<RadioMessage>) {
+                match message {
+                    Some(RadioMessage::ContactReport { position, velocity, class, age, sender }) => {
+                        self.radar.add_detection_point(Detection::Remote { position, velocity, class, age, sender });
+                    },
+                    Some(RadioMessage::TargetHandoff { position, velocity, salvo, bearing, overrides, approach, sender }) => {
+                        // the first handoff after spawn is ours, after that only follow our own
+                        // launcher's salvo slot
+                        let ours = self.assignment.as_ref().is_none_or(|a| a.salvo == salvo && a.sender == sender);
+                        if ours {
+                            // the approach axis is the one we launched on, later handoffs only
+                            // refresh the target
+                            let approach = self.assignment.as_ref().map_or(approach, |a| a.approach);
+                            self.assignment = Some(MissileAssignment { salvo, position, velocity, bearing, overrides, approach, sender, tick: current_tick() });
+                            // seeds a remote track before our radar has anything and steers it
+                            // until a plot of ours lands on it, after that reports only nudge it
+                            let detection = Detection::Remote { position, velocity, class: handoff_class(overrides), age: 0, sender };
+                            self.radar.add_detection_point(detection);
+                        }
+                    },
+                    Some(RadioMessage::TerminalOn { position }) => {
+                        self.terminal_claims.push((current_tick(), position));
+                    },
+                    _ => {},
+                }
+                self.terminal_claims.retain(|(tick, _)| current_tick() - tick < TERMINAL_CLAIM_TICKS);
+            }
+
+            // prefers the assigned target, falls back to the best scoring track nobody
+            // else is terminal on once the assignment can't be confirmed
+            fn select_target_id(&self) -> Option<u128> {
+                let claims = self.terminal_claims.clone();
+                let unclaimed = move |t: &RadarTrack| {
+                    t.engageable(ENGAGE_MIN_PLOTS) && claims.iter().all(|(_, p)| t.distance_from(*p) > TERMINAL_CLAIM_RADIUS)
+                };
+
+                if let Some(assignment) = self.assignment.as_ref() {
+                    let expected = assignment.predicted_position();
+                    let assigned = self.radar.get_closest_target_matching(expected, &|t: &RadarTrack| {
+                        t.engageable(ENGAGE_MIN_PLOTS) && t.distance_from(expected) < ASSIGNMENT_MATCH_RADIUS
+                    });
+                    if assigned.is_some() {
+                        return assigned;
+                    }
+                    if current_tick() - self.spawn_tick < SALVO_CONFIRM_TICKS {
+                        // give our own radar time to find the assignment before giving up on it
+                        return None;
+                    }
+                    trace!("salvo {} assignment dead, picking another track", assignment.salvo);
+                    return self.radar.select_target(&self.config.target_weights, &|t: &RadarTrack| {
+                        t.distance_from(expected) > ASSIGNMENT_MATCH_RADIUS && unclaimed(t)
+                    });
+                }
+
+                self.radar.select_target(&self.config.target_weights, &unclaimed)
+            }
+            // same ordering as the fighter, guide() senses first and reads copies
+            pub fn tick(&mut self) {
+                self.thrust.begin_tick();
+                self.guide();
+                self.controls.flush(&mut GameControls);
+                match self.target.as_ref() {
+                    Some(t) => self.radar.designate(t.id),
+                    None => self.radar.clear_designation(),
+                }
+                self.radar.point(self.thrust.last_command);
+                self.send_heartbeat();
+                self.radio.flush();
+            }
+
+            // tells the launcher we're still flying, the radio spaces them out and
+            // anything guidance queued goes first
+            fn send_heartbeat(&mut self) {
+                let assignment = match self.assignment.as_ref() {
+                    Some(assignment) => assignment,
+                    None => return,
+                };
+                let target = match self.target {
+                    Some(t) => t.position,
+                    None => assignment.predicted_position(),
+                };
+                let heartbeat = RadioMessage::MissileHeartbeat { position: position(), target, salvo: assignment.salvo };
+                self.radio.enqueue(heartbeat, RadioPriority::Low, RADIO_STATE_DEADLINE);
+            }
+
+            fn guide(&mut self) {
+                self.radar.sense();
+                let message = self.radio.poll();
+                self.handle_radio(message);
+                self.target = self.target.map(|t| self.radar.picture().refresh(t));
+                // a dropped target stays in the store as a ghost so reacquiring it keeps its id
+                if let Some(t) = self.target {
+                    self.radar.coast(t.id, LOST_CONTACT_TICKS);
+                }
+
+                if self.soft_launch() {
+                    return;
+                }
+
+                self.note_lost_target();
+                if self.pursue_lost_target() {
+                    return;
+                }
+
+                if self.radar.has_engageable_contacts() {
+                    self.radar.clear_sweep_bias();
+                    let candidate = self.select_target_id();
+                    if let Some(track) = self.radar.reselect(candidate, &self.config.target_weights, &|t: &RadarTrack| t.engageable(ENGAGE_MIN_PLOTS))
+                        .and_then(|id| self.radar.picture().get(id))
+                    {
+                        self.target = Some(track);
+                        self.radar.lock_designated();
+                    }
+                    let contact = match self.target {
+                        Some(t) => t,
+                        None => return,
+                    };
+                    // a weak track gets the wide beam until it firms up
+                    self.radar.request_beam(if contact.quality >= BEAM_NARROW_QUALITY { RadarBeam::Narrow } else { RadarBeam::Wide });
+                    let contact_distance: f64 = contact.distance_from(position_fixed());
+                    let contact_direction: Vec2 = contact.get_target_direction(position_fixed());
+                    let contact_velocity: Vec2 = contact.velocity;
+                    let contact_position: Vec2 = contact.position;
+                    let contact_future = contact_position + (contact_velocity / 60.0);
+
+                    let dv = contact_velocity - velocity();
+
+                    debug_line(LAYER_LEADS, position(), contact_position, 0xff0000);
+                    debug_line(LAYER_LEADS, contact_position, contact_position+dv*4.0, 0xffffff);
+
+                    trace!("velocity.length: {}",velocity().length());
+
+                    // break turns are handled by the acceleration term of augmented PN,
+                    // which comes in as the track firms up
+                    let profile = self.active_profile();
+                    let closing = contact.closing_speed_from(position(), velocity());
+                    let intercept = missile_intercept_point(position(), velocity(), contact_position, contact_velocity,
+                        max_forward_acceleration(), fuel());
+                    let phase = profile.phase((intercept - position()).length().min(contact_distance), closing, fuel(), self.launch_fuel);
+                    let terminal = phase == FlightPhase::Terminal;
+                    if terminal {
+                        // let trailing missiles know this one is spoken for
+                        self.radio.enqueue(RadioMessage::TerminalOn { position: contact_position }, RadioPriority::High, RADIO_STATE_DEADLINE);
+                    }
+                    let weight = apn_weight(self.config.apn_gain, contact.quality);
+                    trace!("apn weight: {}, target acceleration: {}", weight, contact.acceleration);
+
+                    // midcourse corrections go through the planner so the command is achievable
+                    let available = self.available_acceleration(terminal);
+                    // the weave only gets a share of what's available so PN keeps the rest
+                    let feasible = contact.intercept_feasible(position(), velocity(), available, fuel() * WEAVE_FEASIBLE_FUEL_SHARE);
+                    let weave = if profile.weave_allowed(contact_distance, fuel(), self.launch_fuel, feasible) {
+                        let cap = available * WEAVE_AVAILABLE_SHARE;
+                        profile.weave(current_tick() - self.spawn_tick, self.weave_phase, terminal).clamp(-cap, cap)
+                    } else {
+                        0.0
+                    };
+                    let (aim, desired) = match self.dogleg(contact_position, contact_velocity, &profile, terminal) {
+                        // the waypoint rides along with the target, its turns don't matter out there
+                        Some(waypoint) => (waypoint, seek(waypoint, contact_velocity, Vec2::zero(), self.config.nav_gain, 0.0, available, weave)),
+                        None => {
+                            // close in a break turn matters more than the estimate's smoothing
+                            let target_acceleration = if terminal {
+                                break_turn_acceleration(contact_velocity, contact.acceleration, contact.turn_rate, max_plausible_acceleration(contact.contact_class))
+                            } else {
+                                contact.acceleration
+                            };
+                            (contact_position, seek(contact_position, contact_velocity, target_acceleration, self.config.nav_gain, weight, available, weave))
+                        },
+                    };
+                    // coasting keeps the lateral corrections and drops the push down the line of sight
+                    let desired = if phase == FlightPhase::Coast {
+                        let los = (aim - position()).normalize();
+                        desired - los * desired.dot(los)
+                    } else {
+                        desired
+                    };
+                    // terminal keeps the nose on the velocity vector and lets the laterals
+                    // fly the PN demand, swinging the hull this late costs more than it buys
+                    self.thrust.apply_desired_acceleration(desired, !terminal, &mut self.controls);
+                    if terminal {
+                        self.controls.turn_to(velocity().angle());
+                    }
+                    debug_triangle(LAYER_LEADS, contact_future, profile.fuse_radius, 0xff0000);
+
+                    // a fast flyby can step over the fuse radius between ticks, so look at the closest approach too
+                    if cpa_fuse(contact_position - position(), contact_velocity - velocity(), profile.fuse_radius, profile.pass_radius, 1.0 / 60.0) {
+                        explode();
+                    }
+                    trace!("missile closing speed: {}", closing);
+                    hud!(HudLine::Guidance, "track {}, range {:.0}, closing {:.0}, {:?}, fuel {:.0}{}", contact.id, contact_distance, closing,
+                        phase, fuel(), if contact.source.has_local() { "" } else { ", on handoff" });
+                    if fuel() <= 0.0 && closing < 0.0 {
+                        // out of fuel and it's opening, nothing will bring it back
+                        explode();
+                    } else if !contact.intercept_feasible(position(), velocity(), max_forward_acceleration(), fuel()) {
+                        trace!("intercept not feasible with remaining fuel: {}", fuel());
+                    }
+                } else {
+                    self.radar.request_beam(RadarBeam::Wide);
+                    self.launch_boost();
+                }
+            }
+
+            // a missile fired mid-turn points the wrong way, so hold the main engine and
+            // swing the body onto the intercept bearing before boosting
+            // true while the sequence owns the controls
+            fn soft_launch(&mut self) -> bool {
+                if self.soft_launch_done {
+                    return false;
+                }
+                if current_tick() - self.spawn_tick >= SOFT_LAUNCH_TICKS {
+                    self.soft_launch_done = true;
+                    return false;
+                }
+
+                // own radar wins once it has the target, otherwise the launcher's bearing
+                let local = self.target.filter(|t| t.source.has_local());
+                let (bearing, range) = match (local, self.assignment.as_ref()) {
+                    (Some(t), _) => {
+                        let to_target = t.position - position();
+                        (to_target.angle(), to_target.length())
+                    },
+                    (None, Some(a)) => (a.bearing, (a.predicted_position() - position()).length()),
+                    // nothing to align with yet, wait for the handoff
+                    (None, None) => return false,
+                };
+
+                let error = angle_diff(heading(), bearing);
+                if error.abs() < SOFT_LAUNCH_ALIGNED || range < SOFT_LAUNCH_ABORT_RANGE {
+                    trace!("soft launch done, error {}, range {}", error, range);
+                    self.soft_launch_done = true;
+                    return false;
+                }
+
+                self.controls.torque(bang_bang_torque(error, angular_velocity(), max_angular_acceleration()));
+
+                // lateral thrusters only, bleed off the velocity across the bearing
+                let along = Vec2::new(1.0, 0.0).rotate(bearing);
+                let across = velocity() - along * velocity().dot(along);
+                let body = (-across * 2.0).rotate(-heading());
+                self.thrust.apply_desired_acceleration(Vec2::new(0.0, body.y).rotate(heading()), false, &mut self.controls);
+                debug_line(LAYER_LEADS, position(), position() + along * 500.0, 0xff8000);
+                true
+            }
+
+            // a missile can't afford a blind spiral, so remember where the target was
+            fn note_lost_target(&mut self) {
+                let dropped = self.target.is_some_and(|t| !self.radar.still_tracking(t.id));
+                if dropped {
+                    trace!("missile target track dropped, dead reckoning");
+                    self.lost = self.target.take().map(|t| LostContact::from_track(&t));
+                }
+            }
+
+            // keep guiding on the dead reckoned position with a narrow cone on it, false
+            // once there's nothing to recover
+            fn pursue_lost_target(&mut self) -> bool {
+                let lost = match self.lost.as_ref() {
+                    Some(lost) => lost,
+                    None => return false,
+                };
+
+                if let Some((id, track)) = lost.reacquire(&self.radar, &|_| false).and_then(|id| Some((id, self.radar.picture().get(id)?))) {
+                    trace!("missile reacquired lost target as track {}", id);
+                    self.target = Some(track);
+                    self.radar.designate(id);
+                    self.lost = None;
+                    self.radar.clear_sweep_bias();
+                    return false;
+                }
+
+                if lost.expired() {
+                    trace!("missile lost target not reacquired, searching");
+                    self.lost = None;
+                    self.radar.clear_sweep_bias();
+                    return false;
+                }
+
+                let predicted = lost.predicted_position();
+                let range = (predicted - position()).length();
+                self.radar.request_beam(RadarBeam::Narrow);
+                self.radar.bias_sweep((predicted - position()).angle(), lost.search_half_width(position()));
+                self.radar.bracket_sweep(range - lost.reacquire_radius(), range + lost.reacquire_radius());
+                lost.draw();
+                // a lost contact's acceleration is stale, plain PN on the dead reckoning
+                let desired = seek(predicted, lost.velocity, Vec2::new(0.0, 0.0), self.config.nav_gain, 0.0, self.available_acceleration(false), 0.0);
+                self.thrust.apply_desired_acceleration(desired, true, &mut self.controls);
+                true
+            }
+
+            // before our own radar has anything, fly the handoff's intercept at full thrust
+            // instead of coasting along whatever heading the launcher had
+            fn launch_boost(&mut self) {
+                let age = current_tick() - self.spawn_tick;
+                if let Some(assignment) = self.assignment.as_ref() {
+                    let target_position = assignment.predicted_position();
+                    let accel = self.available_acceleration(false);
+                    let intercept = missile_intercept_point(position(), velocity(), target_position, assignment.velocity, accel, fuel());
+                    let bearing = (intercept - position()).normalize();
+                    debug_line(LAYER_LEADS, position(), intercept, 0x00ff00);
+                    self.thrust.apply_desired_acceleration(bearing * accel, true, &mut self.controls);
+                } else if age >= MISSILE_HANDOFF_WAIT_TICKS && age < MISSILE_FORWARD_SEARCH_TICKS {
+                    // no handoff, search the hemisphere the launcher was facing first
+                    let half_width = self.radar.profile().search_sector_half_width;
+                    self.radar.bias_sweep(self.launch_heading, half_width);
+                } else if age >= MISSILE_FORWARD_SEARCH_TICKS {
+                    self.radar.clear_sweep_bias();
+                }
+            }
+        }
+    }
+
+    // frigate: stationary radar picket with flak point defense
+    mod frigate {
+        use super::*;
+
+        pub struct Frigate {
+            target: Option<TrackSnapshot>,
+            radar: Radar,
+            radio: Radio,
+
+            // point defense turrets
+            flak: FlakController,
+
+            // heavy forward gun, for ships
+            main_gun: MainGun,
+
+            // missiles for ships out past the main gun
+            missiles: MissileRack,
+        }
+
+        impl Frigate {
+            pub fn default_config() -> ShipConfig {
+                ShipConfig {
+                    guns: vec![
+                        // approximate mounts: the main gun in the bow, a turret either side amidships
+                        GunConfig { index: 0, projectile_speed: FRIGATE_MAIN_GUN_SPEED, hit_radius: 30.0, offset: vec2(40.0, 0.0), spread: 0.0, turret: false },
+                        GunConfig { index: 1, projectile_speed: BULLET_SPEED, hit_radius: 20.0, offset: vec2(0.0, 15.0), spread: 0.0, turret: true },
+                        GunConfig { index: 2, projectile_speed: BULLET_SPEED, hit_radius: 20.0, offset: vec2(0.0, -15.0), spread: 0.0, turret: true },
+                    ],
+                    radar: RadarProfile::for_class(Class::Frigate),
+                    close_range: 500.0,
+                    far_range: 1_000.0,
+                    envelopes: vec![
+                        // the main gun is for ships, ordnance is the flak's job
+                        (Weapon::Gun, Some(Class::Missile), EngagementEnvelope { min_range: 0.0, max_range: 0.0, standoff_min: 0.0, standoff_max: 0.0 }),
+                        (Weapon::Gun, Some(Class::Torpedo), EngagementEnvelope { min_range: 0.0, max_range: 0.0, standoff_min: 0.0, standoff_max: 0.0 }),
+                        (Weapon::Gun, None, EngagementEnvelope { min_range: 0.0, max_range: 4_000.0, standoff_min: 0.0, standoff_max: 0.0 }),
+                        // missiles pick up where the main gun's reach ends
+                        (Weapon::Missile, Some(Class::Missile), EngagementEnvelope { min_range: 0.0, max_range: 0.0, standoff_min: 0.0, standoff_max: 0.0 }),
+                        (Weapon::Missile, Some(Class::Torpedo), EngagementEnvelope { min_range: 0.0, max_range: 0.0, standoff_min: 0.0, standoff_max: 0.0 }),
+                        (Weapon::Missile, None, EngagementEnvelope { min_range: 4_000.0, max_range: 20_000.0, standoff_min: 0.0, standoff_max: 0.0 }),
+                    ],
+                    knife_fight_range: 0.0,
+                    nav_gain: 0.0,
+                    apn_gain: 0.0,
+                    combat_speed_min: 0.0,
+                    combat_speed_max: 0.0,
+                    flee_health_fraction: 0.0,
+                    target_weights: TargetWeights::for_class(Class::Frigate),
+                }
+            }
+
+            pub fn new(config: ShipConfig) -> Self {
+                Frigate {
+                    target: None,
+                    radar: Radar::new(config.radar, "frigate_radar"),
+                    radio: Radio::new(CHANNEL_SANCHEZ),
+                    flak: FlakController::new([1, 2].iter().filter_map(|i| config.gun(*i)).collect()),
+                    missiles: MissileRack::new(&config, vec![FRIGATE_MISSILE_LAUNCHER]),
+                    main_gun: MainGun::new(config),
+                }
+            }
+
+            pub fn tick(&mut self) {
+                // the frigate only turns in place, sensing and pointing can go together
+                self.radar.radar_loop();
+                trace!("frigate is alive!");
+                for index in self.flak.fire_control(&self.radar).unwrap_or_default() {
+                    fire(index);
+                }
+                self.main_gun.fire_control(&self.radar);
+                if let Some(launcher) = self.missiles.fire_control(&self.radar) {
+                    fire(launcher);
+                }
+
+                let handoff = self.missiles.handoff(&self.radar, self.radio.id);
+                beacon_or_handoff(&mut self.radio, handoff);
+            }
+        }
+
+        // lets escorts know where we are, sharing the ticks with the latest missile's
+        // handoff while there is one
+        pub fn beacon_or_handoff(radio: &mut Radio, handoff: Option<RadioMessage>) {
+            if current_tick() % 2 == 0 || handoff.is_none() {
+                radio.enqueue(RadioMessage::CapitalBeacon { position: position(), velocity: velocity() }, RadioPriority::Normal, RADIO_STATE_DEADLINE);
+            } else if let Some(handoff) = handoff {
+                radio.enqueue(handoff, RadioPriority::Normal, RADIO_STATE_DEADLINE);
+            }
+            radio.flush();
+        }
+
+        // launches at the closest ship inside the missile envelope, which starts where
+        // the main gun's ends. missiles spawn knowing nothing, so the newest salvo's
+        // target goes out as a handoff the same way a fighter's does
+        pub struct MissileRack {
+            config: ShipConfig,
+
+            // weapon indices, the first one loaded fires
+            launchers: Vec<usize>,
+            launched: u32,
+
+            // salvo slot, track id and approach bearing out from the track of the latest launch
+            last_launch: Option<(u32, u128, f64)>,
+        }
+
+        impl MissileRack {
+            pub fn new(config: &ShipConfig, launchers: Vec<usize>) -> Self {
+                MissileRack { config: config.clone(), launchers, launched: 0, last_launch: None }
+            }
+
+            // the launcher to fire this tick, the caller fires it through its own controls
+            pub fn fire_control(&mut self, radar: &Radar) -> Option<usize> {
+                let launcher = *self.launchers.iter().find(|i| reload_ticks(**i) == 0)?;
+                let config = &self.config;
+                let in_envelope = |t: &RadarTrack| {
+                    t.engageable(ENGAGE_MIN_PLOTS) && t.quality() >= QUALITY_MISSILE && config.envelope(Weapon::Missile, t.contact_class)
+                        .is_some_and(|e| e.check(t.distance_from(position())).is_ok())
+                };
+                let target = radar.get_closest_target_matching(position(), &in_envelope).and_then(|id| radar.picture().get(id))?;
+                hud!(HudLine::Weapons, "missile salvo {} at track {} from launcher {}", self.launched, target.id, launcher);
+                self.last_launch = Some((self.launched, target.id, (position() - target.position).angle()));
+                self.launched += 1;
+                Some(launcher)
+            }
+
+            // the latest launch's assignment, until its track is gone
+            pub fn handoff(&self, radar: &Radar, sender: u8) -> Option<RadioMessage> {
+                let (salvo, id, approach) = self.last_launch?;
+                let track = radar.picture().get(id)?;
+                let intercept = missile_intercept_point(position(), velocity(), track.position, track.velocity,
+                    MISSILE_BOOST_ACCELERATION, MISSILE_DELTA_V);
+                Some(RadioMessage::TargetHandoff {
+                    position: track.position,
+                    velocity: track.velocity,
+                    salvo,
+                    bearing: (intercept - position()).angle(),
+                    overrides: handoff_overrides(track.contact_class),
+                    approach,
+                    sender,
+                })
+            }
+        }
+
+        // the forward heavy gun: rounds are seconds in flight, so the hull is turned onto
+        // a propagated lead and only fires once the target's room to maneuver during
+        // the flight fits inside the blast
+        pub struct MainGun {
+            // None leaves the gun idle, a missing config entry shouldn't panic the ship
+            gun: Option<GunConfig>,
+            config: ShipConfig,
+        }
+
+        impl MainGun {
+            pub fn new(config: ShipConfig) -> Self {
+                MainGun { gun: config.gun(0), config }
+            }
+
+            pub fn fire_control(&self, radar: &Radar) {
+                let gun = match self.gun {
+                    Some(gun) => gun,
+                    None => return,
+                };
+                let config = &self.config;
+                let in_envelope = |t: &RadarTrack| {
+                    t.engageable(ENGAGE_MIN_PLOTS) && t.quality() >= QUALITY_GUN && config.envelope(Weapon::Gun, t.contact_class)
+                        .is_some_and(|e| e.check(t.distance_from(position())).is_ok())
+                };
+                let target = match radar.get_closest_target_matching(position(), &in_envelope).and_then(|id| radar.picture().get(id)) {
+                    Some(track) => track,
+                    None => return,
+                };
+                let solution = match intercept_for_gun(&gun, &target, &Shooter::own(Vec2::zero())) {
+                    Some(solution) => solution,
+                    None => return,
+                };
+                // the fighter's pd tuning overshoots badly on a hull this heavy, so turn
+                // bang-bang against what the hull can actually do
+                torque(bang_bang_torque(angle_diff(heading(), solution.aim), angular_velocity(), max_angular_acceleration()));
+                let muzzle = position() + gun.offset.rotate(heading());
+                debug_line(LAYER_LEADS, muzzle, muzzle + solution.lead, 0xff8000);
+                debug_square(LAYER_LEADS, muzzle + solution.lead, solution.dodge, 0xff8000);
+
+                if solution.dodge >= gun.hit_radius {
+                    hud!(HudLine::Weapons, "main gun holding: disc {:.0} m over {:.1}s", solution.dodge, solution.flight_time);
+                } else if solution.miss + solution.dodge < gun.hit_radius && reload_ticks(gun.index) == 0 {
+                    hud!(HudLine::Weapons, "main gun firing on track {}, disc {:.0} m", target.id, solution.dodge);
+                    fire(gun.index);
+                }
+            }
+        }
+
+        // timed flak fire against inbound missiles
+        // shells burst after fuse_time, so a turret fires when the shell's flight time to
+        // the intercept point matches the fuse and the burst lands in the missile's path
+        pub struct FlakController {
+            // guns that can be aimed with aim()
+            turrets: Vec<GunConfig>,
+
+            // seconds from firing until the shell bursts
+            fuse_time: f64,
+
+            // how far flight time may be from the fuse time and still fire, seconds
+            tolerance: f64,
+
+            // threats closer than this in time are shot at regardless of fuse timing
+            last_ditch_time: f64,
+
+            // seconds of turret time each inbound has had so far, by track id
+            fire_given: HashMap<u128, f64>,
+        }
+
+        // seconds left over once a threat has been shot down, negative means the guns
+        // are already late and it goes to the front of the queue
+        pub fn point_defense_slack(time_to_impact: f64, class: Class) -> f64 {
+            time_to_impact - estimated_time_to_kill(class)
+        }
+
+        // threats as (time to impact, seconds of fire they still need), least slack
+        // first: fire already given raises a threat's slack, so the guns swap between
+        // threats of similar urgency instead of finishing one off first
+        pub fn point_defense_order(threats: &[(f64, f64)]) -> Vec<usize> {
+            let slack = |i: usize| threats[i].0 - threats[i].1.max(0.0);
+            let mut order: Vec<usize> = (0..threats.len()).collect();
+            order.sort_by(|a, b| slack(*a).partial_cmp(&slack(*b)).unwrap_or(std::cmp::Ordering::Equal));
+            order
+        }
+
+        impl FlakController {
+            pub fn new(turrets: Vec<GunConfig>) -> Self {
+                FlakController {
+                    turrets,
+                    fuse_time: 1.0,
+                    tolerance: 0.05,
+                    last_ditch_time: 0.5,
+                    fire_given: HashMap::new(),
+                }
+            }
+
+            // inbound missiles and torpedoes with their time to impact, the one with the
+            // least time to spare first
+            fn inbound_threats(&self, radar: &Radar) -> Vec<(f64, TrackSnapshot)> {
+                let mut threats: Vec<(f64, TrackSnapshot)> = Vec::new();
+                for t in radar.picture().iter() {
+                    match t.contact_class {
+                        Class::Missile | Class::Torpedo if t.engageable(ENGAGE_MIN_PLOTS) => {},
+                        _ => continue,
+                    }
+                    let closing = t.closing_speed_from(position(), velocity());
+                    if closing <= 0.0 {
+                        continue;
+                    }
+                    threats.push((t.distance_from(position()) / closing, *t));
+                }
+                threats.sort_by(|a, b| {
+                    point_defense_slack(a.0, a.1.contact_class).partial_cmp(&point_defense_slack(b.0, b.1.contact_class)).unwrap_or(std::cmp::Ordering::Equal)
+                });
+                threats
+            }
+
+            // turrets to fire this tick, the caller fires them through its own controls.
+            // None with nothing inbound, the turrets are free for other work
+            pub fn fire_control(&mut self, radar: &Radar) -> Option<Vec<usize>> {
+                let threats = self.inbound_threats(radar);
+                self.fire_given.retain(|id, _| threats.iter().any(|(_, t)| t.id == *id));
+                if threats.is_empty() {
+                    return None;
+                }
+                let needs: Vec<(f64, f64)> = threats.iter()
+                    .map(|(time_to_impact, t)| (*time_to_impact, estimated_time_to_kill(t.contact_class) - self.fire_given.get(&t.id).copied().unwrap_or(0.0)))
+                    .collect();
+                let order = point_defense_order(&needs);
+
+                let shooter = Shooter::own(Vec2::new(0.0, 0.0));
+                let mut fired = Vec::new();
+                for (k, turret) in self.turrets.iter().enumerate() {
+                    // turrets spread over the threats in slack order, each tick of fire
+                    // counted against the threat it went to
+                    let (time_to_impact, threat) = &threats[order[k % order.len()]];
+                    let time_to_impact = *time_to_impact;
+                    *self.fire_given.entry(threat.id).or_insert(0.0) += 1.0 / 60.0;
+                    let solution = match intercept_for_gun(turret, threat, &shooter) {
+                        Some(solution) => solution,
+                        None => continue,
+                    };
+                    let flight_time = solution.flight_time;
+                    aim(turret.index, solution.aim);
+                    let muzzle = position() + turret.offset.rotate(heading());
+                    debug_line(LAYER_LEADS, muzzle, muzzle + solution.lead, 0xffff00);
+
+                    let fuse_matched = (flight_time - self.fuse_time).abs() < self.tolerance;
+                    let last_ditch = time_to_impact < self.last_ditch_time;
+                    trace!("flak turret {}: flight time {}, time to impact {}", turret.index, flight_time, time_to_impact);
+                    if (fuse_matched || last_ditch) && reload_ticks(turret.index) == 0 {
+                        hud!(HudLine::Weapons, "flak turret {} firing: {}", turret.index, if fuse_matched { "fuse matched" } else { "last ditch" });
+                        fired.push(turret.index);
+                    }
+                }
+                Some(fired)
+            }
+        }
+    }
+
+    // cruiser: flak on inbound ordnance, otherwise turrets split over the ships in their arcs, missiles from the rack
+    mod cruiser {
+        use super::*;
+
+        // a turret and the arc it can reach, relative to the hull's heading
+        #[derive(Debug, Clone, Copy)]
+        pub struct TurretMount {
+            pub gun: GunConfig,
+            pub arc_center: f64,
+            pub arc_half_width: f64,
+        }
+
+        impl TurretMount {
+            pub fn covers(&self, hull_heading: f64, bearing: f64) -> bool {
+                angle_diff(hull_heading + self.arc_center, bearing).abs() <= self.arc_half_width
+            }
+        }
+
+        // index into tracks for each mount: the closest track in its arc that no other
+        // turret has taken yet, or the closest in its arc when they've all been taken,
+        // so a lone track gets every turret that can reach it
+        pub fn assign_turrets(mounts: &[TurretMount], hull_heading: f64, own_position: Vec2, tracks: &[TrackSnapshot]) -> Vec<Option<usize>> {
+            let mut by_range: Vec<usize> = (0..tracks.len()).collect();
+            by_range.sort_by(|a, b| {
+                tracks[*a].distance_from(own_position).partial_cmp(&tracks[*b].distance_from(own_position)).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            let mut taken = vec![false; tracks.len()];
+            mounts.iter()
+                .map(|mount| {
+                    let reachable: Vec<usize> = by_range.iter().copied()
+                        .filter(|k| mount.covers(hull_heading, (tracks[*k].position - own_position).angle()))
+                        .collect();
+                    let pick = reachable.iter().copied().find(|k| !taken[*k]).or_else(|| reachable.first().copied());
+                    if let Some(k) = pick {
+                        taken[k] = true;
+                    }
+                    pick
+                })
+                .collect()
+        }
+
+        // holds station with the hull toward the nearest ship so the launchers face it,
+        // while the turrets put flak on inbound ordnance or each works its own ship
+        pub struct Cruiser {
+            radar: Radar,
+            radio: Radio,
+            thrust: ThrustPlanner,
+            controls: ControlIntents,
+            turrets: Vec<TurretMount>,
+            flak: FlakController,
+            missiles: MissileRack,
+            config: ShipConfig,
+        }
+
+        impl Cruiser {
+            pub fn default_config() -> ShipConfig {
+                ShipConfig {
+                    guns: vec![
+                        // approximate mount, one turret on the centerline
+                        GunConfig { index: 0, projectile_speed: BULLET_SPEED, hit_radius: 30.0, offset: vec2(0.0, 0.0), spread: 0.0, turret: true },
+                    ],
+                    radar: RadarProfile::for_class(Class::Cruiser),
+                    close_range: 500.0,
+                    far_range: 1_000.0,
+                    envelopes: vec![
+                        (Weapon::Gun, None, EngagementEnvelope { min_range: 0.0, max_range: 5_000.0, standoff_min: 0.0, standoff_max: 0.0 }),
+                        (Weapon::Missile, Some(Class::Missile), EngagementEnvelope { min_range: 0.0, max_range: 0.0, standoff_min: 0.0, standoff_max: 0.0 }),
+                        (Weapon::Missile, Some(Class::Torpedo), EngagementEnvelope { min_range: 0.0, max_range: 0.0, standoff_min: 0.0, standoff_max: 0.0 }),
+                        (Weapon::Missile, None, EngagementEnvelope { min_range: 5_000.0, max_range: 20_000.0, standoff_min: 0.0, standoff_max: 0.0 }),
+                    ],
+                    knife_fight_range: 0.0,
+                    nav_gain: 0.0,
+                    apn_gain: 0.0,
+                    combat_speed_min: 0.0,
+                    combat_speed_max: 0.0,
+                    flee_health_fraction: 0.0,
+                    target_weights: TargetWeights::for_class(Class::Cruiser),
+                }
+            }
+
+            pub fn new(config: ShipConfig) -> Self {
+                Cruiser {
+                    radar: Radar::new(config.radar, "cruiser_radar"),
+                    radio: Radio::new(CHANNEL_SANCHEZ),
+                    thrust: ThrustPlanner::new(true),
+                    controls: ControlIntents::default(),
+                    // every turret listed in the config, covering all round until the mounts' arcs are known
+                    turrets: config.guns.iter().filter(|g| g.turret).map(|gun| TurretMount { gun: *gun, arc_center: 0.0, arc_half_width: PI }).collect(),
+                    flak: FlakController::new(config.guns.iter().filter(|g| g.turret).copied().collect()),
+                    missiles: MissileRack::new(&config, CRUISER_MISSILE_LAUNCHERS.to_vec()),
+                    config,
+                }
+            }
+
+            pub fn tick(&mut self) {
+                self.thrust.begin_tick();
+                self.radar.sense();
+
+                self.thrust.apply_desired_acceleration(-velocity(), false, &mut self.controls);
+                let nav_heading = self.navigation_heading();
+                self.controls.torque(bang_bang_torque(angle_diff(heading(), nav_heading), angular_velocity(), max_angular_acceleration()));
+                // a salvo inbound takes every turret on the flak timing
+                match self.flak.fire_control(&self.radar) {
+                    Some(fired) => fired.into_iter().for_each(|index| self.controls.fire(index)),
+                    None => self.aim_turrets(),
+                }
+                if let Some(launcher) = self.missiles.fire_control(&self.radar) {
+                    self.controls.fire(launcher);
+                }
+
+                let handoff = self.missiles.handoff(&self.radar, self.radio.id);
+                beacon_or_handoff(&mut self.radio, handoff);
+
+                self.controls.flush(&mut GameControls);
+                let nearest = self.radar.get_closest_target_matching(position(), &|t: &RadarTrack| t.engageable(ENGAGE_MIN_PLOTS));
+                match nearest {
+                    Some(id) => self.radar.designate(id),
+                    None => self.radar.clear_designation(),
+                }
+                self.radar.point(self.thrust.last_command);
+            }
+
+            // toward the nearest ship, ordnance is left to the turrets
+            fn navigation_heading(&self) -> f64 {
+                let ship = |t: &RadarTrack| t.engageable(ENGAGE_MIN_PLOTS) && !matches!(t.contact_class, Class::Missile | Class::Torpedo);
+                match self.radar.get_closest_target_matching(position(), &ship).and_then(|id| self.radar.picture().get(id)) {
+                    Some(track) => (track.position - position()).angle(),
+                    None => heading(),
+                }
+            }
+
+            fn aim_turrets(&mut self) {
+                let config = &self.config;
+                let tracks: Vec<TrackSnapshot> = self.radar.picture().iter().copied()
+                    .filter(|t| !t.friendly && !t.probably_dead && t.source.has_local() && t.quality >= QUALITY_GUN)
+                    .filter(|t| !matches!(t.contact_class, Class::Missile | Class::Torpedo))
+                    .filter(|t| config.envelope(Weapon::Gun, t.contact_class).is_some_and(|e| e.check(t.distance_from(position())).is_ok()))
+                    .collect();
+                if tracks.is_empty() {
+                    return;
+                }
+                let shooter = Shooter::own(self.thrust.last_command);
+                let assignments = assign_turrets(&self.turrets, heading(), position(), &tracks);
+                for (mount, assigned) in self.turrets.iter().zip(assignments) {
+                    let track = match assigned {
+                        Some(k) => &tracks[k],
+                        None => continue,
+                    };
+                    let solution = match intercept_for_gun(&mount.gun, track, &shooter) {
+                        Some(solution) => solution,
+                        None => continue,
+                    };
+                    aim(mount.gun.index, solution.aim);
+                    let muzzle = position() + mount.gun.offset.rotate(heading());
+                    debug_line(LAYER_LEADS, muzzle, muzzle + solution.lead, 0xffff00);
+                    if solution.miss < mount.gun.hit_radius && reload_ticks(mount.gun.index) == 0 {
+                        hud!(HudLine::Weapons, "turret {} firing on track {}", mount.gun.index, track.id);
+                        self.controls.fire(mount.gun.index);
+                    }
+                }
+            }
+        }
+    }
+
+    // stand-in for classes without a real implementation
+    mod fallback {
+        use super::*;
+
+        // stand-in for classes without a real implementation: holds position, sweeps
+        // the radar and fires the gun at anything that crosses the nose
+        pub struct SafeFallback {
+            class: Class,
+            radar: Radar,
+            thrust: ThrustPlanner,
+            controls: ControlIntents,
+            config: ShipConfig,
+        }
+
+        impl SafeFallback {
+            pub fn default_config() -> ShipConfig {
+                ShipConfig {
+                    guns: vec![GunConfig { index: 0, projectile_speed: BULLET_SPEED, hit_radius: GUN_MISS_TOLERANCE, offset: vec2(FIGHTER_GUN_OFFSET, 0.0), spread: 0.0, turret: false }],
+                    radar: RadarProfile::for_class(class()),
+                    close_range: 500.0,
+                    far_range: 1_000.0,
+                    envelopes: vec![
+                        (Weapon::Gun, None, EngagementEnvelope { min_range: 0.0, max_range: 2_000.0, standoff_min: 500.0, standoff_max: 1_200.0 }),
+                    ],
+                    knife_fight_range: 0.0,
+                    nav_gain: 0.0,
+                    apn_gain: 0.0,
+                    combat_speed_min: 0.0,
+                    combat_speed_max: 0.0,
+                    flee_health_fraction: 0.0,
+                    target_weights: TargetWeights::for_class(class()),
+                }
+            }
+
+            pub fn new(class: Class, config: ShipConfig) -> Self {
+                SafeFallback {
+                    class,
+                    radar: Radar::new(config.radar, "fallback_radar"),
+                    thrust: ThrustPlanner::new(true),
+                    controls: ControlIntents::default(),
+                    config,
+                }
+            }
+
+            pub fn tick(&mut self) {
+                hud!(HudLine::Nav, "fallback ship for {:?}, needs a real implementation", self.class);
+                self.thrust.begin_tick();
+                self.radar.sense();
+
+                // kill our own drift, staying put is the one thing that can't go wrong
+                self.thrust.apply_desired_acceleration(-velocity(), false, &mut self.controls);
+                self.fire_on_crossing();
+
+                self.controls.flush(&mut GameControls);
+                self.radar.point(self.thrust.last_command);
+            }
+
+            // no turning onto targets, the gun only goes when a lead passes the nose
+            fn fire_on_crossing(&mut self) {
+                let engageable = |t: &RadarTrack| t.engageable(ENGAGE_MIN_PLOTS) && t.quality() >= QUALITY_GUN;
+                let target = match self.radar.get_closest_target_matching(position_fixed(), &engageable).and_then(|id| self.radar.picture().get(id)) {
+                    Some(track) => track,
+                    None => return,
+                };
+                let in_envelope = self.config.envelope(Weapon::Gun, target.contact_class)
+                    .is_some_and(|e| e.check(target.distance_from(position_fixed())).is_ok());
+                if !in_envelope {
+                    return;
+                }
+                let solution = match self.config.gun(0).and_then(|gun| intercept_for_gun(&gun, &target, &Shooter::own(self.thrust.last_command))) {
+                    Some(solution) => solution,
+                    None => return,
+                };
+                let ahead = angle_diff(heading(), solution.aim).abs() < PI / 2.0;
+                if ahead && solution.miss < GUN_MISS_TOLERANCE {
+                    hud!(HudLine::Weapons, "fallback gun firing on track {}", target.id);
+                    self.controls.fire(0);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "{} not within {} of {}", actual, tolerance, expected);
+    }
+
+    #[test]
+    fn gate_ellipse_follows_the_velocity() {
+        let center = vec2(100.0, -50.0);
+        let mut gate = RadarTrackGate::new(center, 200.0);
+        // parked, a circle of width / 2
+        assert_eq!(gate.semi_major(), gate.semi_minor());
+        assert!(gate.point_in_gate(center + vec2(0.0, 100.0)));
+        assert!(!gate.point_in_gate(center + vec2(71.0, 71.0)));
+
+        for heading in [0.0, PI / 6.0, PI / 2.0, 2.0, PI, -PI / 3.0] {
+            let along = Vec2::new(1.0, 0.0).rotate(heading);
+            let across = along.rotate(PI / 2.0);
+            // 600 m/s coasting a second stretches it 60 m along track only
+            gate.update_axis(along * 600.0, 60);
+            assert_close(gate.semi_major(), 160.0, 1e-9);
+            assert_close(gate.semi_minor(), 100.0, 1e-9);
+
+            for (axis, half) in [(along, 160.0), (-along, 160.0), (across, 100.0), (-across, 100.0)] {
+                assert!(gate.point_in_gate(center + axis * half), "edge at {} m, heading {}", half, heading);
+                assert!(gate.point_in_gate(center + axis * half * 0.99));
+                assert!(!gate.point_in_gate(center + axis * half * 1.01), "past {} m, heading {}", half, heading);
+            }
+            // a point on the diagonal that a square would hold
+            assert!(!gate.point_in_gate(center + along * 150.0 + across * 90.0));
+        }
+    }
+
+    #[test]
+    fn intercept_point_on_a_stationary_target_is_the_target() {
+        let target = vec2(3000.0, -4000.0);
+        assert_eq!(intercept_point(Vec2::zero(), Vec2::zero(), target, Vec2::zero(), BULLET_SPEED), Some(target));
+        assert_close(time_to_intercept(Vec2::zero(), Vec2::zero(), target, Vec2::zero(), BULLET_SPEED).unwrap(), 5.0, 1e-9);
+
+        // our own drift carries the round, so a target sliding along with us is stationary too
+        let drift = vec2(150.0, 80.0);
+        assert_close(time_to_intercept(Vec2::zero(), drift, target, drift, BULLET_SPEED).unwrap(), 5.0, 1e-9);
+    }
+
+    #[test]
+    fn round_pass_measures_the_closest_the_round_gets() {
+        // dead on a parked target 1 km out
+        let pass = round_pass(Vec2::zero(), Vec2::zero(), 0.0, BULLET_SPEED, vec2(1_000.0, 0.0), Vec2::zero(), 2.0);
+        assert_close(pass.miss, 0.0, 1e-9);
+        assert_close(pass.time, 1.0, 1e-9);
+        assert_close(pass.impact.x, 1_000.0, 1e-9);
+
+        // 0.1 rad off at 1 km is the ~100 m miss the old heading gate let through
+        let pass = round_pass(Vec2::zero(), Vec2::zero(), 0.1, BULLET_SPEED, vec2(1_000.0, 0.0), Vec2::zero(), 2.0);
+        assert_close(pass.miss, 1_000.0 * 0.1f64.sin(), 1e-6);
+        assert!(pass.miss > hull_radius(Class::Fighter));
+
+        // a target crossing at 100 m/s is met when aimed 100 m ahead of it, as long as
+        // our own sideways drift is taken out
+        let lead = vec2(1_000.0, 100.0);
+        let pass = round_pass(Vec2::zero(), Vec2::zero(), lead.angle(), BULLET_SPEED, vec2(1_000.0, 0.0), vec2(0.0, 100.0), 2.0);
+        assert!(pass.miss < hull_radius(Class::Fighter));
+        let drifting = round_pass(Vec2::zero(), vec2(0.0, 100.0), lead.angle(), BULLET_SPEED, vec2(1_000.0, 0.0), vec2(0.0, 100.0), 2.0);
+        assert!(drifting.miss > 50.0);
+
+        // an opening target is closest right away, and nothing counts past the horizon
+        let pass = round_pass(Vec2::zero(), Vec2::zero(), PI, BULLET_SPEED, vec2(1_000.0, 0.0), Vec2::zero(), 2.0);
+        assert_close(pass.time, 0.0, 1e-9);
+        let short = round_pass(Vec2::zero(), Vec2::zero(), 0.0, BULLET_SPEED, vec2(3_000.0, 0.0), Vec2::zero(), 2.0);
+        assert_close(short.miss, 1_000.0, 1e-9);
+    }
+
+    #[test]
+    fn strafing_shooter_leads_in_its_own_frame() {
+        // strafing across at 250 m/s past a target crossing the other way
+        let (shooter_velocity, target, target_velocity) = (vec2(0.0, 250.0), vec2(1_500.0, 0.0), vec2(0.0, -200.0));
+        let lead = intercept_point(Vec2::zero(), shooter_velocity, target, target_velocity, BULLET_SPEED).unwrap();
+        let t = time_to_intercept(Vec2::zero(), shooter_velocity, target, target_velocity, BULLET_SPEED).unwrap();
+
+        // the round goes out along the lead at muzzle speed plus our own velocity and
+        // lands where the target is by then
+        let round = (shooter_velocity + lead.normalize() * BULLET_SPEED) * t;
+        assert_close((round - (target + target_velocity * t)).length(), 0.0, 1e-6);
+
+        // leaving our velocity out aims well behind once the round inherits it
+        let naive = intercept_point(Vec2::zero(), Vec2::zero(), target, target_velocity, BULLET_SPEED).unwrap();
+        let naive_t = naive.length() / BULLET_SPEED;
+        let naive_round = (shooter_velocity + naive.normalize() * BULLET_SPEED) * naive_t;
+        assert!((naive_round - (target + target_velocity * naive_t)).length() > 300.0);
+    }
+
+    #[test]
+    fn intercept_point_hits_crossing_target() {
+        let shooter = vec2(0.0, 0.0);
+        let target = vec2(2000.0, 0.0);
+        let target_velocity = vec2(0.0, 300.0);
+        let lead = intercept_point(shooter, Vec2::zero(), target, target_velocity, BULLET_SPEED).unwrap();
+
+        // bullet and target reach the lead point at the same time
+        let bullet_time = (lead - shooter).length() / BULLET_SPEED;
+        let target_time = (lead - target).length() / target_velocity.length();
+        assert_close(bullet_time, target_time, 1e-9);
+        assert!(lead.y > 0.0);
+    }
+
+    #[test]
+    fn lead_is_none_when_target_outruns_projectile() {
+        assert!(intercept_point(Vec2::zero(), Vec2::zero(), vec2(1000.0, 0.0), vec2(BULLET_SPEED * 2.0, 0.0), BULLET_SPEED).is_none());
+        // exactly as fast: only one coming at us is ever met, halfway in
+        assert!(time_to_intercept(Vec2::zero(), Vec2::zero(), vec2(1000.0, 0.0), vec2(BULLET_SPEED, 0.0), BULLET_SPEED).is_none());
+        assert_close(time_to_intercept(Vec2::zero(), Vec2::zero(), vec2(1000.0, 0.0), vec2(-BULLET_SPEED, 0.0), BULLET_SPEED).unwrap(), 0.5, 1e-9);
+    }
+
+    #[test]
+    fn average_speed_accounts_for_burn_and_coast() {
+        // 0 to 300 m/s at 300 m/s/s covers 150 m in 1 s
+        assert_close(achievable_average_speed(150.0, 0.0, 300.0, 1000.0), 150.0, 1e-6);
+        // burn out after 1 s at 300 m/s, then coast the other 300 m for 1 s
+        assert_close(achievable_average_speed(450.0, 0.0, 300.0, 300.0), 225.0, 1e-6);
+        // no thrust left, the current speed is all there is
+        assert_close(achievable_average_speed(1000.0, 200.0, 0.0, 0.0), 200.0, 1e-6);
+    }
+
+    #[test]
+    fn envelope_lookup_prefers_exact_class() {
+        let config = Fighter::default_config();
+        let gun_vs_fighter = config.envelope(Weapon::Gun, Class::Fighter).unwrap();
+        assert_close(gun_vs_fighter.max_range, 1_500.0, 0.0);
+        let gun_vs_cruiser = config.envelope(Weapon::Gun, Class::Cruiser).unwrap();
+        assert_close(gun_vs_cruiser.max_range, 2_000.0, 0.0);
+        assert_eq!(gun_vs_fighter.check(2_000.0), Err(EnvelopeLimit::MaxRange(1_500.0)));
+
+        let missile_vs_fighter = config.envelope(Weapon::Missile, Class::Fighter).unwrap();
+        assert_eq!(missile_vs_fighter.check(100.0), Err(EnvelopeLimit::MinRange(500.0)));
+        assert!(Missile::default_config().envelope(Weapon::Gun, Class::Fighter).is_none());
+    }
+
+    #[test]
+    fn lock_bracket_grows_with_staleness_and_clamps_width() {
+        let fresh = lock_bracket(5_000.0, 300.0, 0, 50.0);
+        assert_close(fresh.min_distance, 4_900.0, 1e-9);
+        assert_close(fresh.max_distance, 5_100.0, 1e-9);
+
+        // a fast closer half a second after its plot can be 150 m nearer
+        let stale = lock_bracket(5_000.0, 300.0, 30, 50.0);
+        assert_close(stale.min_distance, 4_750.0, 1e-9);
+        assert_close(stale.max_distance, 5_250.0, 1e-9);
+
+        assert_close(lock_bracket(100.0, 0.0, 0, 50.0).width, LOCK_MAX_WIDTH, 1e-12);
+        assert_close(lock_bracket(100_000.0, 0.0, 0, 50.0).width, LOCK_MIN_WIDTH, 1e-12);
+    }
+
+    #[test]
+    fn predicted_lead_accounts_for_own_acceleration() {
+        let target = TargetMotion { position: vec2(1_000.0, 0.0), velocity: Vec2::zero(), acceleration: Vec2::zero() };
+        let shooter = Shooter { position: Vec2::zero(), velocity: Vec2::zero(), acceleration: Vec2::zero(), heading: 0.0 };
+        let still = predicted_lead(&shooter, &target, BULLET_SPEED, 1.0 / 60.0);
+        assert_close(still.x, 1_000.0, 1e-9);
+        assert_close(still.y, 0.0, 1e-9);
+
+        // sliding sideways under thrust drags the relative lead the other way
+        let sliding = predicted_lead(&Shooter { velocity: vec2(0.0, 100.0), acceleration: vec2(0.0, 60.0), ..shooter }, &target, BULLET_SPEED, 1.0 / 60.0);
+        assert!(sliding.y < 0.0);
+
+        assert_close(predicted_miss_distance(0.0, still), 0.0, 1e-9);
+        assert_close(predicted_miss_distance(0.01, still), 1_000.0 * 0.01f64.sin(), 1e-9);
+        // pointed straight away isn't a solution, however small the sine
+        assert_close(predicted_miss_distance(PI, still), 1_000.0, 1e-9);
+        assert_close(predicted_miss_distance(PI - 0.01, still), 1_000.0, 1e-9);
+    }
+
+    #[test]
+    fn thrust_limits_and_when_to_turn() {
+        let (forward, backward, lateral) = (max_forward_acceleration(), max_backward_acceleration(), max_lateral_acceleration());
+        // each axis against its own limit, direction kept
+        let ahead = clamp_to_thrust_limits(vec2(2.0 * forward, 0.0));
+        assert_close(ahead.x, forward, 1e-9);
+        assert_close(clamp_to_thrust_limits(vec2(-2.0 * forward, 0.0)).x, -backward, 1e-9);
+        let diagonal = clamp_to_thrust_limits(vec2(forward, 2.0 * lateral));
+        assert_close(diagonal.y, lateral, 1e-9);
+        assert_close(diagonal.x, forward / 2.0, 1e-9);
+
+        // sideways request the laterals can't cover: turn. one they can: don't
+        let desired = vec2(0.0, forward);
+        assert!(rotation_pays_off(desired, clamp_to_thrust_limits(desired), forward, THRUST_ROTATION_THRESHOLD));
+        let desired = vec2(0.0, lateral);
+        assert!(!rotation_pays_off(desired, clamp_to_thrust_limits(desired), forward, THRUST_ROTATION_THRESHOLD));
+        // already nose on and saturated, turning gains nothing
+        let desired = vec2(3.0 * forward, 0.0);
+        assert!(!rotation_pays_off(desired, clamp_to_thrust_limits(desired), forward, THRUST_ROTATION_THRESHOLD));
+    }
+
+    #[test]
+    fn bang_bang_brakes_in_time() {
+        // at rest, push toward the error
+        assert_close(bang_bang_torque(1.0, 0.0, 2.0), 2.0, 1e-12);
+        assert_close(bang_bang_torque(-1.0, 0.0, 2.0), -2.0, 1e-12);
+        // 2 rad/s at 2 rad/s/s stops in 1 rad, so with 0.5 rad left it has to brake
+        assert_close(bang_bang_torque(0.5, 2.0, 2.0), -2.0, 1e-12);
+        // spinning the wrong way, keep pushing toward the error
+        assert_close(bang_bang_torque(0.5, -2.0, 2.0), 2.0, 1e-12);
+    }
+
+    #[test]
+    fn rotator_settles_without_oscillating() {
+        let accel = 2.0 * PI;
+        let dt = 1.0 / 60.0;
+        // a plain turn, one across the +/-PI seam, and one starting off spinning the wrong way
+        for (start, target, spin) in [(0.0, 2.0, 0.0), (3.0, -3.0, 0.0), (0.0, 1.0, -3.0)] {
+            let mut rotator = Rotator::new();
+            let (mut angle, mut spin): (f64, f64) = (start, spin);
+            rotator.command(angle_diff(angle, target), spin, accel);
+            let estimate = rotator.estimated_ticks_to_angle;
+            assert_eq!(estimate, (turn_time(angle_diff(angle, target), spin, accel) * 60.0).ceil() as u32);
+            let mut settled = None;
+            for tick in 0..600 {
+                let torque = rotator.command(angle_diff(angle, target), spin, accel);
+                spin += torque * dt;
+                angle += spin * dt;
+                if angle_diff(angle, target).abs() < 0.01 && spin.abs() < 0.05 {
+                    settled.get_or_insert(tick);
+                } else {
+                    assert!(settled.is_none(), "left the heading again at tick {}", tick);
+                }
+            }
+            let settled = settled.expect("never settled");
+            assert!(settled <= estimate + 30, "settled at {} against an estimate of {}", settled, estimate);
+            assert_eq!(rotator.phase, RotationPhase::Hold);
+        }
+        // 2 rad from rest at 2 rad/s/s: 1 s up, 1 s down
+        assert_close(turn_time(2.0, 0.0, 2.0), 2.0, 1e-12);
+        // 2 rad/s the wrong way: 1 s to stop 1 rad past, then 3 rad from rest
+        assert_close(turn_time(2.0, -2.0, 2.0), 1.0 + 2.0 * 1.5f64.sqrt(), 1e-12);
+    }
+
+    #[test]
+    fn track_quality_orders_fresh_over_stale() {
+        let fresh = track_quality(0, 6, 0.0, 50.0, true);
+        assert_close(fresh, 1.0, 1e-12);
+        assert!(track_quality(30, 6, 0.0, 50.0, true) < fresh);
+        assert!(track_quality(0, 1, 0.0, 50.0, true) < fresh);
+        assert!(track_quality(0, 6, 50.0, 50.0, true) < fresh);
+        assert!(track_quality(0, 6, 0.0, 50.0, false) < fresh);
+        assert!(track_quality(0, 6, 0.0, 50.0, true) >= QUALITY_GUN);
+    }
+
+    #[test]
+    fn cpa_fuse_catches_fast_flyby() {
+        // 1000 m/s past a target 10 m off axis, neither tick sample lands inside 12 m
+        let rel_p = vec2(8.0, 10.0);
+        let rel_v = vec2(-1000.0, 0.0);
+        let dt = 1.0 / 60.0;
+        assert!(rel_p.length() > 12.0);
+        assert!((rel_p + rel_v * dt).length() > 12.0);
+        assert!(cpa_fuse(rel_p, rel_v, 12.0, 12.0, dt));
+
+        // same pass wide of the fuse radius
+        assert!(!cpa_fuse(vec2(8.0, 20.0), rel_v, 12.0, 12.0, dt));
+        // closest approach is still several ticks out
+        assert!(!cpa_fuse(vec2(100.0, 10.0), rel_v, 12.0, 12.0, dt));
+
+        // 2000 m/s head on, 10 m off axis: both tick samples sit outside a 15 m shell
+        let rel_v = vec2(-2000.0, 0.0);
+        let rel_p = vec2(20.0, 10.0);
+        assert!(rel_p.length() > 15.0 && (rel_p + rel_v * dt).length() > 15.0);
+        assert!(cpa_fuse(rel_p, rel_v, 15.0, 40.0, dt));
+
+        // a pass 25 m wide is only seen once it's behind us, the pass radius takes it
+        let behind = vec2(-13.0, 25.0);
+        assert!(!cpa_fuse(behind + rel_v * -dt, rel_v, 15.0, 40.0, dt));
+        assert!(cpa_fuse(behind, rel_v, 15.0, 40.0, dt));
+        assert!(!cpa_fuse(behind, rel_v, 15.0, 20.0, dt));
+        // long gone
+        assert!(!cpa_fuse(vec2(-200.0, 25.0), rel_v, 15.0, 40.0, dt));
+    }
+
+    #[test]
+    fn turn_takes_short_way_across_wrap() {
+        let controller = TurnController::new(10.0, 100.0);
+        assert_close(controller.command(PI - 0.05, -PI + 0.05, 0.0), 1.0, 1e-9);
+        assert_close(controller.command(-PI + 0.05, PI - 0.05, 0.0), -1.0, 1e-9);
+        // on the heading only the damping acts, and never past what the hull can do
+        assert_close(TurnController::new(4.0, 100.0).command(0.0, 0.0, 1.0), -4.0, 1e-9);
+        assert_close(TurnController::for_hull(2.0).command(0.0, PI / 2.0, 0.0), 2.0, 1e-9);
+        assert_close(TurnController::precise(2.0).command(0.0, -0.01, 0.0), -1.0, 1e-9);
+    }
+
+    #[test]
+    fn track_converges_on_noisy_straight_line() {
+        let mut rng = ShipRng::new(42);
+        let true_velocity = vec2(120.0, -40.0);
+        let start = vec2(1000.0, 500.0);
+        let mut track = RadarTrack::new(0, start + vec2(60.0, -60.0), Vec2::zero(), Class::Fighter, 200.0, 0);
+
+        for tick in 1..=240 {
+            let truth = start + true_velocity * (tick as f64 / 60.0);
+            let noise = vec2(rng.jitter(10.0), rng.jitter(10.0));
+            let velocity_noise = vec2(rng.jitter(5.0), rng.jitter(5.0));
+            track.push_plot(TimedScanResult {
+                tick,
+                scan: ScanResult { position: truth + noise, velocity: true_velocity + velocity_noise, class: Class::Fighter },
+            });
+            track.update_at(tick);
+        }
+
+        let truth = start + true_velocity * 4.0;
+        assert!((track.position - truth).length() < 15.0, "position {} vs {}", track.position, truth);
+        assert!((track.velocity - true_velocity).length() < 10.0, "velocity {} vs {}", track.velocity, true_velocity);
+    }
+
+    #[test]
+    fn kalman_gate_tightens_on_irregular_plots() {
+        let mut rng = ShipRng::new(7);
+        let true_velocity = vec2(-300.0, 150.0);
+        let start = vec2(-2000.0, 4000.0);
+        let mut track = RadarTrack::new(0, start, vec2(100.0, 0.0), Class::Fighter, 400.0, 0);
+        let opening_gate = track.gate.width;
+
+        // plots every one to four ticks, coasting in between
+        let mut next_plot = 1;
+        let mut early_error = 0.0;
+        for tick in 1..=360 {
+            let truth = start + true_velocity * (tick as f64 / 60.0);
+            if tick == next_plot {
+                track.push_plot(TimedScanResult {
+                    tick,
+                    scan: ScanResult {
+                        position: truth + vec2(rng.jitter(10.0), rng.jitter(10.0)),
+                        velocity: true_velocity + vec2(rng.jitter(5.0), rng.jitter(5.0)),
+                        class: Class::Fighter,
+                    },
+                });
+                next_plot += 1 + rng.uniform(0.0, 4.0) as u32;
+            }
+            track.update_at(tick);
+            if tick == 10 {
+                early_error = (track.position - truth).length();
+            }
+        }
+
+        let truth = start + true_velocity * 6.0;
+        let error = (track.position - truth).length();
+        assert!(error < 15.0 && error < early_error, "error {} after {} early", error, early_error);
+        assert!((track.velocity - true_velocity).length() < 10.0);
+        assert!(track.gate.width < opening_gate);
+        assert!(track.gate.width >= KALMAN_MIN_GATE);
+        assert!(track.check_gate(truth, 360), "truth outside a {} m gate", track.gate.width);
+
+        // a long coast grows the gate again
+        let tight = track.gate.width;
+        track.update_at(480);
+        assert!(track.gate.width > tight);
+    }
+
+    #[test]
+    fn queued_plots_fold_in_like_plots_taken_one_per_tick() {
+        let plot = |tick: u32| TimedScanResult {
+            tick,
+            scan: ScanResult {
+                position: vec2(1_000.0 + 5.0 * tick as f64, 200.0 - 3.0 * (tick * tick) as f64),
+                velocity: vec2(300.0, -20.0 * tick as f64),
+                class: Class::Fighter,
+            },
+        };
+        let mut steady = RadarTrack::new(0, vec2(990.0, 210.0), vec2(250.0, 0.0), Class::Fighter, 100.0, 0);
+        let mut backlogged = RadarTrack::new(0, vec2(990.0, 210.0), vec2(250.0, 0.0), Class::Fighter, 100.0, 0);
+
+        for tick in 1..=3 {
+            steady.push_plot(plot(tick));
+            steady.update_at(tick);
+        }
+        // the same plots, out of order and all at once
+        for tick in [3, 1, 2] {
+            backlogged.push_plot(plot(tick));
+        }
+        backlogged.update_at(3);
+        assert!(backlogged.scans.is_empty());
+        assert_eq!(backlogged.position, steady.position);
+        assert_eq!(backlogged.velocity, steady.velocity);
+        assert_eq!(backlogged.last_plot_tick, 3);
+
+        // a plot from before the last one folded in is dropped, not applied
+        steady.push_plot(plot(6));
+        steady.update_at(6);
+        backlogged.push_plot(plot(2));
+        backlogged.push_plot(plot(6));
+        backlogged.update_at(6);
+        assert_eq!(backlogged.position, steady.position);
+        assert_eq!(backlogged.velocity, steady.velocity);
+
+        // and a burst only keeps the newest few
+        for tick in 7..20 {
+            backlogged.push_plot(plot(tick));
+        }
+        assert_eq!(backlogged.scans.len(), MAX_QUEUED_SCANS);
+        assert_eq!(backlogged.scans.front().unwrap().tick, 20 - MAX_QUEUED_SCANS as u32);
+    }
+
+    #[test]
+    fn quiet_tracks_expire_without_new_plots() {
+        let config = Fighter::default_config();
+        let expiry = config.radar.track_expiry_ticks;
+        let mut radar = Radar::new(config.radar, "test_radar");
+        let plot = |position: Vec2| Detection::Local(ScanResult { position, velocity: Vec2::zero(), class: Class::Fighter });
+        radar.add_detection_point(plot(vec2(3_000.0, 0.0)));
+        radar.add_detection_point(plot(vec2(-3_000.0, 0.0)));
+        assert_eq!(radar.potential_targets.len(), 2);
+        let ids: Vec<u128> = radar.potential_targets.keys().copied().collect();
+        let (quiet, plotted) = (ids[0], ids[1]);
+
+        // a later plot refreshes one of them, the other hears nothing
+        {
+            let track = radar.get_track(plotted).unwrap();
+            let mut t = track.borrow_mut();
+            let position = t.position;
+            t.push_plot(TimedScanResult { tick: 40, scan: ScanResult { position, velocity: Vec2::zero(), class: Class::Fighter } });
+            t.update_at(40);
+            assert_eq!(t.contact_tick, 40);
+        }
+
+        radar.expire_tracks(expiry - 1);
+        assert!(radar.still_tracking(quiet) && radar.still_tracking(plotted));
+        radar.expire_tracks(expiry);
+        assert!(!radar.still_tracking(quiet));
+        assert!(radar.still_tracking(plotted));
+        radar.expire_tracks(40 + expiry);
+        assert!(!radar.has_contacts());
+    }
+
+    #[test]
+    fn plotted_tracks_keep_their_ids() {
+        let config = Fighter::default_config();
+        let mut radar = Radar::new(config.radar, "test_radar");
+        let mut stats = MatchStats::default();
+        let targets = [(vec2(4_000.0, 1_000.0), vec2(-150.0, 20.0)), (vec2(-2_000.0, 5_000.0), vec2(80.0, -200.0))];
+        for (position, velocity) in targets {
+            radar.add_detection_point(Detection::Local(ScanResult { position, velocity, class: Class::Fighter }));
+        }
+        let mut ids: Vec<u128> = radar.potential_targets.keys().copied().collect();
+        ids.sort();
+        assert_eq!(ids.len(), 2);
+
+        // five seconds of the beam alternating between them
+        for tick in 1..=300u32 {
+            let (position, velocity) = targets[tick as usize % 2];
+            let plot = ScanResult { position: position + velocity * (tick as f64 / 60.0), velocity, class: Class::Fighter };
+            for track in radar.potential_targets.values() {
+                let mut t = track.borrow_mut();
+                if t.check_gate(plot.position, tick) {
+                    t.push_plot(TimedScanResult { tick, scan: plot });
+                    break;
+                }
+            }
+            radar.expire_tracks(tick);
+            for track in radar.potential_targets.values() {
+                track.borrow_mut().update_at(tick);
+            }
+            assert!(radar.still_tracking(ids[0]) && radar.still_tracking(ids[1]), "track dropped at {}", tick);
+
+            // a ship following the first target by position stays on one id
+            let (position, velocity) = targets[0];
+            let designated = radar.get_closest_target_matching(position + velocity * (tick as f64 / 60.0), &|_| true).unwrap();
+            stats.designate(designated, 0, tick);
+        }
+
+        let mut after: Vec<u128> = radar.potential_targets.keys().copied().collect();
+        after.sort();
+        assert_eq!(after, ids);
+        assert_eq!(stats.churn, 0);
+        // each track was last in contact on the last tick the beam was on it
+        assert_eq!(radar.get_track(ids[0]).unwrap().borrow().contact_tick, 300);
+        assert_eq!(radar.get_track(ids[1]).unwrap().borrow().contact_tick, 299);
+    }
+
+    #[test]
+    fn crossing_tracks_keep_their_own_plots() {
+        let config = Fighter::default_config();
+        let mut radar = Radar::new(config.radar, "test_radar");
+        let mut rng = ShipRng::new(3);
+        // two fighters crossing the same point a second in, at right angles
+        let velocities = [vec2(200.0, 0.0), vec2(0.0, 200.0)];
+        let truth = |i: usize, tick: u32| velocities[i] * ((tick as f64 - 60.0) / 60.0);
+        for (i, id) in [(0, 10), (1, 20)] {
+            let track = RadarTrack::new(id, truth(i, 0), velocities[i], Class::Fighter, config.radar.gate_width, 0);
+            radar.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+        }
+
+        for tick in 1..=120u32 {
+            // both in the beam, so two plots in the same tick
+            for (i, id) in [(0, 10), (1, 20)] {
+                let plot = ScanResult {
+                    position: truth(i, tick) + vec2(rng.jitter(3.0), rng.jitter(3.0)),
+                    velocity: velocities[i] + vec2(rng.jitter(3.0), rng.jitter(3.0)),
+                    class: Class::Fighter,
+                };
+                assert_eq!(radar.choose_track(&plot, tick), Some(id), "plot {} went astray at {}", i, tick);
+                radar.get_track(id).unwrap().borrow_mut().push_plot(TimedScanResult { tick, scan: plot });
+            }
+            for track in radar.potential_targets.values() {
+                track.borrow_mut().update_at(tick);
+            }
+        }
+        assert_eq!(radar.potential_targets.len(), 2);
+
+        // a third plot the same tick finds both tracks taken
+        let (early, late) = (truth(0, 121), truth(0, 121) + vec2(1.0, 0.0));
+        let plot = |position: Vec2| ScanResult { position, velocity: velocities[0], class: Class::Fighter };
+        assert_eq!(radar.choose_track(&plot(early), 121), Some(10));
+        radar.get_track(10).unwrap().borrow_mut().push_plot(TimedScanResult { tick: 121, scan: plot(early) });
+        assert_eq!(radar.choose_track(&plot(late), 121), None);
+    }
+
+    #[test]
+    fn duplicate_plots_in_one_sweep_are_dropped() {
+        let config = Fighter::default_config();
+        let mut radar = Radar::new(config.radar, "test_radar");
+        let plot = |position: Vec2, class: Class| Detection::Local(ScanResult { position, velocity: vec2(100.0, 0.0), class });
+        radar.add_detection_point(plot(vec2(3_000.0, 0.0), Class::Fighter));
+        let id = *radar.potential_targets.keys().next().unwrap();
+        let before = radar.get_track(id).unwrap().borrow().snapshot();
+
+        // the same return again neither starts a twin nor moves the track
+        radar.add_detection_point(plot(vec2(3_001.0, 0.0), Class::Fighter));
+        assert_eq!(radar.potential_targets.len(), 1);
+        let after = radar.get_track(id).unwrap().borrow().snapshot();
+        assert_eq!(after.position, before.position);
+        assert_eq!(after.plot_count, before.plot_count);
+
+        // further off, or a different class on top of it, is something else
+        radar.add_detection_point(plot(vec2(3_000.0, DUPLICATE_PLOT_RADIUS * 2.0), Class::Fighter));
+        radar.add_detection_point(plot(vec2(3_000.0, 0.0), Class::Missile));
+        assert_eq!(radar.potential_targets.len(), 3);
+    }
+
+    #[test]
+    fn tracks_classify_from_agreeing_plots() {
+        let config = Fighter::default_config();
+        let mut radar = Radar::new(config.radar, "test_radar");
+        let plot = |tick: u32, position: Vec2, class: Class| TimedScanResult { tick, scan: ScanResult { position, velocity: Vec2::zero(), class } };
+        let contacts = [(1, vec2(3_000.0, 0.0), Class::Fighter), (2, vec2(0.0, 3_000.0), Class::Missile), (3, vec2(-3_000.0, 0.0), Class::Fighter)];
+        for (id, position, class) in contacts {
+            let mut track = RadarTrack::new(id, position, Vec2::zero(), class, 50.0, 0);
+            track.push_plot(plot(0, position, class));
+            track.update_at(0);
+            radar.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+        }
+        // our wingman is the third one
+        radar.mark_friendly(vec2(-3_000.0, 0.0), Vec2::zero());
+
+        // one plot isn't enough to call it
+        radar.classify_tracks();
+        assert!(matches!(radar.get_track(1).unwrap().borrow().class, TrackType::Tentative));
+        assert!(matches!(radar.get_track(2).unwrap().borrow().class, TrackType::Tentative));
+        assert!(matches!(radar.get_track(3).unwrap().borrow().class, TrackType::Friend));
+        assert!(!radar.get_track(1).unwrap().borrow().engageable(ENGAGE_MIN_PLOTS));
+
+        for (id, position, class) in contacts {
+            let track = radar.get_track(id).unwrap();
+            let mut t = track.borrow_mut();
+            t.push_plot(plot(1, position, class));
+            t.update_at(1);
+        }
+        radar.classify_tracks();
+        assert!(matches!(radar.get_track(1).unwrap().borrow().class, TrackType::Foe));
+        assert!(matches!(radar.get_track(2).unwrap().borrow().class, TrackType::Missile));
+        assert!(matches!(radar.get_track(3).unwrap().borrow().class, TrackType::Friend));
+
+        // the fighter's designation filter only ever sees the foe ship
+        let designatable = |t: &RadarTrack| t.engageable(ENGAGE_MIN_PLOTS) && matches!(t.class, TrackType::Foe);
+        assert_eq!(radar.get_closest_target_matching(vec2(-3_000.0, 0.0), &designatable), Some(1));
+
+        // a plot of a different class starts the classification over
+        {
+            let track = radar.get_track(1).unwrap();
+            let mut t = track.borrow_mut();
+            t.push_plot(plot(2, vec2(3_000.0, 0.0), Class::Frigate));
+            t.update_at(2);
+            assert!(matches!(t.class, TrackType::Tentative));
+            assert_eq!(t.contact_class, Class::Frigate);
+            assert_eq!(t.class_streak, 1);
+        }
+        assert!(matches!(track_type_for(Class::Torpedo, CLASS_CONFIRM_PLOTS), TrackType::Missile));
+    }
+
+    #[test]
+    fn reacquire_skips_friends_other_classes_and_taken_tracks() {
+        let config = Fighter::default_config();
+        let mut radar = Radar::new(config.radar, "test_radar");
+        let lost = LostContact::from_track(&RadarTrack::new(0, vec2(2_000.0, 0.0), Vec2::zero(), Class::Fighter, 200.0, 0).snapshot());
+        let tracks = [
+            (1, vec2(2_005.0, 0.0), Class::Fighter, TrackType::Friend),
+            (2, vec2(2_010.0, 0.0), Class::Missile, TrackType::Missile),
+            (3, vec2(2_020.0, 0.0), Class::Fighter, TrackType::Foe),
+            (4, vec2(2_050.0, 0.0), Class::Fighter, TrackType::Foe),
+            (5, vec2(2_100.0, 0.0), Class::Fighter, TrackType::Foe),
+            // outside the grown gate
+            (6, vec2(2_400.0, 0.0), Class::Fighter, TrackType::Foe),
+        ];
+        for (id, position, class, track_type) in tracks {
+            let mut track = RadarTrack::new(id, position, Vec2::zero(), class, 50.0, 0);
+            track.class = track_type;
+            track.plot_count = ENGAGE_MIN_PLOTS;
+            radar.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+        }
+        radar.designate(3);
+        let taken = |t: &RadarTrack| t.id == 4;
+
+        assert_eq!(lost.reacquire(&radar, &taken), Some(5));
+        assert_eq!(lost.reacquire(&radar, &|_| false), Some(4));
+        radar.clear_designation();
+        assert_eq!(lost.reacquire(&radar, &|_| false), Some(3));
+
+        radar.potential_targets.retain(|id, _| *id != 3 && *id != 4 && *id != 5);
+        assert_eq!(lost.reacquire(&radar, &|_| false), None);
+    }
+
+    #[test]
+    fn incoming_missiles_outrank_a_closer_ship() {
+        let config = Fighter::default_config();
+        let mut radar = Radar::new(config.radar, "test_radar");
+        let tracks = [
+            (1, vec2(600.0, 0.0), vec2(0.0, 0.0), Class::Fighter, TrackType::Foe),
+            (2, vec2(0.0, 1_500.0), vec2(0.0, -800.0), Class::Missile, TrackType::Missile),
+            // passing well wide and opening
+            (3, vec2(-1_000.0, 0.0), vec2(-800.0, 0.0), Class::Missile, TrackType::Missile),
+            // still closing but out past the gun
+            (4, vec2(0.0, -3_000.0), vec2(0.0, 800.0), Class::Missile, TrackType::Missile),
+        ];
+        for (id, position, velocity, class, track_type) in tracks {
+            let mut track = RadarTrack::new(id, position, velocity, class, 50.0, 0);
+            track.class = track_type;
+            track.plot_count = ENGAGE_MIN_PLOTS;
+            radar.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+        }
+        let incoming = |t: &RadarTrack| incoming_missile(&t.snapshot(), Vec2::zero(), Vec2::zero());
+
+        assert!(!incoming(&radar.get_track(1).unwrap().borrow()));
+        assert!(incoming(&radar.get_track(2).unwrap().borrow()));
+        assert!(!incoming(&radar.get_track(3).unwrap().borrow()));
+        assert!(!incoming(&radar.get_track(4).unwrap().borrow()));
+        assert_eq!(radar.get_closest_target_matching(Vec2::zero(), &|_| true), Some(1));
+        assert_eq!(radar.select_threat_matching(Vec2::zero(), Vec2::zero(), &incoming), Some(2));
+
+        // once it's dealt with nothing else qualifies
+        radar.get_track(2).unwrap().borrow_mut().probably_dead = true;
+        assert_eq!(radar.select_threat_matching(Vec2::zero(), Vec2::zero(), &incoming), None);
+
+        // a wingman diving on the defended point is never the threat
+        let mut wingman = RadarTrack::new(5, vec2(0.0, 300.0), vec2(0.0, -800.0), Class::Fighter, 50.0, 0);
+        wingman.class = TrackType::Friend;
+        wingman.plot_count = ENGAGE_MIN_PLOTS;
+        radar.potential_targets.insert(5, Rc::new(RefCell::new(wingman)));
+        assert!(radar.select_threat(Vec2::zero(), Vec2::zero()).is_some());
+        assert_ne!(radar.select_threat(Vec2::zero(), Vec2::zero()), Some(5));
+    }
+
+    #[test]
+    fn evasive_break_crosses_the_line_of_sight_and_reverses() {
+        assert_close(time_to_impact(1_000.0, 500.0), 2.0, 1e-12);
+        assert!(time_to_impact(1_000.0, 0.0).is_infinite());
+        assert!(time_to_impact(1_000.0, -50.0).is_infinite());
+
+        let mut rng = ShipRng::new(5);
+        let mut evasion = MissileEvasion::new(7, &mut rng, 0);
+        let los = vec2(300.0, 400.0);
+        let mut flips = Vec::new();
+        let mut last_sign = 0.0;
+        for tick in 0..200 {
+            let acceleration = evasion.acceleration(los, 60.0, &mut rng, tick);
+            assert_close(acceleration.dot(los), 0.0, 1e-9);
+            assert_close(acceleration.length(), 60.0, 1e-9);
+            let sign = acceleration.dot(los.rotate(PI / 2.0)).signum();
+            if last_sign != 0.0 && sign != last_sign {
+                flips.push(tick);
+            }
+            last_sign = sign;
+        }
+        // reversals keep coming, each after the randomized wait
+        assert!(flips.len() >= 7, "flips at {:?}", flips);
+        for pair in flips.windows(2) {
+            let wait = pair[1] - pair[0];
+            assert!(wait >= EVADE_FLIP_TICKS.0 && wait <= EVADE_FLIP_TICKS.1, "waited {}", wait);
+        }
+    }
+
+    #[test]
+    fn retreat_needs_damage_and_odds_and_holds_before_returning() {
+        // 20 hit points left against one or two undamaged fighters, or a frigate
+        let one = relative_health(20.0, &[Class::Fighter]);
+        let two = relative_health(20.0, &[Class::Fighter, Class::Fighter]);
+        assert_close(one, 0.2, 1e-12);
+        assert_close(two, 0.1, 1e-12);
+        assert!(relative_health(100.0, &[Class::Frigate]) < 0.1);
+        assert!(relative_health(20.0, &[]).is_infinite());
+
+        // healthy or one on one we stay
+        assert!(!should_flee(None, 0.8, 0.3, 0.3, 3, 2_000.0, false));
+        assert!(!should_flee(None, 0.2, 0.3, one, 1, 2_000.0, false));
+        // hurt and outnumbered, or hurt and outrun
+        assert!(should_flee(None, 0.2, 0.3, two, 2, 2_000.0, false));
+        assert!(should_flee(None, 0.2, 0.3, one, 1, 2_000.0, true));
+        // a fighter configured never to run doesn't
+        assert!(!should_flee(None, 0.01, 0.0, 0.0, 4, 500.0, true));
+
+        // the odds evening out doesn't end it straight away
+        assert!(should_flee(Some(10), 0.2, 0.3, 1.0, 1, 2_000.0, false));
+        assert!(!should_flee(Some(FLEE_MIN_TICKS), 0.2, 0.3, FLEE_REENGAGE_RELATIVE_HEALTH, 1, 2_000.0, false));
+        // down to one pursuer, but one we're still badly outmatched by
+        assert!(should_flee(Some(FLEE_MIN_TICKS), 0.2, 0.3, one, 1, 2_000.0, false));
+        // still chased by two inside the re-engage range
+        assert!(should_flee(Some(FLEE_MIN_TICKS), 0.2, 0.3, two, 2, 2_000.0, false));
+        // at full health even two fighters are odds worth turning back on
+        assert!(!should_flee(Some(FLEE_MIN_TICKS), 0.8, 0.3, relative_health(100.0, &[Class::Fighter, Class::Fighter]), 2, 2_000.0, false));
+        // contact broken
+        assert!(!should_flee(Some(FLEE_MIN_TICKS), 0.2, 0.3, two, 2, FLEE_REENGAGE_RANGE + 1.0, false));
+        assert!(!should_flee(Some(FLEE_MIN_TICKS), 0.2, 0.3, f64::INFINITY, 0, f64::INFINITY, false));
+
+        // 500 m/s of closure from 3 km can't be shed at 60 m/s/s before gun range, 100 can
+        assert!(outpaced(3_000.0, 500.0, 60.0));
+        assert!(!outpaced(3_000.0, 100.0, 60.0));
+        assert!(!outpaced(1_000.0, -10.0, 60.0));
+
+        // the nearer of two pursuers pulls the centroid toward it
+        let centroid = hostile_centroid(Vec2::zero(), &[vec2(1_000.0, 0.0), vec2(0.0, 3_000.0)]).unwrap();
+        assert_close(centroid.x, 750.0, 1e-9);
+        assert_close(centroid.y, 750.0, 1e-9);
+        assert!(hostile_centroid(Vec2::zero(), &[]).is_none());
+    }
+
+    #[test]
+    fn range_states_hold_until_well_past_the_line() {
+        let range = 2_000.0;
+        assert_eq!(next_engaged_state(ShipState::Engaged, 2_200.0, range), ShipState::Engaged);
+        assert_eq!(next_engaged_state(ShipState::Engaged, 2_500.0, range), ShipState::OutOfTargetRange);
+        // back inside the weapon range isn't enough, it has to come down past the margin
+        assert_eq!(next_engaged_state(ShipState::OutOfTargetRange, 1_900.0, range), ShipState::OutOfTargetRange);
+        assert_eq!(next_engaged_state(ShipState::OutOfTargetRange, 1_700.0, range), ShipState::Engaged);
+
+        assert_eq!(next_search_state(ShipState::Searching, RADAR_DRY_SPELL_TICKS - 1), ShipState::Searching);
+        assert_eq!(next_search_state(ShipState::Searching, RADAR_DRY_SPELL_TICKS), ShipState::OutOfRadarRange);
+        assert_eq!(next_search_state(ShipState::OutOfRadarRange, RADAR_DRY_SPELL_TICKS + 10), ShipState::OutOfRadarRange);
+        assert_eq!(next_search_state(ShipState::OutOfRadarRange, 0), ShipState::Searching);
+        // the other states aren't the search's to move
+        assert_eq!(next_search_state(ShipState::Engaged, RADAR_DRY_SPELL_TICKS), ShipState::Engaged);
+    }
+
+    #[test]
+    fn designation_holds_until_a_clearly_closer_track() {
+        let config = Fighter::default_config();
+        let mut radar = Radar::new(config.radar, "test_radar");
+        for (id, x) in [(10, 4_000.0), (20, 3_500.0), (30, 500.0)] {
+            let track = RadarTrack::new(id, vec2(x, 0.0), vec2(0.0, 0.0), Class::Fighter, config.radar.gate_width, 0);
+            radar.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+        }
+        let nearest = TargetWeights { distance: 1.0, closing: 0.0, class: 0.0, aspect: 0.0 };
+        let any = |_: &RadarTrack| true;
+
+        assert_eq!(radar.reselect(Some(10), &nearest, &any), Some(10));
+        // a little closer isn't enough to move off the designated track
+        assert_eq!(radar.reselect(Some(20), &nearest, &any), Some(10));
+        assert_eq!(radar.reselect(None, &nearest, &any), Some(10));
+        assert_eq!(radar.reselect(Some(30), &nearest, &any), Some(30));
+
+        // a designation that died, or stopped passing the filter, is given up right away
+        radar.potential_targets.remove(&30);
+        assert_eq!(radar.designated(), None);
+        assert_eq!(radar.reselect(Some(10), &nearest, &any), Some(10));
+        assert_eq!(radar.reselect(Some(20), &nearest, &|t: &RadarTrack| t.id != 10), Some(20));
+        radar.clear_designation();
+        assert_eq!(radar.designated(), None);
+
+        // an opening target scores below zero, the margin still only lets a better one in
+        assert!(!worth_switching(-0.5, -0.6));
+        assert!(!worth_switching(-0.6, -0.5));
+        assert!(worth_switching(-0.6, -0.3));
+        assert!(!worth_switching(0.5, 0.6));
+        assert!(worth_switching(0.5, 0.8));
+    }
+
+    #[test]
+    fn fighters_rank_an_attacker_over_a_nearer_drifter() {
+        let config = Fighter::default_config();
+        let mut radar = Radar::new(config.radar, "test_radar");
+        // a fighter drifting across ahead, and one further out coming straight at us
+        let drifter = RadarTrack::new(10, vec2(2_000.0, 0.0), vec2(0.0, 50.0), Class::Fighter, config.radar.gate_width, 0);
+        let attacker = RadarTrack::new(20, vec2(-3_000.0, 0.0), vec2(400.0, 0.0), Class::Fighter, config.radar.gate_width, 0);
+        for track in [drifter, attacker] {
+            radar.potential_targets.insert(track.id, Rc::new(RefCell::new(track)));
+        }
+        let any = |_: &RadarTrack| true;
+        assert_eq!(radar.get_closest_target_to_point(vec2(0.0, 0.0)), 10);
+        assert_eq!(radar.select_target(&config.target_weights, &any), Some(20));
+
+        // ordnance weights care about getting there, not about who's shooting
+        let missile = TargetWeights::for_class(Class::Missile);
+        let score = |id: u128| radar.score_track(id, &missile).total();
+        assert!(score(10) > 0.0 && score(20) > 0.0);
+        let drifter = radar.score_track(10, &missile);
+        assert_eq!(drifter.class, 0.0);
+        // dead ahead is worth the whole aspect weight, dead astern none of it
+        assert!((drifter.aspect - missile.aspect).abs() < 1e-9);
+        assert!(radar.score_track(20, &missile).aspect.abs() < 1e-9);
+    }
+
+    #[test]
+    fn lock_follows_the_designation_and_falls_back_without_it() {
+        let config = Fighter::default_config();
+        let mut radar = Radar::new(config.radar, "test_radar");
+        // a closer track that isn't designated mustn't steal the lock
+        for (id, x) in [(1, 800.0), (2, 3_000.0)] {
+            let track = RadarTrack::new(id, vec2(x, 0.0), vec2(0.0, 0.0), Class::Fighter, config.radar.gate_width, 0);
+            radar.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+        }
+        radar.lock_designated();
+        assert!(matches!(radar.search_state(), RadarState::MediumRange));
+
+        radar.designate(2);
+        radar.lock_designated();
+        assert!(matches!(radar.search_state(), RadarState::TargetFocus));
+        radar.lock_radar_to_target();
+        assert!(matches!(radar.search_state(), RadarState::TargetFocus));
+
+        radar.potential_targets.remove(&2);
+        radar.lock_radar_to_target();
+        assert!(matches!(radar.search_state(), RadarState::MediumRange));
+        assert_eq!(radar.plot_age(2), None);
+
+        radar.designate(1);
+        radar.lock_designated();
+        radar.release_lock();
+        assert!(matches!(radar.search_state(), RadarState::MediumRange));
+    }
+
+    #[test]
+    fn kalman_follows_a_turning_target() {
+        let mut rng = ShipRng::new(11);
+        let (turn_radius, speed) = (2000.0, 200.0);
+        let rate = speed / turn_radius;
+        let truth_at = |tick: u32| {
+            let angle = rate * tick as f64 / 60.0;
+            (vec2(turn_radius, 0.0).rotate(angle), vec2(0.0, speed).rotate(angle))
+        };
+        let mut track = RadarTrack::new(0, vec2(turn_radius + 50.0, 40.0), Vec2::zero(), Class::Fighter, 200.0, 0);
+
+        let mut worst = 0.0f64;
+        for tick in 1..=600 {
+            let (position, velocity) = truth_at(tick);
+            track.push_plot(TimedScanResult {
+                tick,
+                scan: ScanResult {
+                    position: position + vec2(rng.jitter(10.0), rng.jitter(10.0)),
+                    velocity: velocity + vec2(rng.jitter(5.0), rng.jitter(5.0)),
+                    class: Class::Fighter,
+                },
+            });
+            track.update_at(tick);
+            if tick > 120 {
+                worst = worst.max((track.position - position).length());
+            }
+        }
+
+        let (position, velocity) = truth_at(600);
+        assert!(worst < 20.0, "worst error {} after converging", worst);
+        assert!((track.velocity - velocity).length() < 10.0, "velocity {} vs {}", track.velocity, velocity);
+        assert!(track.check_gate(position, 600));
+    }
+
+    #[test]
+    fn closing_speed_head_on_crossing_and_stern_chase() {
+        // head on: 300 m/s combined closure, meet in 10 s
+        let rel_p = vec2(3000.0, 0.0);
+        let rel_v = vec2(-300.0, 0.0);
+        assert_close(closing_speed(rel_p, rel_v), 300.0, 1e-9);
+        assert_close(time_to_closest_approach(rel_p, rel_v), 10.0, 1e-9);
+        assert_close(distance_at_closest_approach(rel_p, rel_v), 0.0, 1e-9);
+
+        // pure crossing: no closure, already at closest approach
+        let rel_v = vec2(0.0, 200.0);
+        assert_close(closing_speed(rel_p, rel_v), 0.0, 1e-9);
+        assert_close(time_to_closest_approach(rel_p, rel_v), 0.0, 1e-9);
+        assert_close(distance_at_closest_approach(rel_p, rel_v), 3000.0, 1e-9);
+
+        // stern chase against a faster target: opening
+        let rel_v = vec2(50.0, 0.0);
+        assert_close(closing_speed(rel_p, rel_v), -50.0, 1e-9);
+        assert_close(time_to_closest_approach(rel_p, rel_v), 0.0, 1e-9);
+
+        // the opening 50 m/s has to be bought back before any closure
+        assert!(intercept_feasible(rel_p, rel_v, 100.0, 51.0));
+        assert!(!intercept_feasible(rel_p, rel_v, 100.0, 49.0));
+        // and nothing to burn with means no intercept whatever the fuel
+        assert!(!intercept_feasible(rel_p, rel_v, 0.0, 1_000.0));
+
+        // crossing at 200 m/s, 3 km out closing at 300 m/s: 10 s to kill it
+        let rel_v = vec2(-300.0, 200.0);
+        assert!(intercept_feasible(rel_p, rel_v, 30.0, 250.0));
+        assert!(!intercept_feasible(rel_p, rel_v, 15.0, 250.0));
+        assert!(!intercept_feasible(rel_p, rel_v, 30.0, 150.0));
+    }
+
+    #[test]
+    fn track_kinematics_are_measured_from_a_moving_observer() {
+        let observer = Vec2::zero();
+        let observer_velocity = vec2(100.0, 0.0);
+        let track = |position: Vec2, velocity: Vec2| RadarTrack::new(0, position, velocity, Class::Fighter, 200.0, 0);
+
+        // head on: 200 m/s of ours plus 100 m/s of theirs
+        let head_on = track(vec2(3000.0, 0.0), vec2(-200.0, 0.0));
+        assert_close(head_on.closing_speed_from(observer, observer_velocity), 300.0, 1e-9);
+        assert_close(head_on.time_to_closest_approach(observer, observer_velocity), 10.0, 1e-9);
+        assert_close(head_on.distance_at_closest_approach(observer, observer_velocity), 0.0, 1e-9);
+
+        // the same pass 400 m off the nose misses by exactly that
+        let offset = track(vec2(3000.0, 400.0), vec2(-200.0, 0.0));
+        assert_close(offset.time_to_closest_approach(observer, observer_velocity), 10.0, 1e-9);
+        assert_close(offset.distance_at_closest_approach(observer, observer_velocity), 400.0, 1e-9);
+
+        // crossing at our own speed along the line: no closure at all
+        let crossing = track(vec2(3000.0, 0.0), vec2(100.0, 250.0));
+        assert_close(crossing.closing_speed_from(observer, observer_velocity), 0.0, 1e-9);
+        assert_close(crossing.distance_at_closest_approach(observer, observer_velocity), 3000.0, 1e-9);
+
+        // stern chase: a slower target is run down, a faster one opens
+        let slower = track(vec2(3000.0, 0.0), vec2(50.0, 0.0));
+        assert_close(slower.closing_speed_from(observer, observer_velocity), 50.0, 1e-9);
+        assert_close(slower.time_to_closest_approach(observer, observer_velocity), 60.0, 1e-9);
+        assert!(slower.intercept_feasible(observer, observer_velocity, 10.0, 0.0));
+
+        let faster = track(vec2(3000.0, 0.0), vec2(150.0, 0.0));
+        assert_close(faster.closing_speed_from(observer, observer_velocity), -50.0, 1e-9);
+        assert_close(faster.time_to_closest_approach(observer, observer_velocity), 0.0, 1e-9);
+        assert!(!faster.intercept_feasible(observer, observer_velocity, 10.0, 40.0));
+        assert!(faster.intercept_feasible(observer, observer_velocity, 10.0, 60.0));
+    }
+
+    #[test]
+    fn radio_codec_quantizes_and_rejects_malformed_payloads() {
+        // off-grid values come back on the nearest RADIO_RESOLUTION step
+        let report = RadioMessage::ContactReport { position: vec2(100.004, -100.006), velocity: vec2(0.123, 0.0), class: Class::Frigate, age: 0, sender: 0 };
+        assert_eq!(RadioMessage::decode(report.encode()), Some(RadioMessage::ContactReport { position: vec2(100.0, -100.01), velocity: vec2(0.12, 0.0), class: Class::Frigate, age: 0, sender: 0 }));
+        let slot = RadioMessage::FormationSlot { offset: vec2(-0.004, 0.006) };
+        assert_eq!(RadioMessage::decode(slot.encode()), Some(RadioMessage::FormationSlot { offset: vec2(0.0, 0.01) }));
+        assert_eq!(RadioMessage::decode(RadioMessage::Ack { id: u32::MAX }.encode()), Some(RadioMessage::Ack { id: u32::MAX }));
+
+        let handoff = RadioMessage::TargetHandoff { position: vec2(10.0, 20.0), velocity: vec2(-5.0, 5.0), salvo: 7, bearing: 0.5, overrides: 0, approach: 1.5, sender: 1 };
+        let good_vec = pack_vec2(vec2(10.0, 20.0));
+        let bad_vec = [-1.0, good_vec + 0.5, (1u64 << 52) as f64];
+
+        // unknown or fractional tags
+        for tag in [0.0, 12.0, -1.0, RADIO_TAG_ACK + 0.5] {
+            assert_eq!(RadioMessage::decode([tag, good_vec, good_vec, 0.0]), None, "tag {}", tag);
+        }
+
+        // a vector slot that isn't a whole packed pair
+        for bad in bad_vec {
+            let mut payload = handoff.encode();
+            payload[1] = bad;
+            assert_eq!(RadioMessage::decode(payload), None);
+            payload = handoff.encode();
+            payload[2] = bad;
+            assert_eq!(RadioMessage::decode(payload), None);
+            assert_eq!(RadioMessage::decode([RADIO_TAG_CONTACT_REPORT, bad, good_vec, 1.0]), None);
+            assert_eq!(RadioMessage::decode([RADIO_TAG_FORMATION_SLOT, bad, 0.0, 0.0]), None);
+        }
+
+        // header slots out of range
+        let mut payload = handoff.encode();
+        payload[3] = (1u64 << 53) as f64;
+        assert_eq!(RadioMessage::decode(payload), None);
+        payload[3] = -1.0;
+        assert_eq!(RadioMessage::decode(payload), None);
+        assert_eq!(RadioMessage::decode([RADIO_TAG_ACK, 0.0, 0.0, -1.0]), None);
+        assert_eq!(RadioMessage::decode([RADIO_TAG_ACK, 0.0, 0.0, u32::MAX as f64 + 1.0]), None);
+        assert_eq!(RadioMessage::decode([RADIO_TAG_CONTACT_REPORT, good_vec, good_vec, -16.0]), None);
+
+        // class codes past the last class
+        for code in [8.0, 15.0] {
+            assert_eq!(RadioMessage::decode([RADIO_TAG_CONTACT_REPORT, good_vec, good_vec, code + 16.0 * 3.0]), None, "class code {}", code);
+        }
+
+        // any non-finite slot, wherever it is
+        for slot in 0..4 {
+            for bad in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+                let mut payload = handoff.encode();
+                payload[slot] = bad;
+                assert_eq!(RadioMessage::decode(payload), None);
+            }
+        }
+    }
+
+    #[test]
+    fn radio_messages_round_trip() {
+        let messages = [
+            RadioMessage::TargetHandoff { position: vec2(1234.56, -7890.12), velocity: vec2(-300.25, 45.5), salvo: 3, bearing: 1.234, overrides: HANDOFF_NO_WEAVE | HANDOFF_FULL_BURN, approach: 4.567, sender: 42 },
+            RadioMessage::TargetHandoff { position: Vec2::zero(), velocity: Vec2::zero(), salvo: HANDOFF_MAX_SALVO, bearing: 6.282, overrides: 15, approach: 6.282, sender: 255 },
+            RadioMessage::ContactReport { position: vec2(-15000.0, 15000.0), velocity: vec2(0.0, -999.99), class: Class::Missile, age: 12, sender: 201 },
+            RadioMessage::Ack { id: 77 },
+            RadioMessage::FormationSlot { offset: vec2(-300.0, -300.0) },
+            RadioMessage::TerminalOn { position: vec2(10.01, 20.02) },
+            RadioMessage::FormationLead { position: vec2(5.0, 6.0), velocity: vec2(7.0, 8.0), heading: 1.25, engaged: true },
+            RadioMessage::CapitalBeacon { position: vec2(-2500.5, 400.0), velocity: vec2(12.0, -3.0) },
+            RadioMessage::MissileHeartbeat { position: vec2(300.0, -200.0), target: vec2(4000.0, 1000.0), salvo: 5 },
+            RadioMessage::StrikeOffer { target: vec2(4000.0, 1000.0), flight_ticks: 240, sender: 9 },
+            RadioMessage::StrikeProposal { target: vec2(4000.0, 1000.0), arrival_tick: 123_456, sender: 255 },
+            RadioMessage::TargetClaim { target: vec2(-4000.0, 1000.0), strength: 0.875, sender: 3 },
+        ];
+        for message in messages {
+            assert_eq!(RadioMessage::decode(message.encode()), Some(message));
+        }
+        assert_eq!(RadioMessage::decode([f64::NAN, 0.0, 0.0, 0.0]), None);
+        assert_eq!(RadioMessage::decode([RADIO_TAG_TERMINAL_ON, f64::INFINITY, 0.0, 0.0]), None);
+
+        // positions off the map come back on its edge, velocities aren't touched
+        let half = world_size() / 2.0;
+        let far = RadioMessage::CapitalBeacon { position: vec2(3.0 * half, -half - 10.0), velocity: vec2(3.0 * half, 0.0) };
+        assert_eq!(RadioMessage::decode(far.encode()), Some(RadioMessage::CapitalBeacon { position: vec2(half, -half), velocity: vec2(3.0 * half, 0.0) }));
+        assert_eq!(RadioMessage::decode([99.0, 0.0, 0.0, 0.0]), None);
+    }
+
+    // point mass missile with a lagging autopilot against a target flying a
+    // constant circle, returns the closest the two got in 20 s of flight and
+    // the largest lateral demand made outside the last 300 m
+    fn circling_target_miss(nav_gain: f64, apn_weight: f64, available: f64) -> (f64, f64) {
+        let dt = 1.0 / 60.0;
+        let autopilot_lag = 0.2;
+        let (radius, speed) = (400.0, 300.0);
+        let omega = speed / radius;
+        let center = vec2(6000.0, 0.0);
+        let mut missile_p = vec2(0.0, 0.0);
+        let mut missile_v = vec2(300.0, 0.0);
+        let mut achieved = vec2(0.0, 0.0);
+        let mut closest = f64::MAX;
+        let mut peak: f64 = 0.0;
+        for tick in 0..1200 {
+            let phase = omega * tick as f64 * dt;
+            let target_p = center + vec2(radius, 0.0).rotate(phase);
+            let target_v = vec2(0.0, speed).rotate(phase);
+            let target_a = (center - target_p) * (omega * omega);
+
+            let rel_p = target_p - missile_p;
+            let rel_v = target_v - missile_v;
+            closest = closest.min(rel_p.length());
+            if rel_p.length() < 1.0 || (closing_speed(rel_p, rel_v) < 0.0 && rel_p.length() < 500.0) {
+                break;
+            }
+            let lateral = augmented_pn(rel_p, rel_v, target_a, nav_gain, apn_weight);
+            if rel_p.length() > 300.0 {
+                peak = peak.max(lateral.abs());
+            }
+            let command = pn_command(rel_p, lateral, available);
+            achieved += (command - achieved) * (dt / autopilot_lag);
+            missile_p += missile_v * dt + 0.5 * achieved * dt * dt;
+            missile_v += achieved * dt;
+        }
+        (closest, peak)
+    }
+
+    #[test]
+    fn augmented_pn_connects_against_steady_turn() {
+        let available = 300.0;
+        let (plain_miss, plain_peak) = circling_target_miss(MISSILE_NAV_GAIN, 0.0, available);
+        let (augmented_miss, augmented_peak) = circling_target_miss(MISSILE_NAV_GAIN, 1.0, available);
+
+        // plain PN lags the turn, ends up asking for more than the motor has and
+        // passes outside the fuse
+        let fuse_radius = OrdnanceProfile::for_class(Class::Missile).fuse_radius;
+        assert!(plain_peak > available, "plain peak {}", plain_peak);
+        assert!(plain_miss > fuse_radius, "plain miss {}", plain_miss);
+        assert!(augmented_peak < available, "augmented peak {}", augmented_peak);
+        assert_close(augmented_miss, 0.0, fuse_radius);
+
+        // N is tunable, the usual range still connects
+        for nav_gain in [3.0, 5.0] {
+            let (miss, _) = circling_target_miss(nav_gain, 1.0, available);
+            assert_close(miss, 0.0, fuse_radius);
+        }
+    }
+
+    #[test]
+    fn break_turns_feed_forward_from_the_turn_rate() {
+        // turning left at 0.1 rad/s, the lagging estimate still has a bit of the old turn
+        let velocity = vec2(0.0, 200.0);
+        let a = break_turn_acceleration(velocity, vec2(5.0, 3.0), 0.1, 70.0);
+        assert_close(a.x, -20.0, 1e-9);
+        assert_close(a.y, 3.0, 1e-9);
+        // a hard break is held to what the class can pull
+        assert_close(break_turn_acceleration(velocity, Vec2::zero(), -1.0, 70.0).length(), 70.0, 1e-9);
+        assert_eq!(break_turn_acceleration(Vec2::zero(), vec2(5.0, 3.0), 0.1, 70.0), vec2(5.0, 3.0));
+    }
+
+    #[test]
+    fn apn_weight_ramps_on_quality() {
+        assert_eq!(apn_weight(1.0, QUALITY_MISSILE), 0.0);
+        assert_eq!(apn_weight(1.0, 0.0), 0.0);
+        assert_eq!(apn_weight(1.0, QUALITY_GUN), 1.0);
+        assert_eq!(apn_weight(2.0, 1.0), 2.0);
+        assert_close(apn_weight(1.0, (QUALITY_MISSILE + QUALITY_GUN) / 2.0), 0.5, 1e-9);
+    }
+
+    #[test]
+    fn ordnance_profiles_fly_differently() {
+        let missile = OrdnanceProfile::for_class(Class::Missile);
+        let torpedo = OrdnanceProfile::for_class(Class::Torpedo);
+
+        // a quarter period in, the torpedo is at full weave
+        let quarter = torpedo.weave_period / 4;
+        assert_close(torpedo.weave(quarter, 0.0, false), torpedo.weave_acceleration, 1e-9);
+        assert_close(torpedo.weave(quarter, 0.0, true), torpedo.weave_acceleration, 1e-9);
+
+        // the missile weaves only on the approach, and not short of fuel or on a marginal shot
+        assert!(!missile.weave_allowed(missile.weave_outer_range + 1.0, 100.0, 100.0, true));
+        assert!(missile.weave_allowed(missile.weave_outer_range - 1.0, 100.0, 100.0, true));
+        assert!(!missile.weave_allowed(missile.weave_inner_range - 1.0, 100.0, 100.0, true));
+        assert!(!missile.weave_allowed(missile.weave_outer_range - 1.0, 10.0, 100.0, true));
+        assert!(!missile.weave_allowed(missile.weave_outer_range - 1.0, 100.0, 100.0, false));
+
+        // boost to the commanded closing speed or the boost's share of the fuel, then
+        // coast until terminal range
+        let far = 2.0 * missile.terminal_range;
+        let commanded = far / MISSILE_COAST_SECONDS;
+        assert_eq!(missile.phase(far, 0.0, 100.0, 100.0), FlightPhase::Boost);
+        assert_eq!(missile.phase(far, commanded + 1.0, 100.0, 100.0), FlightPhase::Coast);
+        assert_eq!(missile.phase(far, 0.0, 35.0, 100.0), FlightPhase::Coast);
+        assert_eq!(missile.phase(missile.terminal_range - 1.0, 0.0, 0.0, 100.0), FlightPhase::Terminal);
+
+        // the torpedo paces midcourse and coasts on its reserve, the missile burns it all
+        assert_close(torpedo.available_acceleration(false, 100.0, 100.0, 200.0), 200.0 * torpedo.midcourse_acceleration, 1e-9);
+        assert_eq!(torpedo.available_acceleration(false, 20.0, 100.0, 200.0), 0.0);
+        assert_eq!(torpedo.available_acceleration(true, 20.0, 100.0, 200.0), 200.0);
+        assert_eq!(missile.available_acceleration(false, 20.0, 100.0, 200.0), 200.0);
+        assert_eq!(missile.available_acceleration(true, 0.0, 100.0, 200.0), 0.0);
+
+        // the launcher can call off the weave for the terminal run only, or entirely
+        let straight = torpedo.with_overrides(HANDOFF_STRAIGHT_TERMINAL);
+        assert_eq!(straight.weave(quarter, 0.0, true), 0.0);
+        assert!(straight.weave(quarter, 0.0, false) > 0.0);
+        let undefended = torpedo.with_overrides(handoff_overrides(Class::Fighter));
+        assert_eq!(undefended.weave(quarter, 0.0, false), 0.0);
+        assert_eq!(undefended.available_acceleration(false, 20.0, 100.0, 200.0), 200.0);
+        assert_eq!(torpedo.with_overrides(handoff_overrides(Class::Cruiser)), torpedo);
+
+        // the seeded track gets a maneuver bound from the overrides alone
+        assert_eq!(handoff_class(handoff_overrides(Class::Fighter)), Class::Fighter);
+        assert_eq!(handoff_class(handoff_overrides(Class::Frigate)), Class::Unknown);
+    }
+
+    #[test]
+    fn knife_fight_lead_steps_no_faster_than_the_hull() {
+        let dt = 1.0 / 60.0;
+        let step = max_heading_change(1.0, 2.0 * PI, dt);
+        assert_close(step, dt + 0.5 * 2.0 * PI * dt * dt, 1e-12);
+        assert_eq!(max_heading_change(-1.0, 2.0 * PI, dt), step);
+
+        // a lead that jumped across the nose only moves the command one hull step
+        assert_close(rate_limited_bearing(0.0, 1.0, step), step, 1e-12);
+        assert_close(rate_limited_bearing(0.0, -1.0, step), -step, 1e-12);
+        // small changes pass straight through
+        assert_close(rate_limited_bearing(0.5, 0.5 + step / 2.0, step), 0.5 + step / 2.0, 1e-12);
+        // and the step goes the short way across +/-PI
+        let wrapped = rate_limited_bearing(PI - 0.001, -PI + 0.001, step);
+        assert_close(angle_diff(PI - 0.001, wrapped), 0.002, 1e-9);
+    }
+
+    #[test]
+    fn remote_reports_fuse_into_local_tracks() {
+        let config = Fighter::default_config();
+        let mut radar = Radar::new(config.radar, "test_radar");
+        let report = |position: Vec2, age: u32| Detection::Remote { position, velocity: Vec2::zero(), class: Class::Fighter, age, sender: 9 };
+
+        // a teammate tracking us is dropped before it can become a track
+        radar.add_detection_point(report(vec2(30.0, 0.0), 0));
+        assert!(radar.potential_targets.is_empty());
+
+        radar.add_detection_point(report(vec2(5_000.0, 0.0), 0));
+        assert_eq!(radar.potential_targets.len(), 1);
+        let track = Rc::clone(radar.potential_targets.values().next().unwrap());
+        assert_eq!(read_track(&track).source, TrackSource::Remote { sender: 9 });
+        assert_eq!(track.borrow().gate.width, REMOTE_GATE_WIDTH);
+
+        // a stale report from another teammate only pulls the fresher estimate part
+        // of the way, tightens the gate a little and keeps the fresher sender
+        let stale_report = Detection::Remote { position: vec2(5_050.0, 0.0), velocity: Vec2::zero(), class: Class::Fighter, age: 120, sender: 4 };
+        radar.add_detection_point(stale_report);
+        assert_eq!(radar.potential_targets.len(), 1);
+        assert!(remote_report_width(120) > REMOTE_GATE_WIDTH);
+        let (held, stale) = (REMOTE_GATE_WIDTH, remote_report_width(120));
+        let fused = held * stale / (held * held + stale * stale).sqrt();
+        assert_close(track.borrow().gate.width, fused, 1e-9);
+        assert!(fused < held);
+        assert_close(read_track(&track).position.x, 5_000.0 + 50.0 * held * held / (held * held + stale * stale), 1e-6);
+        assert_eq!(read_track(&track).source, TrackSource::Remote { sender: 9 });
+
+        // our own plot upgrades it and snaps the gate down to at most the local size
+        radar.add_detection_point(Detection::Local(ScanResult { position: vec2(5_040.0, 10.0), velocity: Vec2::zero(), class: Class::Fighter }));
+        assert_eq!(radar.potential_targets.len(), 1);
+        assert_eq!(read_track(&track).source, TrackSource::Fused);
+        assert!(read_track(&track).source.has_local());
+        let local_width = track.borrow().gate.width;
+        assert!(local_width <= config.radar.gate_width);
+
+        // and later reports only nudge it
+        let before = read_track(&track).position;
+        radar.add_detection_point(report(before + vec2(40.0, 0.0), 0));
+        assert_eq!(track.borrow().gate.width, local_width);
+        assert_close((read_track(&track).position - before).length(), 40.0 * REMOTE_BLEND, 1e-6);
+    }
+
+    #[test]
+    fn missile_and_fighter_split_the_attack_axis() {
+        let offset = OrdnanceProfile::for_class(Class::Missile).approach_offset;
+        assert!((PI / 3.0..=PI / 2.0).contains(&offset));
+        let target = vec2(1_000.0, 0.0);
+
+        // launched from the fighter's side, the missile swings offset around the target
+        let fighter_aspect = PI;
+        let waypoint = dogleg_waypoint(target, vec2(0.0, 0.0), fighter_aspect, offset, 2_000.0).unwrap();
+        assert_close((waypoint - target).length(), 2_000.0, 1e-9);
+        assert_close(angle_diff(fighter_aspect, (waypoint - target).angle()), offset, 1e-9);
+        // and turns in once it's around
+        let around = target + vec2(3_000.0, 0.0).rotate(fighter_aspect + offset - 0.1);
+        assert_eq!(dogleg_waypoint(target, around, fighter_aspect, offset, 2_000.0), None);
+
+        // the fighter's orbit swings its aspect the other way from the missile's axis
+        for missile_side in [1.0, -1.0] {
+            let mut orbit = OrbitPattern::new(500.0, 1_000.0);
+            let to_target = target;
+            let aspect = (-to_target).angle();
+            let avoid = aspect + missile_side * offset;
+            orbit.set_avoid_bearing(Some(avoid));
+            let accel = orbit.orbit_acceleration(to_target, Vec2::zero());
+            let aspect_rate = -to_target.x * accel.y + to_target.y * accel.x;
+            assert!(aspect_rate * angle_diff(aspect, avoid) < 0.0, "side {}", missile_side);
+        }
+    }
+
+    #[test]
+    fn approach_arrives_at_standoff_slow() {
+        // closing head on from well out at 600 m/s, only the planner's brake acting
+        let dt = 1.0 / 60.0;
+        let standoff = 600.0;
+        for start in [4_000.0, 8_000.0] {
+            let mut planner = ApproachPlanner::new();
+            let (mut range, mut closing): (f64, f64) = (start, 600.0);
+            while closing > 25.0 && range > 0.0 {
+                let brake = planner.plan(range - standoff, closing, vec2(1.0, 0.0)).map_or(0.0, |b| -b.x);
+                closing -= brake * dt;
+                range -= closing * dt;
+            }
+            // down to arrival speed inside the orbit band, not blown through it
+            assert!(range > 300.0 && range < 900.0, "from {} stopped at {:.0} closing {:.0}", start, range, closing);
+        }
+    }
+
+    #[test]
+    fn orbit_holds_standoff_and_cuts_back_when_pressed() {
+        let mut orbit = OrbitPattern::new(500.0, 1_000.0);
+        // target due east at the standoff radius, sitting still: all tangential
+        let accel = orbit.orbit_acceleration(vec2(600.0, 0.0), Vec2::zero());
+        assert_close(accel.x, 0.0, 1e-9);
+        assert!(accel.y.abs() > 0.0);
+        let direction = accel.y.signum();
+        // further out it pulls in as well
+        assert!(orbit.orbit_acceleration(vec2(800.0, 0.0), Vec2::zero()).x > 0.0);
+
+        // pressed inside the minimum radius it reverses, once the cooldown is up
+        for _ in 0..60 {
+            orbit.orbit_acceleration(vec2(600.0, 0.0), Vec2::zero());
+        }
+        let accel = orbit.orbit_acceleration(vec2(200.0, 0.0), Vec2::zero());
+        assert_eq!(accel.y.signum(), -direction);
+        let accel = orbit.orbit_acceleration(vec2(200.0, 0.0), Vec2::zero());
+        assert_eq!(accel.y.signum(), -direction);
+    }
+
+    #[test]
+    fn closing_bands_follow_the_pattern_params() {
+        let mut orbit = OrbitPattern::new(500.0, 1_000.0);
+        let east = vec2(1.0, 0.0);
+        let relative_velocity = vec2(-40.0, 30.0);
+
+        // drift the way the range is going, sit on the target, then close
+        assert_eq!(orbit.band_acceleration(300.0, east, relative_velocity, true), east * 10.0);
+        assert_eq!(orbit.band_acceleration(300.0, east, relative_velocity, false), -east * 10.0);
+        assert_eq!(orbit.band_acceleration(700.0, east, relative_velocity, false), orbit.match_velocity(relative_velocity));
+        assert_eq!(orbit.band_acceleration(2_000.0, east, relative_velocity, false), east * 100.0);
+
+        // matching takes the relative velocity out rather than just pointing along it
+        let mut remaining = relative_velocity;
+        for _ in 0..120 {
+            remaining -= orbit.match_velocity(remaining) / 60.0;
+        }
+        assert!(remaining.length() < relative_velocity.length() * 0.05, "{} left", remaining);
+        assert_eq!(orbit.match_velocity(relative_velocity * 2.0), orbit.match_velocity(relative_velocity) * 2.0);
+
+        // each param only moves its own band
+        orbit.drift_acceleration = 25.0;
+        orbit.closing_acceleration = 60.0;
+        assert_eq!(orbit.band_acceleration(300.0, east, relative_velocity, true), east * 25.0);
+        assert_eq!(orbit.band_acceleration(700.0, east, relative_velocity, false), orbit.match_velocity(relative_velocity));
+        assert_eq!(orbit.band_acceleration(2_000.0, east, relative_velocity, false), east * 60.0);
+
+        // and the bands move with the ranges
+        orbit.close_range = 200.0;
+        orbit.far_range = 400.0;
+        assert_eq!(orbit.band_acceleration(300.0, east, relative_velocity, true), orbit.match_velocity(relative_velocity));
+        assert_eq!(orbit.band_acceleration(700.0, east, relative_velocity, false), east * 60.0);
+    }
+
+    #[test]
+    fn stats_count_churn_but_not_reselection() {
+        let mut stats = MatchStats::default();
+        stats.designate(1, 100, 160);
+        stats.designate(1, 100, 161);
+        assert_eq!(stats.churn, 0);
+        // switching away without a kill is churn
+        stats.designate(2, 200, 320);
+        assert_eq!(stats.churn, 1);
+        // after a kill it isn't
+        stats.kill();
+        stats.designate(3, 300, 330);
+        assert_eq!(stats.churn, 1);
+        // going back to a track doesn't count its delay twice
+        stats.designate(1, 100, 900);
+        assert_eq!(stats.designated.len(), 3);
+        assert_eq!(stats.designation_delay_ticks, 60 + 120 + 30);
+        stats.reject(FireReject::MissDistance);
+        assert!(stats.summary().contains("miss 1"));
+    }
+
+    #[test]
+    fn friendly_in_corridor_only_ahead_and_short_of_range() {
+        let corridor = |heading, range, friendly: &[(Vec2, Vec2)]|
+            friendly_in_corridor(Vec2::zero(), Vec2::zero(), heading, 1_000.0, range, friendly, FRIENDLY_CORRIDOR_WIDTH);
+        let friendly = [(vec2(500.0, 20.0), Vec2::zero())];
+        assert!(corridor(0.0, 1_000.0, &friendly));
+        // beyond the target, behind us, or well off the line
+        assert!(!corridor(0.0, 400.0, &friendly));
+        assert!(!corridor(PI, 1_000.0, &friendly));
+        assert!(!corridor(PI / 4.0, 1_000.0, &friendly));
+
+        // the corridor widens downrange
+        assert!(corridor(0.0, 3_000.0, &[(vec2(2_000.0, 80.0), Vec2::zero())]));
+        assert!(!corridor(0.0, 3_000.0, &[(vec2(200.0, 80.0), Vec2::zero())]));
+        // crossing into the line by the time the rounds get there, or already past it
+        assert!(corridor(0.0, 3_000.0, &[(vec2(1_000.0, -200.0), vec2(0.0, 200.0))]));
+        assert!(!corridor(0.0, 3_000.0, &[(vec2(1_000.0, 0.0), vec2(0.0, 200.0))]));
+
+        let friendly = [vec2(1_000.0, 100.0)];
+        assert!(friendly_in_launch_cone(Vec2::zero(), 0.0, 2_000.0, &friendly, FRIENDLY_LAUNCH_CONE));
+        assert!(!friendly_in_launch_cone(Vec2::zero(), 0.0, 500.0, &friendly, FRIENDLY_LAUNCH_CONE));
+        assert!(!friendly_in_launch_cone(Vec2::zero(), -0.3, 2_000.0, &friendly, FRIENDLY_LAUNCH_CONE));
+    }
+
+    #[test]
+    fn aimed_torpedoes_jump_the_point_defense_queue() {
+        // the missile arrives first, but the torpedo takes long enough to kill that it has less slack
+        let missile = RadarTrack::new(1, vec2(1_500.0, 0.0), vec2(-1_000.0, 0.0), Class::Missile, 50.0, 0);
+        let torpedo = RadarTrack::new(2, vec2(0.0, 600.0), vec2(0.0, -300.0), Class::Torpedo, 50.0, 0);
+        assert!(point_defense_slack(2.0, Class::Torpedo) < point_defense_slack(1.5, Class::Missile));
+
+        let score = |t: &RadarTrack| threat_score(t, Vec2::zero(), Vec2::zero());
+        assert!(score(&torpedo) > score(&missile));
+
+        // one thing at a time: the same range and closing speed but sliding past
+        // the defended point only loses the danger multiplier
+        let wide = RadarTrack::new(3, vec2(0.0, 600.0), vec2(300.0, -300.0), Class::Torpedo, 50.0, 0);
+        assert_close(wide.closing_speed_from(Vec2::zero(), Vec2::zero()), 300.0, 1e-9);
+        assert!(wide.distance_at_closest_approach(Vec2::zero(), Vec2::zero()) > TORPEDO_AIM_RADIUS);
+        assert_close(score(&wide), score(&torpedo) / TORPEDO_DANGER, 1e-12);
+        // and the same miss as a missile only changes how long it takes to kill
+        let wide_missile = RadarTrack::new(4, vec2(0.0, 600.0), vec2(300.0, -300.0), Class::Missile, 50.0, 0);
+        assert!(score(&wide) > score(&wide_missile));
+        let kill_range = |class: Class| 600.0 - 300.0 * estimated_time_to_kill(class);
+        assert_close(score(&wide) / score(&wide_missile), kill_range(Class::Missile) / kill_range(Class::Torpedo), 1e-9);
+    }
+
+    type RaidPick = dyn Fn(&[(f64, f64, f64)]) -> usize;
+
+    // one gun against a raid of (range, closing speed, class) threats flying straight
+    // in, picking a threat every tick, true if everything is shot down before it lands
+    fn survives_raid(raid: &[(f64, f64, Class)], pick: &RaidPick) -> bool {
+        let dt = 1.0 / 60.0;
+        // (range, closing speed, fire still needed)
+        let mut threats: Vec<(f64, f64, f64)> = raid.iter().map(|(r, v, class)| (*r, *v, estimated_time_to_kill(*class))).collect();
+        for _ in 0..600 {
+            if threats.is_empty() {
+                return true;
+            }
+            let i = pick(&threats);
+            threats[i].2 -= dt;
+            threats.retain(|t| t.2 > 1e-9);
+            for t in threats.iter_mut() {
+                t.0 -= t.1 * dt;
+            }
+            if threats.iter().any(|t| t.0 <= 0.0) {
+                return false;
+            }
+        }
+        threats.is_empty()
+    }
+
+    #[test]
+    fn point_defense_interleaves_a_mixed_raid() {
+        // the torpedo is closest the whole time the missiles are inbound, shooting it
+        // first leaves no time for the second missile
+        let raid = [(250.0, 100.0, Class::Torpedo), (1_100.0, 1_000.0, Class::Missile), (1_120.0, 1_000.0, Class::Missile)];
+        let nearest = |threats: &[(f64, f64, f64)]| (0..threats.len())
+            .min_by(|a, b| threats[*a].0.partial_cmp(&threats[*b].0).unwrap())
+            .unwrap();
+        let by_slack = |threats: &[(f64, f64, f64)]| {
+            let needs: Vec<(f64, f64)> = threats.iter().map(|(r, v, left)| (r / v, *left)).collect();
+            point_defense_order(&needs)[0]
+        };
+        assert!(!survives_raid(&raid, &nearest));
+        assert!(survives_raid(&raid, &by_slack));
+
+        // fire swaps between two equally urgent torpedoes instead of finishing one
+        let torpedoes = [(2.0, 1.0), (2.0, 1.0)];
+        assert_eq!(point_defense_order(&torpedoes), vec![0, 1]);
+        assert_eq!(point_defense_order(&[(2.0, 1.0 - 1.0 / 60.0), (2.0, 1.0)]), vec![1, 0]);
+        // one still inbound after all the fire it was estimated to need keeps its
+        // place by time to impact
+        assert_eq!(point_defense_order(&[(0.5, -0.2), (3.0, 1.0)]), vec![0, 1]);
+    }
+
+    #[test]
+    fn wingmen_agree_on_one_arrival_tick() {
+        let target = vec2(5_000.0, 0.0);
+        let mut near = StrikeCoordinator::new();
+        let mut far = StrikeCoordinator::new();
+        near.hear_peer(0);
+        far.hear_peer(0);
+
+        // both hold and offer until they've heard each other
+        assert_eq!(near.clearance(target, 120, 1, 0), StrikeClearance::Hold);
+        assert_eq!(far.clearance(target, 200, 2, 0), StrikeClearance::Hold);
+        far.hear_offer(target, 120, 1, 1);
+        near.hear_offer(target, 200, 2, 1);
+
+        // the shorter flight proposes an arrival the longer one can make right away
+        assert_eq!(far.clearance(target, 200, 2, 1), StrikeClearance::Hold);
+        assert_eq!(near.clearance(target, 120, 1, 1), StrikeClearance::Hold);
+        let proposal = near.message(1, 1);
+        assert_eq!(proposal, Some(RadioMessage::StrikeProposal { target, arrival_tick: 201, sender: 1 }));
+        far.hear_proposal(target, 201, 2);
+        assert_eq!(far.clearance(target, 199, 2, 2), StrikeClearance::Coordinated(201));
+        assert_eq!(near.clearance(target, 120, 1, 80), StrikeClearance::Hold);
+        assert_eq!(near.clearance(target, 120, 1, 81), StrikeClearance::Coordinated(201));
+    }
+
+    #[test]
+    fn target_claims_go_to_whoever_gets_there_first() {
+        let target = vec2(5_000.0, 0.0);
+        let near = claim_strength(3_000.0, 100.0, 60.0);
+        let far = claim_strength(6_000.0, 0.0, 60.0);
+        assert!(near > far && near <= 1.0 && far > 0.0);
+
+        let mut claims = TargetClaims::new();
+        claims.hear(target + vec2(50.0, 0.0), near, 7, 0);
+        assert!(claims.yields(target, far, 3));
+        assert!(!claims.yields(target, near + 0.01, 3));
+        // someone else's target isn't a reason to yield
+        assert!(!claims.yields(-target, far, 3));
+
+        // equal claims, only the lower id backs off
+        assert!(claims.yields(target, near, 3));
+        let mut theirs = TargetClaims::new();
+        theirs.hear(target, near, 3, 0);
+        assert!(!theirs.yields(target, near, 7));
+
+        // a repeat replaces the sender's old claim, silence drops it
+        claims.hear(-target, near, 7, 60);
+        assert!(!claims.yields(target, far, 3));
+        claims.expire(60 + CLAIM_EXPIRY_TICKS);
+        assert!(!claims.yields(-target, far, 3));
+    }
+
+    #[test]
+    fn two_fighters_split_two_foes() {
+        let foes = [(1, vec2(3_000.0, 0.0)), (2, vec2(0.0, 4_000.0))];
+        let fighter = |radio_id: u8| {
+            let mut fighter = Fighter::new(Fighter::default_config());
+            fighter.set_radio_id(radio_id);
+            let radar = fighter.radar_mut();
+            for (id, position) in foes {
+                let mut track = RadarTrack::new(id, position, Vec2::zero(), Class::Fighter, 50.0, 0);
+                track.class = TrackType::Foe;
+                track.plot_count = ENGAGE_MIN_PLOTS;
+                track.quality = 1.0;
+                radar.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+            }
+            radar.take_picture();
+            fighter
+        };
+        let (mut low, mut high) = (fighter(3), fighter(7));
+
+        // on their own both go for the nearer foe
+        low.select_target();
+        high.select_target();
+        assert_eq!(low.designated(), Some(1));
+        assert_eq!(high.designated(), Some(1));
+
+        // equal claims from the same spot, the lower id takes the other one
+        let (low_claim, high_claim) = (low.claim(), high.claim());
+        assert!(low_claim.is_some() && high_claim.is_some());
+        low.handle_radio(high_claim);
+        high.handle_radio(low_claim);
+        low.select_target();
+        high.select_target();
+        assert_eq!(low.designated(), Some(2));
+        assert_eq!(high.designated(), Some(1));
+    }
+
+    #[test]
+    fn point_defense_preempts_and_hands_the_target_back() {
+        let mut fighter = Fighter::new(Fighter::default_config());
+        let track = |id: u128, position: Vec2, velocity: Vec2, class: Class, kind: TrackType| {
+            let mut track = RadarTrack::new(id, position, velocity, class, 50.0, 0);
+            track.class = kind;
+            track.plot_count = ENGAGE_MIN_PLOTS;
+            track.quality = 1.0;
+            Rc::new(RefCell::new(track))
+        };
+        let foe = track(1, vec2(3_000.0, 0.0), Vec2::zero(), Class::Fighter, TrackType::Foe);
+        let inbound = || track(5, vec2(1_000.0, 500.0), vec2(-500.0, -250.0), Class::Missile, TrackType::Missile);
+        let outbound = || track(5, vec2(1_000.0, 500.0), vec2(500.0, 250.0), Class::Missile, TrackType::Missile);
+        let set_tracks = |fighter: &mut Fighter, tracks: Vec<Rc<RefCell<RadarTrack>>>| {
+            let radar = fighter.radar_mut();
+            radar.potential_targets = tracks.into_iter().map(|t| (t.borrow().id, t.clone())).collect();
+            radar.take_picture();
+        };
+
+        set_tracks(&mut fighter, vec![foe.clone()]);
+        fighter.select_target();
+        assert_eq!(fighter.designated(), Some(1));
+        assert_eq!(*fighter.get_state(), ShipState::Engaged);
+
+        // an inbound missile takes the gun and target selection leaves it alone
+        set_tracks(&mut fighter, vec![foe.clone(), inbound()]);
+        fighter.update_point_defense();
+        assert_eq!(fighter.designated(), Some(5));
+        assert_eq!(*fighter.get_state(), ShipState::PointDefense);
+        fighter.select_target();
+        assert_eq!(fighter.designated(), Some(5));
+        fighter.update_point_defense();
+        assert_eq!(fighter.designated(), Some(5));
+
+        // once it stops closing the old target and state come back
+        set_tracks(&mut fighter, vec![foe.clone(), outbound()]);
+        fighter.update_point_defense();
+        assert_eq!(fighter.designated(), Some(1));
+        assert_eq!(*fighter.get_state(), ShipState::Engaged);
+
+        // the old target dying in the meantime leaves a search
+        set_tracks(&mut fighter, vec![foe.clone(), inbound()]);
+        fighter.update_point_defense();
+        assert_eq!(fighter.designated(), Some(5));
+        set_tracks(&mut fighter, vec![]);
+        fighter.update_point_defense();
+        assert_eq!(fighter.designated(), None);
+        assert_eq!(*fighter.get_state(), ShipState::Searching);
+    }
+
+    #[test]
+    fn strike_falls_back_when_the_wingman_goes_quiet() {
+        let target = vec2(5_000.0, 0.0);
+        let mut alone = StrikeCoordinator::new();
+        assert_eq!(alone.clearance(target, 120, 1, 0), StrikeClearance::Independent);
+
+        alone.hear_peer(10);
+        assert_eq!(alone.clearance(target, 120, 1, 10), StrikeClearance::Hold);
+        assert_eq!(alone.clearance(target, 120, 1, 10 + STRIKE_TIMEOUT_TICKS), StrikeClearance::Hold);
+        assert_eq!(alone.clearance(target, 120, 1, 11 + STRIKE_TIMEOUT_TICKS), StrikeClearance::Independent);
+        // and isn't held for again on the next launch
+        assert_eq!(alone.clearance(target, 120, 1, 12 + STRIKE_TIMEOUT_TICKS), StrikeClearance::Independent);
+    }
+
+    #[test]
+    fn a_wingman_busy_elsewhere_only_costs_one_timeout() {
+        let (target, elsewhere) = (vec2(5_000.0, 0.0), vec2(-5_000.0, 0.0));
+        let mut strike = StrikeCoordinator::new();
+        let mut first_launch = None;
+        for now in 0..4 * STRIKE_TIMEOUT_TICKS {
+            // the wingman keeps offering on its own target the whole time
+            if now % 10 == 0 {
+                strike.hear_offer(elsewhere, 150, 2, now);
+            }
+            match strike.clearance(target, 120, 1, now) {
+                StrikeClearance::Independent => { first_launch.get_or_insert(now); },
+                StrikeClearance::Hold => assert!(first_launch.is_none(), "held again at {}", now),
+                StrikeClearance::Coordinated(_) => panic!("nothing to coordinate on"),
+            }
+        }
+        assert_eq!(first_launch, Some(STRIKE_TIMEOUT_TICKS + 1));
+
+        // once it offers on our target we coordinate again
+        strike.hear_offer(target, 150, 2, 4 * STRIKE_TIMEOUT_TICKS);
+        assert_eq!(strike.clearance(target, 120, 1, 4 * STRIKE_TIMEOUT_TICKS), StrikeClearance::Hold);
+    }
+
+    #[test]
+    fn flight_time_needs_a_feasible_intercept() {
+        let t = missile_flight_time(Vec2::zero(), Vec2::zero(), vec2(3_000.0, 0.0), Vec2::zero(), 300.0, 2_000.0).unwrap();
+        assert_close(t, 3_000.0 / achievable_average_speed(3_000.0, 0.0, 300.0, 2_000.0), 1e-9);
+        assert!(missile_flight_time(Vec2::zero(), Vec2::zero(), vec2(3_000.0, 0.0), vec2(0.0, 3_000.0), 300.0, 2_000.0).is_none());
+    }
+
+    #[test]
+    fn beam_changes_wait_hold_and_rate_limit() {
+        let mut beam = BeamController::new(RadarBeam::Wide, DEFAULT_BEAM_WIDTHS);
+        assert_close(beam.update(0), PI / 2.0, 1e-12);
+
+        // a request flickering every few ticks never moves the beam
+        for tick in 1..40 {
+            beam.request(if (tick / 5) % 2 == 0 { RadarBeam::Narrow } else { RadarBeam::Wide });
+            assert_close(beam.update(tick), PI / 2.0, 1e-12);
+        }
+
+        // a steady request narrows it, a step at a time rather than all at once
+        beam.request(RadarBeam::Narrow);
+        let mut widths = Vec::new();
+        for tick in 40..60 {
+            widths.push(beam.update(tick));
+        }
+        let first = widths.iter().position(|w| *w < PI / 2.0).unwrap();
+        assert!(first as u32 + 1 >= BEAM_HOLD_TICKS);
+        assert!(widths[first] > PI / 8.0);
+        assert_close(*widths.last().unwrap(), PI / 8.0, 1e-12);
+        let changed_at = 40 + first as u32;
+
+        // widening right back has to wait out the change interval, then is immediate
+        beam.request(RadarBeam::Wide);
+        for tick in 60..changed_at + BEAM_CHANGE_INTERVAL {
+            assert!(beam.update(tick) < PI / 2.0);
+        }
+        assert_eq!(beam.ticks_until_change_allowed(changed_at + BEAM_CHANGE_INTERVAL), 0);
+        assert_close(beam.update(changed_at + BEAM_CHANGE_INTERVAL), PI / 2.0, 1e-12);
+    }
+
+    #[test]
+    fn launch_signature_cues_once_per_launch() {
+        // a fighter's own thrust between close plots doesn't read as a launch
+        assert!(!launch_signature(Class::Fighter, &TrackType::Foe, 3.0, 2));
+        assert!(launch_signature(Class::Fighter, &TrackType::Foe, 15.0, 2));
+        assert!(!launch_signature(Class::Fighter, &TrackType::Foe, 15.0, LAUNCH_SIGNATURE_TICKS + 1));
+        assert!(!launch_signature(Class::Fighter, &TrackType::Tentative, 15.0, 2));
+        assert!(!launch_signature(Class::Frigate, &TrackType::Foe, 15.0, 2));
+
+        let mut track = RadarTrack::new(1, vec2(1_000.0, 0.0), vec2(-100.0, 0.0), Class::Fighter, 50.0, 0);
+        track.class = TrackType::Foe;
+        let mut cues = Vec::new();
+        for tick in 1..60 {
+            // kicked sideways at tick 10, then steady
+            let velocity = if tick < 10 { vec2(-100.0, 0.0) } else { vec2(-100.0, 15.0) };
+            track.push_plot(TimedScanResult { tick, scan: ScanResult { position: track.position, velocity, class: Class::Fighter } });
+            track.update_at(tick);
+            if std::mem::take(&mut track.launch_cue) {
+                cues.push(tick);
+            }
+        }
+        assert_eq!(cues, vec![10]);
+
+        // provisional until the radar sees it, and not engageable on the cue alone
+        assert!(!TrackSource::Inferred { launcher: 1 }.has_local());
+        let mut inferred = RadarTrack::new(2, vec2(1_000.0, 0.0), vec2(-300.0, 0.0), Class::Missile, INFERRED_GATE_RADIUS, 0);
+        inferred.source = TrackSource::Inferred { launcher: 1 };
+        inferred.class = TrackType::Missile;
+        inferred.update_at(1);
+        assert!(!inferred.engageable(ENGAGE_MIN_PLOTS) && inferred.quality < QUALITY_ENGAGE);
+        inferred.confirm_local(50.0);
+        assert_eq!(inferred.source, TrackSource::Local);
+        assert!(matches!(inferred.class, TrackType::Tentative));
+    }
+
+    #[test]
+    fn revisits_favor_close_crossers_without_starving_the_sweep() {
+        let close = desired_revisit_ticks(500.0, 300.0, 1.0, QUALITY_GUN);
+        let far = desired_revisit_ticks(20_000.0, 300.0, 1.0, QUALITY_ENGAGE);
+        assert!(close <= 3, "close {}", close);
+        assert!(far > 5 * close, "far {}", far);
+
+        // a slow far track is left alone while it's fresh, but once its quality
+        // slips to the floor the weapon is holding for it and it's due at once
+        let healthy = desired_revisit_ticks(3_000.0, 10.0, QUALITY_GUN + 0.01, QUALITY_GUN);
+        let degraded = desired_revisit_ticks(3_000.0, 10.0, QUALITY_GUN - 0.01, QUALITY_GUN);
+        assert!(degraded <= healthy, "degraded {} healthy {}", degraded, healthy);
+        assert_eq!(degraded, REVISIT_MIN_TICKS);
+        assert_eq!(desired_revisit_ticks(3_000.0, 10.0, QUALITY_GUN, QUALITY_GUN), REVISIT_MIN_TICKS);
+        assert!(desired_revisit_ticks(3_000.0, 10.0, 1.0, QUALITY_GUN) > REVISIT_MIN_TICKS);
+
+        // everything overdue all the time: the designated track still gets its rate
+        // and the sweep still gets its share
+        let mut scheduler = RevisitScheduler::new();
+        let candidates: Vec<RevisitCandidate> = (0..6u128)
+            .map(|id| RevisitCandidate { id, last_plot_tick: 0, desired_ticks: if id == 0 { 3 } else { REVISIT_MIN_TICKS } })
+            .collect();
+        let mut last_designated = 0;
+        for now in 1..600 {
+            let choice = scheduler.choose(now, &candidates, Some(0));
+            if choice == Revisit::Dwell(0) {
+                last_designated = now;
+            }
+            assert!(now - last_designated <= 3, "tick {}", now);
+        }
+        assert!(scheduler.search_share() >= REVISIT_MIN_SEARCH_SHARE - 0.01, "share {}", scheduler.search_share());
+
+        // nothing due, the beam keeps sweeping
+        let fresh = [RevisitCandidate { id: 1, last_plot_tick: 599, desired_ticks: 10 }];
+        assert_eq!(scheduler.choose(600, &fresh, None), Revisit::Search);
+    }
+
+    #[test]
+    fn sweep_leans_toward_contacts_but_still_goes_round() {
+        // nothing seen, the plain rotation
+        let mut sectors = SweepSectors::new(0.0);
+        let picks: Vec<usize> = (0..SWEEP_SECTORS).map(|_| sectors.choose()).collect();
+        assert_eq!(picks, (1..=SWEEP_SECTORS).map(|s| s % SWEEP_SECTORS).collect::<Vec<_>>());
+
+        // contacts off one bearing: that sector comes up more often, every other one
+        // still within SWEEP_MAX_AGE picks
+        let hot = SweepSectors::sector_of(PI / 2.0 + 0.01);
+        for _ in 0..10 {
+            sectors.note_contact(PI / 2.0 + 0.01);
+        }
+        assert_close(sectors.weight(hot), SWEEP_SECTOR_MAX_WEIGHT, 1e-12);
+        let mut counts = [0; SWEEP_SECTORS];
+        let mut last = [0usize; SWEEP_SECTORS];
+        for pick in 1..=10 * SWEEP_SECTORS {
+            let sector = sectors.choose();
+            counts[sector] += 1;
+            last[sector] = pick;
+            for (s, seen) in last.iter().enumerate() {
+                assert!(pick - seen <= SWEEP_MAX_AGE as usize + SWEEP_SECTORS, "sector {} starved at {}", s, pick);
+            }
+        }
+        assert!(counts[hot] > 2 * counts[(hot + SWEEP_SECTORS / 2) % SWEEP_SECTORS], "{:?}", counts);
+        assert!(counts.iter().all(|c| *c > 0), "{:?}", counts);
+
+        // no fading while contacts keep coming, then back to uniform exactly once
+        assert!(!sectors.decay(0));
+        let mut faded_at = None;
+        for tick in 1..2_000 {
+            if sectors.decay(tick) {
+                assert!(faded_at.is_none());
+                faded_at = Some(tick);
+            }
+        }
+        assert!(faded_at.is_some() && sectors.decayed());
+    }
+
+    #[test]
+    fn designated_dwells_scale_with_range_and_leave_the_sweep_running() {
+        assert_eq!(designated_dwell_ticks(500.0), REVISIT_MIN_TICKS);
+        assert_eq!(designated_dwell_ticks(10_000.0), 4);
+        assert_eq!(designated_dwell_ticks(100_000.0), DESIGNATED_MAX_DWELL_TICKS);
+
+        // a close target plotted on every dwell, nothing else on the scope: it's
+        // looked at every other tick and the rest goes to the background sweep
+        let mut scheduler = RevisitScheduler::new();
+        let mut last_plot = 0;
+        let mut searches = 0;
+        for now in 1..=600 {
+            let designated = [RevisitCandidate { id: 1, last_plot_tick: last_plot, desired_ticks: designated_dwell_ticks(1_500.0) }];
+            match scheduler.choose(now, &designated, Some(1)) {
+                Revisit::Dwell(_) => last_plot = now,
+                Revisit::Search => searches += 1,
+            }
+            assert!(now - last_plot <= REVISIT_MIN_TICKS, "tick {}", now);
+        }
+        assert!(searches >= 290, "searches {}", searches);
+
+        // no designation, no dwell
+        let mut scheduler = RevisitScheduler::new();
+        let fresh = [RevisitCandidate { id: 1, last_plot_tick: 0, desired_ticks: 30 }];
+        assert_eq!(scheduler.choose(1, &fresh, None), Revisit::Search);
+    }
+
+    #[test]
+    fn frigate_missiles_start_where_the_main_gun_ends() {
+        let config = Frigate::default_config();
+        let gun = config.envelope(Weapon::Gun, Class::Fighter).unwrap();
+        let missile = config.envelope(Weapon::Missile, Class::Fighter).unwrap();
+        assert_close(missile.min_range, gun.max_range, 1e-9);
+        assert!(missile.check(gun.max_range + 1_000.0).is_ok() && gun.check(gun.max_range + 1_000.0).is_err());
+        // ordnance is the flak's, not worth a missile
+        assert!(config.envelope(Weapon::Missile, Class::Missile).unwrap().check(5_000.0).is_err());
+    }
+
+    #[test]
+    fn turrets_split_tracks_and_share_a_lone_one() {
+        let gun = |index| GunConfig { index, projectile_speed: BULLET_SPEED, hit_radius: 30.0, offset: Vec2::zero(), spread: 0.0, turret: true };
+        let port = TurretMount { gun: gun(0), arc_center: PI / 2.0, arc_half_width: 3.0 * PI / 4.0 };
+        let starboard = TurretMount { gun: gun(1), arc_center: -PI / 2.0, arc_half_width: 3.0 * PI / 4.0 };
+        let track = |id, position| RadarTrack::new(id, position, Vec2::zero(), Class::Fighter, 50.0, 0).snapshot();
+
+        // ahead is in both arcs: the nearer goes to the first turret, the other to the second
+        let tracks = [track(1, vec2(2_000.0, 0.0)), track(2, vec2(1_000.0, 100.0))];
+        assert_eq!(assign_turrets(&[port, starboard], 0.0, Vec2::zero(), &tracks), vec![Some(1), Some(0)]);
+
+        // one each side, each turret keeps to its own
+        let tracks = [track(1, vec2(0.0, 1_500.0)), track(2, vec2(0.0, -3_000.0))];
+        assert_eq!(assign_turrets(&[port, starboard], 0.0, Vec2::zero(), &tracks), vec![Some(0), Some(1)]);
+
+        // a lone track gets every turret that reaches it
+        let tracks = [track(1, vec2(1_000.0, 0.0))];
+        assert_eq!(assign_turrets(&[port, starboard], 0.0, Vec2::zero(), &tracks), vec![Some(0), Some(0)]);
+
+        // out of an arc is left alone, and the arcs turn with the hull
+        let tracks = [track(1, vec2(0.0, -1_000.0))];
+        assert_eq!(assign_turrets(&[port], 0.0, Vec2::zero(), &tracks), vec![None]);
+        assert_eq!(assign_turrets(&[port], PI, Vec2::zero(), &tracks), vec![Some(0)]);
+    }
+
+    #[test]
+    fn cruiser_flak_takes_the_turrets_only_for_inbound_ordnance() {
+        let config = Cruiser::default_config();
+        let mut flak = FlakController::new(config.guns.iter().filter(|g| g.turret).copied().collect());
+        let mut radar = Radar::new(config.radar, "test_radar");
+        let add = |radar: &mut Radar, id: u128, position: Vec2, velocity: Vec2, class: Class, track_type: TrackType| {
+            let mut track = RadarTrack::new(id, position, velocity, class, 50.0, 0);
+            track.class = track_type;
+            track.plot_count = ENGAGE_MIN_PLOTS;
+            radar.potential_targets.insert(id, Rc::new(RefCell::new(track)));
+            radar.take_picture();
+        };
+
+        // a ship alone leaves the turrets to assign_turrets
+        add(&mut radar, 1, vec2(2_000.0, 0.0), Vec2::zero(), Class::Fighter, TrackType::Foe);
+        assert_eq!(flak.fire_control(&radar), None);
+
+        // a four missile salvo: flak owns the turrets, and the one about to hit gets the last ditch shot
+        for (id, position) in [(2, vec2(200.0, 0.0)), (3, vec2(3_000.0, 500.0)), (4, vec2(3_000.0, -500.0)), (5, vec2(4_000.0, 0.0))] {
+            add(&mut radar, id, position, -position.normalize() * 800.0, Class::Missile, TrackType::Missile);
+        }
+        assert_eq!(flak.fire_control(&radar), Some(vec![0]));
+
+        // salvo gone, the turrets go back to the ship
+        radar.potential_targets.retain(|id, _| *id == 1);
+        radar.take_picture();
+        assert_eq!(flak.fire_control(&radar), None);
+    }
+
+    #[test]
+    fn delayed_plots_stay_in_a_fast_movers_gate() {
+        let velocity = vec2(1_200.0, 0.0);
+        let truth = |tick: u32| velocity * (tick as f64 / 60.0);
+        let mut track = RadarTrack::new(1, truth(0), velocity, Class::Missile, 50.0, 0);
+        let lag = 2;
+        for tick in lag + 1..60 {
+            // tracks coast to now before the scan, the plot is from lag ticks back
+            track.update_at(tick);
+            let seen = tick - lag;
+            let plot = truth(seen);
+            // against the estimate as of now the plot falls outside and would split off
+            assert!(!track.gate.point_in_gate(plot), "tick {}", tick);
+            assert!(track.check_gate(plot, seen), "tick {}", tick);
+            track.push_plot(TimedScanResult { tick: seen, scan: ScanResult { position: plot, velocity, class: Class::Missile } });
+            track.update_at(tick);
+        }
+        // and the estimate isn't dragged back toward the stale plots
+        assert_close((track.position - truth(59)).length(), 0.0, 1.0);
+    }
+
+    #[test]
+    fn accelerating_tracks_gate_and_extrapolate_second_order() {
+        let (v0, a) = (vec2(600.0, 0.0), vec2(300.0, 0.0));
+        let truth = |tick: u32| {
+            let t = tick as f64 / 60.0;
+            (v0 * t + 0.5 * a * t * t, v0 + a * t)
+        };
+        let mut track = RadarTrack::new(1, truth(0).0, v0, Class::Missile, 50.0, 0);
+        let lag = 4;
+        for tick in lag + 1..120 {
+            track.update_at(tick);
+            let seen = tick - lag;
+            let (plot, velocity) = truth(seen);
+            assert!(track.check_gate(plot, seen), "tick {}", tick);
+            track.push_plot(TimedScanResult { tick: seen, scan: ScanResult { position: plot, velocity, class: Class::Missile } });
+            track.update_at(tick);
+        }
+        assert_close(track.acceleration.x, a.x, 5.0);
+        // lagged plots moved up to now, velocity too, or the estimate trails by a·lag
+        let (position, velocity) = truth(119);
+        assert_close((track.position - position).length(), 0.0, 1.0);
+        assert_close((track.velocity - velocity).length(), 0.0, 1.0);
+        assert_close((track.plot_at_estimate(truth(119 - lag).0, 119 - lag) - position).length(), 0.0, 0.1);
+        assert_close((track.extrapolated(149) - truth(149).0).length(), 0.0, 1.0);
+    }
+
+    #[test]
+    fn energy_is_shed_unless_the_target_would_escape() {
+        let band = (50.0, 300.0);
+        let pursuit = vec2(0.0, 100.0);
+
+        // slow enough, pursuit passes through untouched
+        let (desired, decision) = manage_energy(vec2(800.0, 0.0), vec2(-200.0, 0.0), pursuit, band, 100.0, 1_500.0);
+        assert_eq!(decision, EnergyDecision::InBand);
+        assert_eq!(desired, pursuit);
+
+        // slingshotting through at 800 m/s relative: brake along the relative velocity
+        // first, pursuit gets whatever's left
+        let (desired, decision) = manage_energy(vec2(800.0, 0.0), vec2(-800.0, 0.0), pursuit, band, 100.0, 1_500.0);
+        assert_eq!(decision, EnergyDecision::Shedding);
+        assert_close(desired.x, -100.0, 1e-9);
+        assert_close(desired.y, 0.0, 1e-9);
+
+        // running away near the edge of the envelope, braking would lose it
+        let (desired, decision) = manage_energy(vec2(1_400.0, 0.0), vec2(400.0, 0.0), pursuit, band, 100.0, 1_500.0);
+        assert_eq!(decision, EnergyDecision::HoldingSpeed);
+        assert_eq!(desired, pursuit);
+    }
+
+    #[test]
+    fn avoidance_steers_off_collision_courses_only() {
+        let wingman = Obstacle::Friend(3);
+
+        // head on, passing 50 m to our left: pushed right, across the closing velocity
+        let obstacles = [(wingman, vec2(600.0, 50.0), vec2(-300.0, 0.0))];
+        let a = collision_avoidance(Vec2::zero(), Vec2::zero(), &obstacles, 100.0).unwrap();
+        assert_eq!(a.obstacle, wingman);
+        assert_close(a.miss, 50.0, 1e-9);
+        assert_close(a.acceleration.x, 0.0, 1e-9);
+        assert_close(a.acceleration.y, -100.0 * (1.0 - 50.0 / AVOID_RADIUS), 1e-9);
+
+        // same pass but too far out in time, or wide of the radius
+        assert!(collision_avoidance(Vec2::zero(), Vec2::zero(), &[(wingman, vec2(5_000.0, 50.0), vec2(-300.0, 0.0))], 100.0).is_none());
+        assert!(collision_avoidance(Vec2::zero(), Vec2::zero(), &[(wingman, vec2(600.0, 500.0), vec2(-300.0, 0.0))], 100.0).is_none());
+
+        // a missile sitting alongside pushes straight away, the closer call names the threat
+        let obstacles = [(wingman, vec2(600.0, 100.0), vec2(-300.0, 0.0)), (Obstacle::Missile(1), vec2(0.0, 20.0), Vec2::zero())];
+        let a = collision_avoidance(Vec2::zero(), Vec2::zero(), &obstacles, 100.0).unwrap();
+        assert_eq!(a.obstacle, Obstacle::Missile(1));
+        assert!(a.acceleration.y < 0.0);
+    }
+
+    #[test]
+    fn turning_targets_propagate_along_the_arc() {
+        // a quarter turn at 100 m/s on a 100 m radius circle
+        let p = propagate_target(Vec2::zero(), vec2(100.0, 0.0), Vec2::zero(), 1.0, PI / 2.0);
+        assert_close(p.x, 100.0, 1e-9);
+        assert_close(p.y, 100.0, 1e-9);
+        let straight = propagate_target(Vec2::zero(), vec2(100.0, 0.0), vec2(0.0, 10.0), 0.0, 2.0);
+        assert_close(straight.x, 200.0, 1e-9);
+        assert_close(straight.y, 20.0, 1e-9);
+    }
+
+    #[test]
+    fn slow_rounds_hold_on_agile_targets() {
+        let config = Frigate::default_config();
+        let main_gun = config.gun(0).unwrap();
+        let speed = config.projectile_speed(0);
+        assert!(speed < BULLET_SPEED);
+
+        let shooter = Shooter { position: Vec2::zero(), velocity: Vec2::zero(), acceleration: Vec2::zero(), heading: 0.0 };
+        let track = |velocity: Vec2, class: Class| RadarTrack::new(1, vec2(1_540.0, 0.0), velocity, class, 50.0, 0).snapshot();
+
+        // a fighter 1.5 km out has too much room to dodge a round seconds in flight
+        let fighter = intercept_for_gun(&main_gun, &track(vec2(0.0, 200.0), Class::Fighter), &shooter).unwrap();
+        assert!(fighter.flight_time > 2.0);
+        assert!(fighter.dodge > main_gun.hit_radius);
+        // the round and the target get to the lead together
+        assert_close(fighter.lead.length() / speed, fighter.flight_time, 1.0 / 60.0);
+
+        // a cruiser at the same range can't get out of the way
+        let cruiser = intercept_for_gun(&main_gun, &track(vec2(0.0, 50.0), Class::Cruiser), &shooter).unwrap();
+        assert!(cruiser.dodge < main_gun.hit_radius);
+
+        assert!(intercept_for_gun(&main_gun, &track(vec2(2.0 * speed, 0.0), Class::Fighter), &shooter).is_none());
+    }
+
+    #[test]
+    fn each_gun_solves_from_its_own_mount() {
+        let config = Frigate::default_config();
+        let shooter = Shooter { position: Vec2::zero(), velocity: Vec2::zero(), acceleration: Vec2::zero(), heading: 0.0 };
+        let track = RadarTrack::new(1, vec2(1_000.0, 0.0), Vec2::zero(), Class::Missile, 50.0, 0).snapshot();
+
+        // a stationary target dead ahead: the bow gun is 40 m closer than the turrets
+        // and its slower rounds still take longer
+        let bow = intercept_for_gun(&config.gun(0).unwrap(), &track, &shooter).unwrap();
+        assert_close(bow.flight_time, 960.0 / FRIGATE_MAIN_GUN_SPEED, 1e-9);
+        assert_close(bow.aim, 0.0, 1e-12);
+        assert_close(bow.miss, 0.0, 1e-9);
+
+        // the port turret aims across from 15 m to the left, no miss once slewed
+        let port = intercept_for_gun(&config.gun(1).unwrap(), &track, &shooter).unwrap();
+        assert!(port.aim < 0.0);
+        assert_close(port.lead.length() / BULLET_SPEED, port.flight_time, 1e-9);
+        assert_close(port.miss, 0.0, 1e-9);
+
+        // a gun along the nose pointed elsewhere misses by the barrel's error
+        let off_nose = Shooter { heading: 0.1, ..shooter };
+        let bow = intercept_for_gun(&config.gun(0).unwrap(), &track, &off_nose).unwrap();
+        assert!(bow.miss > 50.0);
+
+        // and pointed away it misses by the whole range, not the reversed line's zero
+        let reversed = Shooter { heading: PI, ..shooter };
+        let bow = intercept_for_gun(&config.gun(0).unwrap(), &track, &reversed).unwrap();
+        assert_close(bow.miss, bow.lead.length(), 1e-9);
+
+        assert!(intercept_for_gun(&GunConfig { projectile_speed: 0.0, ..config.gun(0).unwrap() }, &track, &shooter).is_none());
+    }
+
+    #[test]
+    fn openings_follow_the_spawn_picture() {
+        let half = 10_000.0;
+        assert_eq!(choose_opening(Class::Fighter, vec2(500.0, -200.0), half, false, false), Opening::AggressiveRush);
+        assert_eq!(choose_opening(Class::Fighter, vec2(-8_000.0, 0.0), half, false, false), Opening::CautiousApproach);
+        assert_eq!(choose_opening(Class::Fighter, vec2(-8_000.0, 0.0), half, true, false), Opening::StandardSearch);
+        assert_eq!(choose_opening(Class::Fighter, vec2(-8_000.0, 0.0), half, true, true), Opening::DefensiveEscort);
+        assert_eq!(choose_opening(Class::Frigate, Vec2::zero(), half, false, false), Opening::StandardSearch);
+
+        let config = Fighter::default_config();
+        let rush = OpeningProfile::for_opening(Opening::AggressiveRush, &config);
+        let cautious = OpeningProfile::for_opening(Opening::CautiousApproach, &config);
+        assert!(rush.search_speed_scale > cautious.search_speed_scale);
+        assert!(rush.combat_speed.1 > config.combat_speed_max && cautious.combat_speed.1 < config.combat_speed_max);
+        assert!(matches!(OpeningProfile::for_opening(Opening::DefensiveEscort, &config).state, ShipState::Escorting));
+    }
+
+    #[test]
+    fn missile_radar_profile_stays_inside_its_fuel_range() {
+        let missile = RadarProfile::for_class(Class::Missile);
+        let fighter = RadarProfile::for_class(Class::Fighter);
+        assert!(missile.ranges.long <= MISSILE_RADAR_RANGE);
+        assert_eq!(missile.escalate_after_ticks, 0);
+        assert!(missile.track_expiry_ticks < fighter.track_expiry_ticks);
+
+        let radar = Radar::new(missile, "test_radar");
+        assert_close(radar.profile().ranges.medium, MISSILE_RADAR_RANGE, 1e-12);
+    }
+
+    #[test]
+    fn radio_scheduler_sends_by_priority_and_drops_stale() {
+        let mut radio = Radio::new(CHANNEL_SANCHEZ);
+        radio.set_interval(RadioKind::MissileHeartbeat, MISSILE_HEARTBEAT_INTERVAL);
+        let report = |x: f64| RadioMessage::ContactReport { position: vec2(x, 0.0), velocity: Vec2::zero(), class: Class::Fighter, age: 0, sender: 1 };
+        let heartbeat = RadioMessage::MissileHeartbeat { position: Vec2::zero(), target: Vec2::zero(), salvo: 0 };
+
+        // high beats normal beats low, two reports queue side by side
+        radio.enqueue(report(1.0), RadioPriority::Low, 5);
+        radio.enqueue(report(2.0), RadioPriority::Low, 5);
+        radio.enqueue(RadioMessage::TerminalOn { position: Vec2::zero() }, RadioPriority::High, 0);
+        radio.enqueue(RadioMessage::CapitalBeacon { position: Vec2::zero(), velocity: Vec2::zero() }, RadioPriority::Normal, 1);
+        assert_eq!(radio.schedule(0).0.map(|m| m.kind()), Some(RadioKind::TerminalOn));
+        assert_eq!(radio.schedule(1).0.map(|m| m.kind()), Some(RadioKind::CapitalBeacon));
+
+        // oldest of equal priority first and aged by its wait, the second report
+        // goes stale unsent
+        let aged = RadioMessage::ContactReport { position: vec2(1.0, 0.0), velocity: Vec2::zero(), class: Class::Fighter, age: 2, sender: 1 };
+        assert_eq!(radio.schedule(2).0, Some(aged));
+        let (sent, traffic) = radio.schedule(6);
+        assert_eq!(sent, None);
+        assert_eq!(traffic.dropped, vec![RadioKind::ContactReport]);
+
+        // low priority periodic traffic waits out its interval, a newer copy replaces the queued one
+        radio.enqueue(heartbeat, RadioPriority::Low, 100);
+        assert_eq!(radio.schedule(10).0, Some(heartbeat));
+        radio.enqueue(heartbeat, RadioPriority::Low, 100);
+        radio.enqueue(heartbeat, RadioPriority::Low, 100);
+        assert_eq!(radio.schedule(11).0, None);
+        assert_eq!(radio.schedule(10 + MISSILE_HEARTBEAT_INTERVAL).0, Some(heartbeat));
+        assert_eq!(radio.schedule(11 + MISSILE_HEARTBEAT_INTERVAL).0, None);
+    }
+
+    // counts the control calls a tick makes instead of sending them
+    #[derive(Default)]
+    struct RecordingControls {
+        accelerations: Vec<Vec2>,
+        rotations: u32,
+        fired: Vec<usize>,
+    }
+
+    impl ControlSink for RecordingControls {
+        fn accelerate(&mut self, acceleration: Vec2) {
+            self.accelerations.push(acceleration);
+        }
+
+        fn torque(&mut self, _torque: f64) {
+            self.rotations += 1;
+        }
+
+        fn turn(&mut self, _speed: f64) {
+            self.rotations += 1;
+        }
+
+        fn fire(&mut self, index: usize) {
+            self.fired.push(index);
+        }
+    }
+
+    #[test]
+    fn control_intents_flush_one_command_per_tick() {
+        let mut intents = ControlIntents::default();
+        intents.accelerate(vec2(1.0, 0.0));
+        intents.torque(1.0);
+        intents.accelerate(vec2(0.0, 2.0));
+        intents.turn_to(1.0);
+        intents.fire(0);
+        intents.fire(0);
+        let mut controls = RecordingControls::default();
+        intents.flush(&mut controls);
+        assert_eq!(controls.accelerations, vec![vec2(0.0, 2.0)]);
+        assert_eq!(controls.rotations, 1);
+        assert_eq!(controls.fired, vec![0]);
+
+        // nothing asked for still zeroes the thrust
+        let mut controls = RecordingControls::default();
+        intents.flush(&mut controls);
+        assert_eq!(controls.accelerations, vec![Vec2::zero()]);
+        assert_eq!(controls.rotations, 0);
+
+        let mut fighter = Fighter::new(Fighter::default_config());
+        for _ in 0..3 {
+            let mut controls = RecordingControls::default();
+            fighter.tick_with(&mut controls);
+            assert_eq!(controls.accelerations.len(), 1);
+            assert!(controls.rotations <= 1);
+        }
+    }
+
+    #[test]
+    fn control_intents_keep_the_higher_priority_request() {
+        let mut intents = ControlIntents::default();
+        intents.behavior(ControlPriority::Evade, "evasion");
+        intents.accelerate(vec2(0.0, 30.0));
+        intents.behavior(ControlPriority::Routine, "no_target");
+        intents.accelerate(vec2(42.0, 0.0));
+        intents.torque(1.0);
+        assert_eq!(intents.acceleration_by.map(|(_, name)| name), Some("evasion"));
+        assert_eq!(intents.rotation_by.map(|(_, name)| name), Some("no_target"));
+        // same priority, the later request wins as before
+        intents.behavior(ControlPriority::Evade, "retreat");
+        intents.accelerate(vec2(-30.0, 0.0));
+
+        let mut controls = RecordingControls::default();
+        intents.flush(&mut controls);
+        assert_eq!(controls.accelerations, vec![vec2(-30.0, 0.0)]);
+        assert_eq!(controls.rotations, 1);
+        assert!(intents.acceleration_by.is_none());
+    }
+
+    #[test]
+    fn a_missing_target_falls_back_to_searching() {
+        let radar = Radar::new(Fighter::default_config().radar, "test_radar");
+        assert!(radar.get_track(42).is_none());
+
+        let mut fighter = Fighter::new(Fighter::default_config());
+        fighter.set_state(ShipState::Engaged);
+        fighter.shoot();
+        fighter.launch_missile();
+        fighter.basic_maneuver_to_target();
+        assert_eq!(*fighter.get_state(), ShipState::Searching);
+    }
+
+    #[test]
+    fn own_missile_records_follow_heartbeats() {
+        let target = read_track(&Rc::new(RefCell::new(RadarTrack::new(4, vec2(5_000.0, 0.0), Vec2::zero(), Class::Fighter, 50.0, 0))));
+        let other = read_track(&Rc::new(RefCell::new(RadarTrack::new(5, vec2(-5_000.0, 0.0), Vec2::zero(), Class::Fighter, 50.0, 0))));
+        let mut missile = MissileStatus::launched(0, target, None);
+
+        // on the rails it's where the boost puts it, not yet anywhere near the target
+        assert!(missile.estimate(0).is_some());
+        assert!(!missile.terminal_on(&target, 0));
+
+        missile.hear_heartbeat(100, vec2(4_000.0, 0.0));
+        assert_eq!(missile.estimate(100), Some((vec2(4_000.0, 0.0), Vec2::zero())));
+        assert!(missile.terminal_on(&target, 100));
+        assert!(!missile.terminal_on(&other, 100));
+
+        // gone quiet, nobody to hold fire for or steer around
+        let later = 101 + 2 * MISSILE_HEARTBEAT_INTERVAL;
+        assert!(missile.estimate(later).is_none());
+        assert!(!missile.terminal_on(&target, later));
+    }
+
+    #[test]
+    fn salvos_split_across_targets() {
+        let track = |id: u128, x: f64| read_track(&Rc::new(RefCell::new(RadarTrack::new(id, vec2(x, 0.0), Vec2::zero(), Class::Fighter, 50.0, 0))));
+        let tracks = [track(1, 2_000.0), track(2, 3_000.0)];
+        let pick = |in_flight: &[u128]| salvo_assignment(&tracks, in_flight, None).map(|t| t.id);
+        assert_eq!(pick(&[]), Some(1));
+        assert_eq!(pick(&[1]), Some(2));
+        assert_eq!(pick(&[1, 2]), Some(1));
+        // a missile at something no longer in the picture doesn't count
+        assert_eq!(pick(&[1, 9]), Some(2));
+        assert_eq!(salvo_assignment(&[], &[1], None).map(|t| t.id), None);
+
+        // a tie goes to the designated target over the nearer one
+        let designated = |in_flight: &[u128]| salvo_assignment(&tracks, in_flight, Some(2)).map(|t| t.id);
+        assert_eq!(designated(&[]), Some(2));
+        assert_eq!(designated(&[1, 2]), Some(2));
+        assert_eq!(designated(&[2]), Some(1));
+    }
+
+    #[test]
+    fn read_track_releases_its_borrow() {
+        let track = Rc::new(RefCell::new(RadarTrack::new(7, vec2(100.0, 0.0), vec2(0.0, 50.0), Class::Missile, 50.0, 0)));
+        let snapshot = read_track(&track);
+        assert!(track.try_borrow_mut().is_ok());
+        assert_eq!(snapshot.id, 7);
+        assert_close(snapshot.distance_from(Vec2::zero()), 100.0, 1e-9);
+        assert_close(snapshot.closing_speed_from(Vec2::zero(), vec2(10.0, 0.0)), 10.0, 1e-9);
+    }
+
+    #[test]
+    fn picture_follows_reports_and_keeps_dropped_copies() {
+        let mut radar = Radar::new(Fighter::default_config().radar, "test_radar");
+        let report = |position: Vec2| Detection::Remote { position, velocity: Vec2::zero(), class: Class::Fighter, age: 0, sender: 9 };
+
+        // a report after sense shows up without waiting for the next one
+        radar.add_detection_point(report(vec2(5_000.0, 0.0)));
+        assert_eq!(radar.picture().tracks.len(), 1);
+        let held = *radar.picture().iter().next().unwrap();
+        assert!(radar.potential_targets.values().all(|t| t.try_borrow_mut().is_ok()));
+
+        radar.add_detection_point(report(vec2(5_040.0, 0.0)));
+        let refreshed = radar.picture().refresh(held);
+        assert_eq!(refreshed.id, held.id);
+        assert!(refreshed.position.x > held.position.x);
+
+        // once the track is gone the last copy is all a consumer has
+        radar.expire_tracks(u32::MAX);
+        radar.add_detection_point(report(vec2(-5_000.0, 0.0)));
+        assert!(radar.picture().get(held.id).is_none());
+        assert_eq!(radar.picture().refresh(refreshed).position, refreshed.position);
+
+        // an inferred kill after sense shows up straight away too
+        let id = radar.picture().iter().next().unwrap().id;
+        assert!(!radar.picture().get(id).unwrap().probably_dead);
+        radar.mark_probably_dead(id);
+        assert!(radar.picture().get(id).unwrap().probably_dead);
+    }
+
+    #[test]
+    fn coasted_track_waits_as_a_ghost_and_keeps_its_id() {
+        let config = Fighter::default_config();
+        let expiry = config.radar.track_expiry_ticks;
+        let mut radar = Radar::new(config.radar, "test_radar");
+        let report = |position: Vec2| Detection::Remote { position, velocity: Vec2::zero(), class: Class::Fighter, age: 0, sender: 9 };
+
+        radar.add_detection_point(report(vec2(5_000.0, 0.0)));
+        let id = radar.picture().iter().next().unwrap().id;
+        radar.coast(id, LOST_CONTACT_TICKS);
+
+        // expired but coasted, out of the picture until something lands in its gate
+        radar.expire_tracks(expiry);
+        assert_eq!(radar.ghost_count(), 1);
+        radar.add_detection_point(report(vec2(-5_000.0, 0.0)));
+        assert!(radar.picture().get(id).is_none());
+
+        radar.add_detection_point(report(vec2(5_010.0, 0.0)));
+        assert_eq!(radar.ghost_count(), 0);
+        assert!(radar.picture().get(id).is_some());
+
+        // an uncoasted track is deleted outright, a ghost only lasts its coast
+        radar.coast(id, LOST_CONTACT_TICKS);
+        radar.expire_tracks(expiry);
+        assert_eq!(radar.ghost_count(), 1);
+        radar.expire_tracks(2 * expiry + LOST_CONTACT_TICKS);
+        assert_eq!(radar.ghost_count(), 0);
+        assert!(radar.potential_targets.is_empty());
+    }
+
+    #[test]
+    fn search_starts_at_center_then_mows() {
+        let half = 12_000.0;
+        assert_eq!(search_waypoint(0, half), Vec2::zero());
+        assert_eq!(search_waypoint(1, half), vec2(half, -half));
+        assert_eq!(search_waypoint(2, half), vec2(-half, -half));
+        assert_eq!(search_waypoint(3, half), vec2(half, -half + SEARCH_LEG_SPACING));
+
+        // rows never leave the box and the pattern wraps back to the first row
+        let rows = ((2.0 * half) / SEARCH_LEG_SPACING).floor() as u32;
+        for leg in 1..4 * (rows + 1) {
+            let w = search_waypoint(leg, half);
+            assert!(w.x.abs() <= half && w.y.abs() <= half, "leg {} at {}", leg, w);
+        }
+        assert_eq!(search_waypoint(1 + 2 * (rows + 1), half), search_waypoint(1, half));
+    }
+
+    #[test]
+    fn kill_needs_silence_at_impact_and_collapsed_quality() {
+        let window = Some((100, 130));
+        assert!(kill_inferred(120, 150, window, 0.2));
+        // no ordnance on the way, quiet is just quiet
+        assert!(!kill_inferred(120, 150, None, 0.0));
+        // one missed plot isn't a kill
+        assert!(!kill_inferred(120, 125, window, 0.2));
+        // quality hasn't fallen away yet
+        assert!(!kill_inferred(120, 150, window, 0.8));
+        // went quiet long before anything could have arrived
+        assert!(!kill_inferred(40, 150, window, 0.0));
+        // still plotting well after the last round passed
+        assert!(!kill_inferred(160, 180, window, 0.0));
+    }
+
+    #[test]
+    fn weapons_manager_bursts_the_gun_and_spaces_launches() {
+        let mut weapons = WeaponsManager::new();
+        let mut controls = ControlIntents::default();
+        assert_eq!(weapons.fire_gun(3, 0, &mut controls), Err(WeaponHold::Reloading));
+        for tick in 0..GUN_BURST_ROUNDS {
+            assert_eq!(weapons.fire_gun(0, tick, &mut controls), Ok(()));
+        }
+        assert_eq!(weapons.gun.rounds_this_burst, 0);
+        assert_eq!(weapons.fire_gun(0, GUN_BURST_ROUNDS, &mut controls), Err(WeaponHold::BurstPause));
+        assert_eq!(weapons.gun.last_hold, Some(WeaponHold::BurstPause));
+        assert_eq!(weapons.fire_gun(0, GUN_BURST_ROUNDS + GUN_BURST_PAUSE_TICKS, &mut controls), Ok(()));
+
+        let mut track = RadarTrack::new(1, vec2(5_000.0, 0.0), Vec2::zero(), Class::Fighter, 50.0, 0);
+        assert_eq!(weapons.clear_to_launch(0, 0, &track.snapshot(), true, false), Err(WeaponHold::NotFoe));
+        track.class = TrackType::Foe;
+        let foe = track.snapshot();
+        assert_eq!(weapons.clear_to_launch(0, 0, &foe, false, false), Err(WeaponHold::Infeasible));
+        assert_eq!(weapons.clear_to_launch(0, 0, &foe, true, true), Err(WeaponHold::TerminalOnTrack));
+        assert_eq!(weapons.clear_to_launch(0, 0, &foe, true, false), Ok(()));
+        weapons.launch(0, &mut controls);
+        assert_eq!(weapons.clear_to_launch(0, MISSILE_LAUNCH_INTERVAL - 1, &foe, true, false), Err(WeaponHold::LaunchInterval));
+        assert_eq!(weapons.clear_to_launch(0, MISSILE_LAUNCH_INTERVAL, &foe, true, false), Ok(()));
+    }
 }